@@ -0,0 +1,625 @@
+//! Computer controlled players: every bot connects to the server like a real client, through the same protocol
+//! and network event loop, then joins a room, gets ready and plays every game until the server disconnects it.
+//!
+//! During a game, a bot digs every tile it stands on, walks to the chests in sight
+//! and otherwise explores the map by walking toward the closest unexplored tile.
+
+use crate::screen::Screen;
+use protocol::{
+    Message,
+    MESSAGE_DATA_LENGTH,
+    MESSAGE_ACTION_SEND_USERNAME,
+    MESSAGE_ACTION_SET_READY,
+    MESSAGE_ACTION_REQUEST_ROOMS,
+    MESSAGE_ACTION_CREATE_ROOM,
+    MESSAGE_ACTION_JOIN_ROOM,
+    MESSAGE_ACTION_DIG,
+    MESSAGE_ACTION_MOVE,
+    MESSAGE_ACTION_RETURN_TO_LOBBY,
+    MESSAGE_ACTION_CHOOSE_SKIN,
+};
+use crate::shared_resources::SharedResources;
+use crate::network::{
+    MessageSender,
+    ConnectionStatus,
+    NetworkOptions,
+    message_channel,
+    start_network_event_loop,
+};
+use crate::rooms::ROOM_CODE_LENGTH;
+use crate::skins::SKINS_AMOUNT;
+use crate::game_state::{
+    GameState,
+    PLAYERS_AMOUNT,
+};
+use crate::visibility::TileVisibility;
+use crate::pathfinding::{
+    find_path,
+    get_reachable_tiles,
+    get_distance,
+    MOVEMENT_RANGE,
+};
+
+use std::io;
+use std::net::SocketAddr;
+use std::thread::{
+    sleep,
+    spawn,
+    JoinHandle,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
+const TILES_AMOUNT: usize = 400;
+const CHEST_TILE: u8 = 9;
+
+/// Skill level of the bots.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum BotDifficulty {
+
+    /* slow and short moves, only opens the chests it walks on */
+    Easy,
+
+    Normal,
+
+    /* quick and full moves */
+    Hard,
+}
+
+impl BotDifficulty {
+
+    /// Returns the difficulty with the given name (easy, normal or hard), if any.
+    ///
+    /// # Args:
+    ///
+    /// `name` - the name of the difficulty
+    pub fn from_name(name: &str) -> Option<BotDifficulty> {
+        match name {
+            "easy" => Some(BotDifficulty::Easy),
+            "normal" => Some(BotDifficulty::Normal),
+            "hard" => Some(BotDifficulty::Hard),
+            _ => None,
+        }
+    }
+
+    /// Returns the time the bot waits between two actions.
+    fn get_thinking_delay(&self) -> Duration {
+        match self {
+            BotDifficulty::Easy => Duration::from_millis(2000),
+            BotDifficulty::Normal => Duration::from_millis(1000),
+            BotDifficulty::Hard => Duration::from_millis(400),
+        }
+    }
+
+    /// Returns the maximum amount of steps of one move of the bot.
+    fn get_movement_range(&self) -> usize {
+        match self {
+            BotDifficulty::Easy => 2,
+            BotDifficulty::Normal => 4,
+            BotDifficulty::Hard => MOVEMENT_RANGE,
+        }
+    }
+
+    /// Indicates if the bot walks to the chests it sees.
+    fn goes_to_chests(&self) -> bool {
+        *self != BotDifficulty::Easy
+    }
+}
+
+pub struct Bot {
+    sender: MessageSender,
+    shared_resources: SharedResources,
+    username: String,
+    skin: u8,
+    difficulty: BotDifficulty,
+
+    /* true if the bot creates a room when there is none to join, false if it only joins the existing rooms */
+    hosts_room: bool,
+
+    rooms_request_previous_time: Option<Instant>,
+    action_previous_time: Instant,
+    ready: bool,
+
+    /* the tile the bot asked to dig last, not dug again while the server answers */
+    dug_position: Option<usize>,
+}
+
+impl Bot {
+
+    /// Connects a bot to the server, waiting for the connection to succeed or fail; the bot plays once run.
+    ///
+    /// # Args:
+    ///
+    /// `address` - the address of the server
+    /// `username` - the username of the bot
+    /// `skin` - the index of the skin of the bot
+    /// `difficulty` - the skill level of the bot
+    /// `hosts_room` - true if the bot creates a room when there is none to join
+    pub fn connect(
+        address: SocketAddr,
+        username: &str,
+        skin: u8,
+        difficulty: BotDifficulty,
+        hosts_room: bool,
+    ) -> io::Result<Bot> {
+
+        let (sender, receiver) = message_channel();
+        let shared_resources = SharedResources::new(Screen::UsernamePrompt);
+
+        let status = start_network_event_loop(
+            address,
+            receiver,
+            shared_resources.clone(),
+            None,
+            NetworkOptions::default(),
+            None,
+        );
+
+        /* the event loop gives up on its own once the connection timeout is reached */
+        const STATUS_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+        loop {
+            match *status.lock().unwrap() {
+                ConnectionStatus::Connecting => {},
+                ConnectionStatus::Failed | ConnectionStatus::Lost => {
+                    return Err(io::Error::new(io::ErrorKind::NotConnected, "the connection failed"));
+                },
+                ConnectionStatus::Connected | ConnectionStatus::Reconnecting => break,
+            };
+
+            sleep(STATUS_CHECK_INTERVAL);
+        }
+
+        Ok(Bot {
+            sender,
+            shared_resources,
+            username: username.to_string(),
+            skin,
+            difficulty,
+            hosts_room,
+            rooms_request_previous_time: None,
+            action_previous_time: Instant::now(),
+            ready: false,
+            dug_position: None,
+        })
+    }
+
+    /// Plays until the server disconnects the bot.
+    pub fn run(mut self) {
+
+        const TICK_DURATION: Duration = Duration::from_millis(50);
+
+        loop {
+
+            /* the event loop goes back to the connect screen once the server is disconnected for good */
+            let current_screen = *self.shared_resources.current_screen.lock().unwrap();
+
+            match current_screen {
+                Screen::Connect => return,
+                Screen::UsernamePrompt => self.introduce(),
+                Screen::RoomBrowser => self.browse_rooms(),
+                Screen::Lobby => self.get_ready(),
+                Screen::Game => self.play(),
+                Screen::GameOver => self.play_again(),
+                Screen::SkinSelection => {},
+            };
+
+            sleep(TICK_DURATION);
+        }
+    }
+
+    /// Sends the username and the skin of the bot, then goes to the rooms.
+    fn introduce(&mut self) {
+
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[..self.username.len()].copy_from_slice(self.username.as_bytes());
+        self.send(MESSAGE_ACTION_SEND_USERNAME, bytes);
+
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[0] = self.skin;
+        self.send(MESSAGE_ACTION_CHOOSE_SKIN, bytes);
+
+        self.set_screen(Screen::RoomBrowser);
+    }
+
+    /// Regularly requests the rooms list and joins the first room waiting for players;
+    /// a hosting bot creates a room if there is none.
+    fn browse_rooms(&mut self) {
+
+        const ROOMS_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+        let request_rooms = match self.rooms_request_previous_time {
+            Some(time) => time.elapsed() >= ROOMS_REQUEST_INTERVAL,
+            None => true,
+        };
+
+        /* the rooms list is only read once the server answered the request */
+        if request_rooms {
+            self.send(MESSAGE_ACTION_REQUEST_ROOMS, [0; MESSAGE_DATA_LENGTH]);
+            self.rooms_request_previous_time = Some(Instant::now());
+            return;
+        }
+
+        let code = self.shared_resources.rooms
+            .lock()
+            .unwrap()
+            .get_rooms()
+            .iter()
+            .find(|room| !room.is_playing() && room.get_players() < room.get_max_players())
+            .map(|room| room.get_code());
+
+        if let Some(code) = code {
+            let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+            bytes[..ROOM_CODE_LENGTH].copy_from_slice(&code);
+            self.send(MESSAGE_ACTION_JOIN_ROOM, bytes);
+        }
+        else if self.hosts_room {
+
+            /* the game model only handles a fixed amount of players, the rooms are always created for all of them */
+            let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+            bytes[0] = PLAYERS_AMOUNT as u8;
+            self.send(MESSAGE_ACTION_CREATE_ROOM, bytes);
+        }
+        else {
+            return;
+        }
+
+        /* the server sends the bot back to the rooms if the room cannot be joined */
+        self.rooms_request_previous_time = None;
+        self.ready = false;
+        self.set_screen(Screen::Lobby);
+    }
+
+    /// Tells the server the bot is ready, once per lobby.
+    fn get_ready(&mut self) {
+
+        if self.ready {
+            return;
+        }
+
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[0] = true as u8;
+        self.send(MESSAGE_ACTION_SET_READY, bytes);
+
+        self.ready = true;
+    }
+
+    /// Goes back to the lobby of the room once the game is over, to play the next game.
+    fn play_again(&mut self) {
+
+        self.send(MESSAGE_ACTION_RETURN_TO_LOBBY, [0; MESSAGE_DATA_LENGTH]);
+
+        self.ready = false;
+        self.dug_position = None;
+        self.set_screen(Screen::Lobby);
+    }
+
+    /// Acts once the thinking delay is over, whenever the bot can act.
+    fn play(&mut self) {
+
+        if self.action_previous_time.elapsed() < self.difficulty.get_thinking_delay() {
+            return;
+        }
+
+        let tiles = *self.shared_resources.tiles.lock().unwrap();
+
+        let game_state_mutex = self.shared_resources.game_state.clone();
+        let action = {
+            let mut game_state_mutex_guard = game_state_mutex.lock().unwrap();
+            let game_state = &mut *game_state_mutex_guard;
+
+            let local_player = match game_state.get_local_player() {
+                Some(local_player) if game_state.can_local_player_act() => local_player,
+                _ => return,
+            };
+
+            let position = game_state.get_character_positions()[local_player];
+            game_state.update_visibility(position);
+
+            self.choose_action(
+                &tiles,
+                game_state,
+                position,
+            )
+        };
+
+        if let Some((action, bytes)) = action {
+            self.send(action, bytes);
+            self.action_previous_time = Instant::now();
+        }
+    }
+
+    /// Returns the next action of the bot (the message action and data): digs the tile it stands on if not dug yet,
+    /// otherwise moves toward its destination. None if there is nothing left to do.
+    ///
+    /// # Args:
+    ///
+    /// `tiles` - the tiles of the map
+    /// `game_state` - the game state
+    /// `position` - the index of the tile of the bot character
+    fn choose_action(
+        &mut self,
+        tiles: &[u8; TILES_AMOUNT],
+        game_state: &GameState,
+        position: usize,
+    ) -> Option<(u8, [u8; MESSAGE_DATA_LENGTH])> {
+
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+
+        if !game_state.get_dug_tiles()[position] && self.dug_position != Some(position) {
+            self.dug_position = Some(position);
+
+            bytes[..2].copy_from_slice(&(position as u16).to_be_bytes());
+            return Some((MESSAGE_ACTION_DIG, bytes));
+        }
+
+        let destination = choose_destination(
+            tiles,
+            game_state,
+            position,
+            self.difficulty.goes_to_chests(),
+        )?;
+
+        /* the bot goes as far as it can along the path at every move */
+        let path = find_path(tiles, position, destination)?;
+        let step = path[(path.len() - 1).min(self.difficulty.get_movement_range())];
+
+        bytes[..2].copy_from_slice(&(step as u16).to_be_bytes());
+        Some((MESSAGE_ACTION_MOVE, bytes))
+    }
+
+    /// Sends one message to the server.
+    ///
+    /// # Args:
+    ///
+    /// `action` - the action of the message
+    /// `bytes` - the data of the message
+    fn send(
+        &self,
+        action: u8,
+        bytes: [u8; MESSAGE_DATA_LENGTH],
+    ) {
+        let mut message = Message::new(action);
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+    }
+
+    /// Changes the current screen of the bot; the bot has no display, the screen is its current step.
+    ///
+    /// # Args:
+    ///
+    /// `screen` - the new screen
+    fn set_screen(
+        &self,
+        screen: Screen,
+    ) {
+        *self.shared_resources.current_screen.lock().unwrap() = screen;
+    }
+}
+
+/// Connects the given amount of bots to the server, each one connecting and playing into its own thread,
+/// so the caller is never blocked by an unreachable server. The bots which cannot connect are reported and stop.
+///
+/// # Args:
+///
+/// `address` - the address of the server
+/// `count` - the amount of bots
+/// `difficulty` - the skill level of the bots
+/// `host_room` - true if the first bot creates a room for all the bots when there is none to join
+pub fn start_bots(
+    address: SocketAddr,
+    count: usize,
+    difficulty: BotDifficulty,
+    host_room: bool,
+) -> Vec<JoinHandle<()>> {
+
+    (0..count)
+        .map(|index| {
+
+            let username = format!("Bot {}", index + 1);
+
+            let hosts_room = host_room && index == 0;
+
+            spawn(move || {
+                match Bot::connect(
+                    address,
+                    &username,
+                    (index % SKINS_AMOUNT) as u8,
+                    difficulty,
+                    hosts_room,
+                ) {
+                    Ok(bot) => bot.run(),
+                    Err(error) => println!("{} cannot connect to the server: {}", username, error),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Returns the tile the bot walks to: the closest chest in sight not opened yet (if the bot goes to the chests),
+/// otherwise the closest unexplored tile, otherwise the closest tile not dug yet. Only the reachable tiles are chosen.
+///
+/// # Args:
+///
+/// `tiles` - the tiles of the map
+/// `game_state` - the game state
+/// `position` - the index of the tile of the bot character
+/// `goes_to_chests` - true if the bot walks to the chests it sees
+fn choose_destination(
+    tiles: &[u8; TILES_AMOUNT],
+    game_state: &GameState,
+    position: usize,
+    goes_to_chests: bool,
+) -> Option<usize> {
+
+    let reachable_tiles = get_reachable_tiles(
+        tiles,
+        position,
+        TILES_AMOUNT,
+    );
+
+    let visibility = game_state.get_visibility();
+    let dug_tiles = game_state.get_dug_tiles();
+
+    let get_closest_tile = |is_destination: &dyn Fn(usize) -> bool| {
+        (0..TILES_AMOUNT)
+            .filter(|index| reachable_tiles[*index] && *index != position && is_destination(*index))
+            .min_by_key(|index| get_distance(position, *index))
+    };
+
+    let closest_chest = get_closest_tile(&|index| {
+        goes_to_chests &&
+            tiles[index] == CHEST_TILE &&
+            !dug_tiles[index] &&
+            visibility.get_tile_visibility(index) == TileVisibility::Visible
+    });
+
+    closest_chest
+        .or_else(|| get_closest_tile(&|index| visibility.get_tile_visibility(index) == TileVisibility::Unexplored))
+        .or_else(|| get_closest_tile(&|index| !dug_tiles[index]))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::mock_server::{
+        MockServer,
+        MockServerStep,
+        ReceivedMessage,
+    };
+
+    use std::net::TcpListener;
+
+    /// Returns a map where the ten first lines are sand, the other ones are water.
+    fn get_half_island() -> [u8; TILES_AMOUNT] {
+        let mut tiles = [10; TILES_AMOUNT];
+        tiles[..200].copy_from_slice(&[0; 200]);
+        tiles
+    }
+
+    /// Waits until the server receives a message with the given action and returns it; fails after five seconds.
+    ///
+    /// # Args:
+    ///
+    /// `server` - the server the bot is connected to
+    /// `action` - the expected action
+    #[track_caller]
+    fn wait_for_message(
+        server: &MockServer,
+        action: u8,
+    ) -> ReceivedMessage {
+
+        let start = Instant::now();
+
+        loop {
+
+            if let Some(message) = server
+                .get_received_messages()
+                .into_iter()
+                .find(|message| message.action == action) {
+                return message;
+            }
+
+            assert!(start.elapsed() < Duration::from_secs(5), "message not received in time");
+            sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_bot_joins_a_room_and_plays() {
+
+        let server = MockServer::start(vec![
+            MockServerStep::Expect(MESSAGE_ACTION_SEND_USERNAME),
+            MockServerStep::Expect(MESSAGE_ACTION_CHOOSE_SKIN),
+            MockServerStep::Expect(MESSAGE_ACTION_REQUEST_ROOMS),
+            MockServerStep::rooms_list(&[("Treasure hunt", "4321", 1, 2)]),
+            MockServerStep::Expect(MESSAGE_ACTION_JOIN_ROOM),
+            MockServerStep::Expect(MESSAGE_ACTION_SET_READY),
+            MockServerStep::push_map(&get_half_island()),
+            MockServerStep::start_game(1, ["ANNE", "Bot 1"], [0, 1]),
+            MockServerStep::move_character(1, 21),
+            MockServerStep::Expect(MESSAGE_ACTION_DIG),
+            MockServerStep::Expect(MESSAGE_ACTION_MOVE),
+        ]);
+
+        let bot = Bot::connect(
+            server.get_address(),
+            "Bot 1",
+            1,
+            BotDifficulty::Hard,
+            false,
+        ).unwrap();
+        spawn(move || bot.run());
+
+        let join = wait_for_message(&server, MESSAGE_ACTION_JOIN_ROOM);
+        assert_eq!(&join.data[..ROOM_CODE_LENGTH], b"4321");
+
+        let dig = wait_for_message(&server, MESSAGE_ACTION_DIG);
+        assert_eq!(&dig.data[..2], &21u16.to_be_bytes());
+
+        /* the bot walks as far as it can, toward the unexplored part of the island */
+        let step = wait_for_message(&server, MESSAGE_ACTION_MOVE);
+        let step = u16::from_be_bytes([step.data[0], step.data[1]]) as usize;
+        assert_eq!(get_distance(21, step), MOVEMENT_RANGE);
+        assert!(step < 200);
+    }
+
+    #[test]
+    fn test_hosting_bot_creates_a_room() {
+
+        let server = MockServer::start(vec![
+            MockServerStep::Expect(MESSAGE_ACTION_CREATE_ROOM),
+            MockServerStep::Expect(MESSAGE_ACTION_SET_READY),
+        ]);
+
+        let bot = Bot::connect(
+            server.get_address(),
+            "Bot 1",
+            0,
+            BotDifficulty::Normal,
+            true,
+        ).unwrap();
+        spawn(move || bot.run());
+
+        let create_room = wait_for_message(&server, MESSAGE_ACTION_CREATE_ROOM);
+        assert_eq!(create_room.data[0], PLAYERS_AMOUNT as u8);
+    }
+
+    #[test]
+    fn test_bots_connect_into_their_own_threads() {
+
+        /* nothing listens on the port of a closed listener */
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+        let bots = start_bots(
+            address,
+            2,
+            BotDifficulty::Normal,
+            true,
+        );
+
+        /* the bots which cannot connect stop without blocking the caller */
+        assert_eq!(bots.len(), 2);
+
+        for bot in bots {
+            bot.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_bot_goes_to_the_chests_in_sight() {
+
+        let mut tiles = get_half_island();
+        tiles[25] = CHEST_TILE;
+
+        let mut game_state = GameState::new();
+        game_state.update_visibility(21);
+
+        assert_eq!(choose_destination(&tiles, &game_state, 21, true), Some(25));
+
+        /* without going to the chests, the bot explores */
+        let destination = choose_destination(&tiles, &game_state, 21, false).unwrap();
+        assert!(game_state.get_visibility().get_tile_visibility(destination) == TileVisibility::Unexplored);
+    }
+}
@@ -0,0 +1,60 @@
+//! Structure and implementation of one graphical character.
+
+/// Displayable character structure.
+pub struct Character {
+    skin: usize,
+    position: usize,
+}
+
+impl Character {
+
+    /// Character constructor.
+    ///
+    /// # Args:
+    ///
+    /// `skin` - the index of the character skin
+    /// `position` - the character default position
+    pub fn new(
+        skin: usize,
+        position: usize,
+    ) -> Self {
+        Character {
+            skin,
+            position,
+        }
+    }
+
+    /// Returns the index of the character skin.
+    pub fn get_skin(&self) -> usize {
+        self.skin
+    }
+
+    /// Updates the character skin.
+    ///
+    /// # Args:
+    ///
+    /// `skin` - the index of the new skin of the character
+    pub fn set_skin(
+        &mut self,
+        skin: usize,
+    ) {
+        self.skin = skin;
+    }
+
+    /// Returns the character position.
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+
+    /// Updates the character position.
+    ///
+    /// # Args:
+    ///
+    /// `position` - the index of the new tile of the character
+    pub fn set_position(
+        &mut self,
+        position: usize,
+    ) {
+        self.position = position;
+    }
+}
@@ -0,0 +1,168 @@
+//! Structure and implementation of the chat log, filled by the server messages.
+
+use protocol::get_string_from_bytes;
+
+/// One line of the chat, written by a player or by the server (system message).
+pub struct ChatMessage {
+    author: Option<String>,
+    text: String,
+}
+
+impl ChatMessage {
+
+    /// Returns the username of the player who wrote the message, none for system messages.
+    pub fn get_author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Returns the text of the message.
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+}
+
+/* the oldest messages are dropped past this amount, so the log does not grow during long sessions */
+const MAX_MESSAGES: usize = 100;
+
+/// The last chat messages received since the client started.
+pub struct ChatLog {
+    messages: Vec<ChatMessage>,
+
+    /* incremented every time a message is added, so the displays know when to refresh */
+    version: usize,
+}
+
+impl ChatLog {
+
+    pub fn new() -> ChatLog {
+        ChatLog {
+            messages: Vec::new(),
+            version: 0,
+        }
+    }
+
+    /// Adds the message sent by the server to the log.
+    ///
+    /// The data contains the message kind (0 for a player message, 1 when a player joined, 2 when a player left),
+    /// the username of the concerned player on 32 bytes and the text of the message on 32 bytes (both padded with zeros).
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the chat message
+    pub fn add(
+        &mut self,
+        data: &[u8],
+    ) {
+
+        const USERNAME_OFFSET: usize = 1;
+        const TEXT_OFFSET: usize = USERNAME_OFFSET + 32;
+        const TEXT_END: usize = TEXT_OFFSET + 32;
+
+        let username = get_string_from_bytes(&data[USERNAME_OFFSET..TEXT_OFFSET]);
+
+        const PLAYER_MESSAGE: u8 = 0;
+        const PLAYER_JOINED_MESSAGE: u8 = 1;
+        const PLAYER_LEFT_MESSAGE: u8 = 2;
+
+        let message = match data[0] {
+            PLAYER_MESSAGE => ChatMessage {
+                author: Some(username),
+                text: get_string_from_bytes(&data[TEXT_OFFSET..TEXT_END]),
+            },
+            PLAYER_JOINED_MESSAGE => ChatMessage {
+                author: None,
+                text: format!("{} joined.", username),
+            },
+            PLAYER_LEFT_MESSAGE => ChatMessage {
+                author: None,
+                text: format!("{} left.", username),
+            },
+            _ => return,
+        };
+
+        self.messages.push(message);
+
+        if self.messages.len() > MAX_MESSAGES {
+            self.messages.remove(0);
+        }
+
+        self.version = self.version.wrapping_add(1);
+    }
+
+    /// Returns the kept messages, from the oldest to the newest.
+    pub fn get_messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// Returns a number that changes every time a message is added.
+    pub fn get_version(&self) -> usize {
+        self.version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Returns the data of a chat message sent by the server.
+    ///
+    /// # Args:
+    ///
+    /// `kind` - the message kind
+    /// `username` - the username of the concerned player
+    /// `text` - the text of the message
+    fn get_chat_data(
+        kind: u8,
+        username: &str,
+        text: &str,
+    ) -> [u8; 65] {
+        let mut data = [0; 65];
+        data[0] = kind;
+        data[1..1 + username.len()].copy_from_slice(username.as_bytes());
+        data[33..33 + text.len()].copy_from_slice(text.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_add_player_and_system_messages() {
+
+        let mut chat_log = ChatLog::new();
+        chat_log.add(&get_chat_data(1, "ANNE", ""));
+        chat_log.add(&get_chat_data(0, "ANNE", "Ahoy!"));
+        chat_log.add(&get_chat_data(2, "ANNE", ""));
+
+        let messages = chat_log.get_messages();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].get_author(), None);
+        assert_eq!(messages[0].get_text(), "ANNE joined.");
+        assert_eq!(messages[1].get_author(), Some("ANNE"));
+        assert_eq!(messages[1].get_text(), "Ahoy!");
+        assert_eq!(messages[2].get_text(), "ANNE left.");
+    }
+
+    #[test]
+    fn test_add_ignores_unknown_kind() {
+
+        let mut chat_log = ChatLog::new();
+        chat_log.add(&get_chat_data(3, "ANNE", "Ahoy!"));
+
+        assert!(chat_log.get_messages().is_empty());
+        assert_eq!(chat_log.get_version(), 0);
+    }
+
+    #[test]
+    fn test_add_drops_the_oldest_messages() {
+
+        let mut chat_log = ChatLog::new();
+
+        for index in 0..MAX_MESSAGES + 5 {
+            chat_log.add(&get_chat_data(0, "ANNE", &index.to_string()));
+        }
+
+        let messages = chat_log.get_messages();
+        assert_eq!(messages.len(), MAX_MESSAGES);
+        assert_eq!(messages[0].get_text(), "5");
+        assert_eq!(chat_log.get_version(), MAX_MESSAGES + 5);
+    }
+}
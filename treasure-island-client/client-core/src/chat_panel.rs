@@ -0,0 +1,424 @@
+//! Handles the chat panel displayed on the lobby and game screens.
+
+use crate::chat::ChatLog;
+use protocol::{
+    Message,
+    MESSAGE_ACTION_SEND_CHAT_MESSAGE,
+};
+use crate::network::MessageSender;
+use crate::renderer::Renderer;
+
+use piston_window::{
+    Transformed,
+    Event,
+    Key,
+    Button,
+    PressEvent,
+    TextEvent,
+};
+
+use std::sync::{
+    Mutex,
+    Arc,
+};
+use std::cell::RefCell;
+
+const CHAT_MESSAGE_MAX_LENGTH: usize = 32;
+
+const PANEL_HORIZONTAL_POSITION: f64 = 20.0;
+const PANEL_VERTICAL_POSITION: f64 = 640.0;
+const PANEL_WIDTH: f64 = 560.0;
+const PANEL_HEIGHT: f64 = 420.0;
+const PANEL_MARGIN: f64 = 15.0;
+
+const LINE_FONT_SIZE: u32 = 24;
+const LINE_HEIGHT: f64 = 30.0;
+
+/* the last line of the panel is kept for the text entry */
+const DISPLAYED_LINES: usize = 12;
+
+const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const SYSTEM_MESSAGE_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+
+/// The chat messages split into lines that fit into the panel, with their colors.
+/// Kept until the chat log or the lines width changes, as measuring the text is slow.
+struct WrappedLines {
+    lines: Vec<(String, [f32; 4])>,
+    chat_log_version: Option<usize>,
+    width: f64,
+}
+
+pub struct ChatPanel {
+    chat_log: Arc<Mutex<ChatLog>>,
+    sender: MessageSender,
+    text: String,
+    typing: bool,
+
+    /* amount of lines the history is scrolled up from its end */
+    scroll: usize,
+
+    /* refreshed while rendering, which does not modify the panel otherwise */
+    wrapped_lines: RefCell<WrappedLines>,
+}
+
+impl ChatPanel {
+
+    /// Constructor.
+    ///
+    /// # Args:
+    ///
+    /// `chat_log` - thread safe pointer to the chat log; shared with the thread that receives the chat messages from the server
+    /// `sender` - the sender to send the typed messages to the server
+    pub fn new(
+        chat_log: Arc<Mutex<ChatLog>>,
+        sender: MessageSender,
+    ) -> ChatPanel {
+        ChatPanel {
+            chat_log,
+            sender,
+            text: String::new(),
+            typing: false,
+            scroll: 0,
+            wrapped_lines: RefCell::new(WrappedLines {
+                lines: Vec::new(),
+                chat_log_version: None,
+                width: 0.0,
+            }),
+        }
+    }
+
+    /// Renders the panel, the newest messages at the bottom.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer to draw with
+    pub fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+    ) {
+
+        let transform = renderer.get_transform();
+
+        const PANEL_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.4];
+        renderer.draw_rectangle(
+            PANEL_COLOR,
+            [
+                PANEL_HORIZONTAL_POSITION,
+                PANEL_VERTICAL_POSITION,
+                PANEL_WIDTH,
+                PANEL_HEIGHT,
+            ],
+            transform,
+        );
+
+        let width = PANEL_WIDTH - 2.0 * PANEL_MARGIN;
+        self.update_wrapped_lines(renderer, width);
+
+        let wrapped_lines = self.wrapped_lines.borrow();
+        let lines = &wrapped_lines.lines;
+
+        /* the history may have been shortened since the panel has been scrolled */
+        let scroll = self.scroll.min(lines.len().saturating_sub(DISPLAYED_LINES));
+        let last_line = lines.len() - scroll;
+        let first_line = last_line.saturating_sub(DISPLAYED_LINES);
+
+        for (index, (line, color)) in lines[first_line..last_line].iter().enumerate() {
+            renderer.draw_text(
+                *color,
+                LINE_FONT_SIZE,
+                line,
+                transform.trans(
+                    PANEL_HORIZONTAL_POSITION + PANEL_MARGIN,
+                    PANEL_VERTICAL_POSITION + PANEL_MARGIN + ((index + 1) as f64) * LINE_HEIGHT,
+                ),
+            );
+        }
+
+        let entry = if self.typing {
+            format!("> {}_", self.text)
+        } else {
+            "[Tab] to chat".to_string()
+        };
+
+        const ENTRY_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+        renderer.draw_text(
+            ENTRY_COLOR,
+            LINE_FONT_SIZE,
+            &entry,
+            transform.trans(
+                PANEL_HORIZONTAL_POSITION + PANEL_MARGIN,
+                PANEL_VERTICAL_POSITION + PANEL_HEIGHT - PANEL_MARGIN,
+            ),
+        );
+    }
+
+    /// Handle the events of the panel: toggles the text entry, types and sends messages, scrolls the history.
+    /// Returns true if the event has been consumed by the panel, so the screen must not handle it.
+    ///
+    /// # Args:
+    ///
+    /// `event` - the event to handle
+    pub fn handle_events(
+        &mut self,
+        event: &Event,
+    ) -> bool {
+
+        match event.press_args() {
+            Some(Button::Keyboard(Key::Tab)) => {
+                self.typing = !self.typing;
+                return true;
+            },
+            Some(Button::Keyboard(Key::PageUp)) => {
+                let max_scroll = self.wrapped_lines.borrow().lines.len().saturating_sub(DISPLAYED_LINES);
+                self.scroll = (self.scroll + 1).min(max_scroll);
+                return true;
+            },
+            Some(Button::Keyboard(Key::PageDown)) => {
+                self.scroll = self.scroll.saturating_sub(1);
+                return true;
+            },
+            Some(Button::Keyboard(Key::Return)) if self.typing => {
+                self.send();
+                return true;
+            },
+            Some(Button::Keyboard(Key::Backspace)) if self.typing => {
+                self.text.pop();
+                return true;
+            },
+
+            /* every other key is typed through the text events */
+            Some(Button::Keyboard(_)) if self.typing => {
+                return true;
+            },
+            _ => {}
+        };
+
+        if !self.typing {
+            return false;
+        }
+
+        if let Some(text) = event.text_args() {
+
+            for character in text.chars().filter(|character| !character.is_control()) {
+                if self.text.len() + character.len_utf8() <= CHAT_MESSAGE_MAX_LENGTH {
+                    self.text.push(character);
+                }
+            }
+
+            return true;
+        }
+
+        /* the screen still receives the non-keyboard events (render, update...) */
+        false
+    }
+
+    /// Wraps the chat messages again if the chat log or the lines width changed since the last time.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer used to measure the text
+    /// `width` - the maximum width of a line
+    fn update_wrapped_lines(
+        &self,
+        renderer: &mut dyn Renderer,
+        width: f64,
+    ) {
+
+        let chat_log_mutex_guard = self.chat_log.lock().unwrap();
+        let chat_log = &*chat_log_mutex_guard;
+
+        let mut wrapped_lines = self.wrapped_lines.borrow_mut();
+
+        if wrapped_lines.chat_log_version == Some(chat_log.get_version()) &&
+            wrapped_lines.width == width {
+            return;
+        }
+
+        wrapped_lines.lines.clear();
+
+        for message in chat_log.get_messages() {
+
+            let (text, color) = match message.get_author() {
+                Some(author) => (format!("{}: {}", author, message.get_text()), WHITE_COLOR),
+                None => (message.get_text().to_string(), SYSTEM_MESSAGE_COLOR),
+            };
+
+            for line in wrap_text(renderer, &text, width) {
+                wrapped_lines.lines.push((line, color));
+            }
+        }
+
+        wrapped_lines.chat_log_version = Some(chat_log.get_version());
+        wrapped_lines.width = width;
+    }
+
+    /// Sends the typed message to the server and closes the text entry.
+    fn send(&mut self) {
+
+        self.typing = false;
+        self.scroll = 0;
+
+        if self.text.is_empty() {
+            return;
+        }
+
+        let mut message = Message::new(MESSAGE_ACTION_SEND_CHAT_MESSAGE);
+
+        const MESSAGE_DATA_LENGTH: usize = 32;
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[..self.text.len()].copy_from_slice(self.text.as_bytes());
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+
+        self.text.clear();
+    }
+}
+
+/// Splits the given text into lines that fit into the given width, words are never split
+/// unless a single word is larger than the width.
+///
+/// # Args:
+///
+/// `renderer` - the renderer used to measure the text
+/// `text` - the text to split
+/// `width` - the maximum width of a line
+fn wrap_text(
+    renderer: &mut dyn Renderer,
+    text: &str,
+    width: f64,
+) -> Vec<String> {
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+
+        if renderer.get_text_width(LINE_FONT_SIZE, &candidate) <= width {
+            line = candidate;
+            continue;
+        }
+
+        if !line.is_empty() {
+            lines.push(line);
+        }
+
+        /* the word alone does not fit, cut it on as many lines as required */
+        line = String::new();
+        for character in word.chars() {
+            line.push(character);
+            if renderer.get_text_width(LINE_FONT_SIZE, &line) > width {
+                line.pop();
+                lines.push(line);
+                line = character.to_string();
+            }
+        }
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::network::message_channel;
+    use crate::renderer::RecordingRenderer;
+
+    use piston_window::{
+        Input,
+        ButtonArgs,
+        ButtonState,
+    };
+
+    /// Returns a panel displaying the given amount of short player messages, rendered once.
+    ///
+    /// # Args:
+    ///
+    /// `messages_amount` - the amount of messages into the chat log
+    /// `renderer` - the renderer to draw with
+    fn get_rendered_panel(
+        messages_amount: usize,
+        renderer: &mut RecordingRenderer,
+    ) -> ChatPanel {
+
+        let mut chat_log = ChatLog::new();
+
+        for index in 0..messages_amount {
+            let mut data = [0; 65];
+            data[1..5].copy_from_slice(b"ANNE");
+            data[33..33 + index.to_string().len()].copy_from_slice(index.to_string().as_bytes());
+            chat_log.add(&data);
+        }
+
+        let (sender, _) = message_channel();
+        let panel = ChatPanel::new(
+            Arc::new(Mutex::new(chat_log)),
+            sender,
+        );
+
+        panel.render(renderer);
+        panel
+    }
+
+    /// Simulates a key press on the panel.
+    ///
+    /// # Args:
+    ///
+    /// `panel` - the panel receiving the event
+    /// `key` - the pressed key
+    fn press(
+        panel: &mut ChatPanel,
+        key: Key,
+    ) {
+        panel.handle_events(&Event::Input(
+            Input::Button(ButtonArgs {
+                state: ButtonState::Press,
+                button: Button::Keyboard(key),
+                scancode: None,
+            }),
+            None,
+        ));
+    }
+
+    #[test]
+    fn test_scroll_is_clamped_to_the_wrapped_lines() {
+
+        let mut renderer = RecordingRenderer::new([1920.0, 1080.0]);
+        let mut panel = get_rendered_panel(DISPLAYED_LINES + 3, &mut renderer);
+
+        for _ in 0..10 {
+            press(&mut panel, Key::PageUp);
+        }
+
+        assert_eq!(panel.scroll, 3);
+
+        press(&mut panel, Key::PageDown);
+        assert_eq!(panel.scroll, 2);
+
+        let mut renderer = RecordingRenderer::new([1920.0, 1080.0]);
+        panel.render(&mut renderer);
+        assert_eq!(renderer.get_texts()[0], "ANNE: 1");
+    }
+
+    #[test]
+    fn test_short_history_is_not_scrolled() {
+
+        let mut renderer = RecordingRenderer::new([1920.0, 1080.0]);
+        let mut panel = get_rendered_panel(2, &mut renderer);
+
+        press(&mut panel, Key::PageUp);
+
+        assert_eq!(panel.scroll, 0);
+        assert_eq!(renderer.get_texts()[0], "ANNE: 0");
+    }
+}
@@ -0,0 +1,884 @@
+//! Handles the whole client: the screens, the resources they share with the network
+//! and the routing of the events and of the rendering to the current screen.
+//!
+//! The client does not know about the window: the events come from the Piston window or are simulated
+//! (headless mode), the frames are drawn through any renderer.
+
+use crate::network::{
+    MessageReceiver,
+    ConnectionStatus,
+    NetworkOptions,
+    message_channel,
+    start_network_event_loop,
+};
+use crate::screen::Screen;
+use crate::renderer::Renderer;
+use crate::shared_resources::SharedResources;
+use crate::recording::{
+    Recorder,
+    Recording,
+    ReplaySpeed,
+    start_replay_server,
+};
+use crate::input::InputMapper;
+use crate::discovery::ServerDiscovery;
+use crate::bot::{
+    BotDifficulty,
+    start_bots,
+};
+
+use crate::connect_screen::ConnectScreen;
+use crate::username_prompt_screen::UsernamePromptScreen;
+use crate::skin_selection_screen::SkinSelectionScreen;
+use crate::room_browser_screen::RoomBrowserScreen;
+use crate::lobby_screen::LobbyScreen;
+use crate::game_screen::GameScreen;
+use crate::game_over_screen::{
+    GameOverScreen,
+    GameOverChoice,
+};
+
+use piston_window::{
+    Event,
+    Button,
+    Key,
+    PressEvent,
+    Transformed,
+};
+use piston_window::color::hex;
+
+use std::net::SocketAddr;
+use std::io;
+use std::sync::{
+    Mutex,
+    Arc,
+};
+use std::sync::mpsc::{
+    Sender,
+    channel,
+};
+
+pub struct Client {
+    discovery_address: SocketAddr,
+    shared_resources: SharedResources,
+
+    /* the network event loop only starts once a server is chosen on the connect screen */
+    receiver: Option<MessageReceiver>,
+    connection_status: Option<Arc<Mutex<ConnectionStatus>>>,
+
+    input_mapper: InputMapper,
+    quit_requested: bool,
+
+    /* records the sessions, if any */
+    recorder: Option<Arc<Mutex<Recorder>>>,
+
+    /* replays the next recorded message, only when replaying step by step */
+    replay_step_sender: Option<Sender<()>>,
+
+    /* the amount of bots connected to the chosen server alongside the client and their difficulty, if any */
+    bots: Option<(usize, BotDifficulty)>,
+
+    connect_screen: ConnectScreen,
+    username_prompt_screen: UsernamePromptScreen,
+    skin_selection_screen: SkinSelectionScreen,
+    room_browser_screen: RoomBrowserScreen,
+    lobby_screen: LobbyScreen,
+    game_screen: GameScreen,
+    game_over_screen: GameOverScreen,
+}
+
+impl Client {
+
+    /// Constructor. Loads the cross-threads resources and all the screens, starting on the connect screen.
+    ///
+    /// # Args:
+    ///
+    /// `discovery_address` - the address the servers discovery probes are sent to
+    pub fn new(discovery_address: SocketAddr) -> Client {
+
+        /* load cross-threads resources with their pointers and mutexes
+           (current screen, map, lobby, rooms, chat, game state, skins) */
+
+        let shared_resources = SharedResources::new(Screen::Connect);
+
+        let (
+            sender,
+            receiver,
+        ) = message_channel();
+
+        /* load all screens and clone their thread-safe shared resources (if any) */
+
+        let connect_screen = ConnectScreen::new(ServerDiscovery::new(discovery_address));
+
+        let username_prompt_screen = UsernamePromptScreen::new(sender.clone());
+
+        let skin_selection_screen = SkinSelectionScreen::new(
+            sender.clone(),
+            shared_resources.skins.clone(),
+        );
+
+        let room_browser_screen = RoomBrowserScreen::new(
+            sender.clone(),
+            shared_resources.rooms.clone(),
+        );
+
+        let lobby_screen = LobbyScreen::new(
+            sender.clone(),
+            shared_resources.lobby.clone(),
+            shared_resources.chat_log.clone(),
+            shared_resources.ping.clone(),
+        );
+
+        let game_screen = GameScreen::new(
+            shared_resources.tiles.clone(),
+            shared_resources.game_state.clone(),
+            shared_resources.ping.clone(),
+            sender.clone(),
+            shared_resources.chat_log.clone(),
+        );
+
+        let game_over_screen = GameOverScreen::new(
+            sender,
+            shared_resources.game_state.clone(),
+        );
+
+        Client {
+            discovery_address,
+            shared_resources,
+            receiver: Some(receiver),
+            connection_status: None,
+            input_mapper: InputMapper::new(),
+            quit_requested: false,
+            recorder: None,
+            replay_step_sender: None,
+            bots: None,
+            connect_screen,
+            username_prompt_screen,
+            skin_selection_screen,
+            room_browser_screen,
+            lobby_screen,
+            game_screen,
+            game_over_screen,
+        }
+    }
+
+    /// Handle one event: maps it to an action and forwards both to the current screen.
+    /// Resets the whole client if the server has been disconnected.
+    ///
+    /// # Args:
+    ///
+    /// `event` - the event to handle
+    pub fn handle_event(
+        &mut self,
+        event: &Event,
+    ) {
+
+        /* the network goes back to the connect screen once the server cannot be reached anymore;
+           everything received from the server is outdated, so the client starts again from scratch */
+        if self.receiver.is_none() && self.get_current_screen() == Screen::Connect {
+
+            let connection_failed = match &self.connection_status {
+                Some(status) => *status.lock().unwrap() == ConnectionStatus::Failed,
+                None => false,
+            };
+
+            let recorder = self.recorder.take();
+            let bots = self.bots;
+            *self = Client::new(self.discovery_address);
+            self.recorder = recorder;
+            self.bots = bots;
+
+            if connection_failed {
+                self.connect_screen.set_connection_failed();
+            } else {
+                self.connect_screen.set_connection_lost();
+            }
+        }
+
+        if let Some(replay_step_sender) = &self.replay_step_sender {
+            if let Some(Button::Keyboard(Key::N)) = event.press_args() {
+                replay_step_sender.send(()).unwrap();
+            }
+        }
+
+        let action = self.input_mapper.get_action(event);
+
+        /* the current screen is not locked while connecting, as connecting changes it */
+        if self.get_current_screen() == Screen::Connect {
+
+            self.connect_screen.handle_events(action);
+
+            if let Some(address) = self.connect_screen.take_chosen_server() {
+                self.connect(address);
+            }
+
+            return;
+        }
+
+        let mut current_screen_mutex_guard = self.shared_resources.current_screen.lock().unwrap();
+        let current_screen_guard = &mut *current_screen_mutex_guard;
+
+        match *current_screen_guard {
+            /* handled before locking the current screen */
+            Screen::Connect => {},
+            Screen::UsernamePrompt => {
+                self.username_prompt_screen.handle_events(
+                    event,
+                    action,
+                    current_screen_guard,
+                );
+            },
+            Screen::SkinSelection => {
+                self.skin_selection_screen.handle_events(
+                    action,
+                    current_screen_guard,
+                );
+            },
+            Screen::RoomBrowser => {
+                self.room_browser_screen.handle_events(
+                    event,
+                    action,
+                    current_screen_guard,
+                );
+
+                if *current_screen_guard == Screen::Lobby {
+                    self.lobby_screen.set_spectating(self.room_browser_screen.is_spectating());
+                }
+            },
+            Screen::Lobby => {
+                self.lobby_screen.handle_events(
+                    event,
+                    action,
+                    current_screen_guard,
+                );
+            },
+            Screen::Game => {
+                self.game_screen.handle_events(
+                    event,
+                    action,
+                );
+            },
+            Screen::GameOver => {
+                self.game_over_screen.handle_events(action);
+
+                /* the server sends the lobby of the room again, the one of the previous game is outdated */
+                match self.game_over_screen.take_choice() {
+                    Some(GameOverChoice::ReturnToLobby) => {
+                        self.shared_resources.lobby.lock().unwrap().clear();
+                        self.lobby_screen.set_ready(false);
+                        *current_screen_guard = Screen::Lobby;
+                    },
+                    Some(GameOverChoice::PlayAgain) => {
+                        self.shared_resources.lobby.lock().unwrap().clear();
+                        self.lobby_screen.set_ready(true);
+                        *current_screen_guard = Screen::Lobby;
+                    },
+                    Some(GameOverChoice::Quit) => self.quit_requested = true,
+                    None => {},
+                };
+            },
+        };
+    }
+
+    /// Connects to the given server and asks for the username, as if the server had been chosen on the connect screen;
+    /// used to skip the connect screen. Does nothing if the client is already connected.
+    ///
+    /// # Args:
+    ///
+    /// `address` - the address of the server
+    pub fn connect(
+        &mut self,
+        address: SocketAddr,
+    ) {
+
+        let receiver = match self.receiver.take() {
+            Some(receiver) => receiver,
+            None => return,
+        };
+
+        /* the connection does not block the window: the messages typed meanwhile are sent once connected,
+           and the event loop goes back to the connect screen if the connection fails */
+        self.connection_status = Some(start_network_event_loop(
+            address,
+            receiver,
+            self.shared_resources.clone(),
+            self.recorder.clone(),
+            NetworkOptions::default(),
+            None,
+        ));
+
+        /* the bots join the rooms waiting for players, the player creates the room */
+        if let Some((count, difficulty)) = self.bots {
+            start_bots(
+                address,
+                count,
+                difficulty,
+                false,
+            );
+        }
+
+        *self.shared_resources.current_screen.lock().unwrap() = Screen::UsernamePrompt;
+    }
+
+    /// Renders the current screen.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer to draw with
+    pub fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+    ) {
+
+        const BACKGROUND_COLOR: &str = "88FFFF"; /* light blue */
+        renderer.clear(hex(BACKGROUND_COLOR));
+
+        match self.get_current_screen() {
+            Screen::Connect => self.connect_screen.render(renderer),
+            Screen::UsernamePrompt => self.username_prompt_screen.render(renderer),
+            Screen::SkinSelection => self.skin_selection_screen.render(renderer),
+            Screen::RoomBrowser => self.room_browser_screen.render(renderer),
+            Screen::Lobby => self.lobby_screen.render(renderer),
+            Screen::Game => self.game_screen.render(renderer),
+            Screen::GameOver => self.game_over_screen.render(renderer),
+        };
+
+        /* the session goes on without the recording once a message cannot be written */
+        let recording_stopped = self.recorder
+            .as_ref()
+            .is_some_and(|recorder| recorder.lock().unwrap().is_stopped());
+
+        if recording_stopped {
+
+            let transform = renderer.get_transform();
+            let [_, window_height] = renderer.get_view_size();
+
+            const WARNING_COLOR: [f32; 4] = [0.9, 0.2, 0.2, 1.0];
+            const WARNING_FONT_SIZE: u32 = 24;
+            const WARNING_MARGIN: f64 = 20.0;
+            renderer.draw_text(
+                WARNING_COLOR,
+                WARNING_FONT_SIZE,
+                "Recording stopped",
+                transform.trans(
+                    WARNING_MARGIN,
+                    window_height - WARNING_MARGIN,
+                ),
+            );
+        }
+    }
+
+    /// Records the messages received from and sent to the server during the following sessions.
+    ///
+    /// # Args:
+    ///
+    /// `recorder` - the recorder to use
+    pub fn set_recorder(
+        &mut self,
+        recorder: Arc<Mutex<Recorder>>,
+    ) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Connects the given amount of bots to the server chosen on the connect screen, once the client is connected.
+    ///
+    /// # Args:
+    ///
+    /// `count` - the amount of bots
+    /// `difficulty` - the skill level of the bots
+    pub fn set_bots(
+        &mut self,
+        count: usize,
+        difficulty: BotDifficulty,
+    ) {
+        self.bots = Some((count, difficulty));
+    }
+
+    /// Replays the given recording instead of connecting to a server, from the lobby;
+    /// the messages sent by the client are dropped. Does nothing if the client is already connected.
+    ///
+    /// # Args:
+    ///
+    /// `recording` - the recording to replay
+    /// `speed` - the factor the replay is accelerated by (1.0 for the real speed),
+    ///           message by message (with the N key) if none
+    pub fn replay(
+        &mut self,
+        recording: &Recording,
+        speed: Option<f64>,
+    ) -> io::Result<()> {
+
+        let receiver = match self.receiver.take() {
+            Some(receiver) => receiver,
+            None => return Ok(()),
+        };
+
+        let speed = match speed {
+            Some(factor) => ReplaySpeed::Factor(factor),
+            None => {
+                let (replay_step_sender, replay_step_receiver) = channel();
+                self.replay_step_sender = Some(replay_step_sender);
+                ReplaySpeed::Step(replay_step_receiver)
+            },
+        };
+
+        let address = start_replay_server(
+            recording,
+            speed,
+        )?;
+
+        /* before starting the replay, as the replayed messages may change the screen */
+        *self.shared_resources.current_screen.lock().unwrap() = Screen::Lobby;
+
+        /* the replay server only accepts one connection */
+        self.connection_status = Some(start_network_event_loop(
+            address,
+            receiver,
+            self.shared_resources.clone(),
+            None,
+            NetworkOptions {
+                reconnection_attempts: 0,
+                ..NetworkOptions::default()
+            },
+            None,
+        ));
+
+        Ok(())
+    }
+
+    /// Indicates if the player asked to quit the game.
+    pub fn is_quit_requested(&self) -> bool {
+        self.quit_requested
+    }
+
+    /// Returns the current screen.
+    pub fn get_current_screen(&self) -> Screen {
+        *self.shared_resources.current_screen.lock().unwrap()
+    }
+
+    /// Indicates if the game is over and its result displayed.
+    pub fn is_game_over(&self) -> bool {
+        self.get_current_screen() == Screen::GameOver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use protocol::{
+        MESSAGE_ACTION_SEND_USERNAME,
+        MESSAGE_ACTION_CREATE_ROOM,
+        MESSAGE_ACTION_CHOOSE_SKIN,
+        MESSAGE_ACTION_SPECTATE_ROOM,
+        MESSAGE_ACTION_PING,
+    };
+    use crate::discovery::DiscoveryResponder;
+    use crate::renderer::RecordingRenderer;
+    use crate::mock_server::{
+        MockServer,
+        MockServerStep,
+    };
+    use crate::recording::Direction;
+    use crate::visibility::TileVisibility;
+
+    use piston_window::{
+        Input,
+        ButtonArgs,
+        ButtonState,
+        Button,
+        Key,
+        Loop,
+        RenderArgs,
+    };
+
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+    use std::path::Path;
+    use std::process;
+    use std::thread::{
+        sleep,
+        spawn,
+    };
+    use std::net::TcpListener;
+    use std::time::{
+        Duration,
+        Instant,
+    };
+
+    const WINDOW_SIZE: [f64; 2] = [1920.0, 1080.0];
+
+    /// Simulates a key press.
+    fn press(
+        client: &mut Client,
+        key: Key,
+    ) {
+        client.handle_event(&Event::Input(
+            Input::Button(ButtonArgs {
+                state: ButtonState::Press,
+                button: Button::Keyboard(key),
+                scancode: None,
+            }),
+            None,
+        ));
+    }
+
+    /// Simulates frames until the condition is true; fails after five seconds.
+    #[track_caller]
+    fn wait_until(
+        client: &mut Client,
+        renderer: &mut RecordingRenderer,
+        condition: impl Fn(&Client, &RecordingRenderer) -> bool,
+    ) {
+        let start = Instant::now();
+
+        loop {
+
+            client.handle_event(&Event::Loop(Loop::Render(RenderArgs {
+                ext_dt: 0.0,
+                window_size: WINDOW_SIZE,
+                draw_size: [1920, 1080],
+            })));
+            client.render(renderer);
+
+            if condition(client, renderer) {
+                return;
+            }
+
+            assert!(start.elapsed() < Duration::from_secs(5), "condition not met in time");
+            sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Picks the mock server on the connect screen (announced through the discovery),
+    /// then goes through the username prompt and the skin selection until the room browser.
+    /// The session is recorded by the given recorder, if any.
+    fn go_to_room_browser(
+        server: &MockServer,
+        renderer: &mut RecordingRenderer,
+        recorder: Option<Arc<Mutex<Recorder>>>,
+    ) -> Client {
+
+        let responder = DiscoveryResponder::new(
+            "127.0.0.1:0".parse().unwrap(),
+            "Mock server",
+            server.get_address().port(),
+            0,
+        );
+
+        let mut client = Client::new(responder.get_address());
+        if let Some(recorder) = recorder {
+            client.set_recorder(recorder);
+        }
+        spawn(move || responder.respond_once());
+
+        wait_until(&mut client, renderer, |_, renderer| {
+            renderer.get_texts().iter().any(|text| text.starts_with("Mock server"))
+        });
+
+        /* the local server comes first */
+        press(&mut client, Key::S);
+        press(&mut client, Key::Return);
+        assert_eq!(client.get_current_screen(), Screen::UsernamePrompt);
+
+        for key in [Key::J, Key::A, Key::C, Key::K] {
+            press(&mut client, key);
+        }
+        press(&mut client, Key::Return);
+        assert_eq!(client.get_current_screen(), Screen::SkinSelection);
+
+        press(&mut client, Key::Return);
+        assert_eq!(client.get_current_screen(), Screen::RoomBrowser);
+
+        client
+    }
+
+    /// Goes to the room browser, then creates a room and goes to its lobby.
+    /// The session is recorded by the given recorder, if any.
+    fn join_lobby(
+        server: &MockServer,
+        renderer: &mut RecordingRenderer,
+        recorder: Option<Arc<Mutex<Recorder>>>,
+    ) -> Client {
+
+        let mut client = go_to_room_browser(
+            server,
+            renderer,
+            recorder,
+        );
+
+        /* the create room item follows the refresh one */
+        press(&mut client, Key::S);
+        press(&mut client, Key::Return);
+        assert_eq!(client.get_current_screen(), Screen::Lobby);
+
+        client
+    }
+
+    /// Returns the script of the server until the lobby.
+    fn get_lobby_script() -> Vec<MockServerStep> {
+        vec![
+            MockServerStep::Expect(MESSAGE_ACTION_SEND_USERNAME),
+            MockServerStep::Expect(MESSAGE_ACTION_CHOOSE_SKIN),
+            MockServerStep::Expect(MESSAGE_ACTION_CREATE_ROOM),
+        ]
+    }
+
+    #[test]
+    fn test_client_goes_from_lobby_to_game() {
+
+        let mut tiles = [10; 400];
+        tiles[..200].copy_from_slice(&[0; 200]);
+
+        let mut script = get_lobby_script();
+        script.push(MockServerStep::push_map(&tiles));
+        script.push(MockServerStep::start_game(0, ["JACK", "ANNE"], [1, 0]));
+        script.push(MockServerStep::move_character(1, 45));
+
+        let server = MockServer::start(script);
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        let mut client = join_lobby(&server, &mut renderer, None);
+
+        wait_until(&mut client, &mut renderer, |client, _| {
+            client.get_current_screen() == Screen::Game &&
+                client.shared_resources.game_state.lock().unwrap().get_character_positions()[1] == 45
+        });
+
+        /* the pings aside, the username comes first */
+        let username = server
+            .get_received_messages()
+            .into_iter()
+            .find(|message| message.action != MESSAGE_ACTION_PING)
+            .unwrap();
+        assert_eq!(username.action, MESSAGE_ACTION_SEND_USERNAME);
+        assert_eq!(&username.data[..5], b"JACK\0");
+
+        assert_eq!(client.shared_resources.tiles.lock().unwrap()[..], tiles[..]);
+
+        let game_state = client.shared_resources.game_state.lock().unwrap();
+        assert_eq!(game_state.get_username(1), "ANNE");
+        assert_eq!(game_state.get_skins(), &[1, 0]);
+    }
+
+    #[test]
+    fn test_client_displays_game_over() {
+
+        let mut script = get_lobby_script();
+        script.push(MockServerStep::start_game(0, ["JACK", "ANNE"], [0, 1]));
+        script.push(MockServerStep::game_over(0, [2, 1], 300));
+
+        let server = MockServer::start(script);
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        let mut client = join_lobby(&server, &mut renderer, None);
+
+        wait_until(&mut client, &mut renderer, |client, renderer| {
+            client.get_current_screen() == Screen::GameOver &&
+                renderer.get_texts().contains(&"You win!")
+        });
+    }
+
+    #[test]
+    fn test_client_connects_without_connect_screen() {
+
+        let server = MockServer::start(vec![
+            MockServerStep::Expect(MESSAGE_ACTION_SEND_USERNAME),
+        ]);
+
+        /* nothing answers the discovery probes */
+        let mut client = Client::new("127.0.0.1:9".parse().unwrap());
+        client.connect(server.get_address());
+        assert_eq!(client.get_current_screen(), Screen::UsernamePrompt);
+
+        for key in [Key::J, Key::A, Key::C, Key::K, Key::Return] {
+            press(&mut client, key);
+        }
+
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        wait_until(&mut client, &mut renderer, |_, _| {
+            server
+                .get_received_messages()
+                .iter()
+                .any(|message| message.action == MESSAGE_ACTION_SEND_USERNAME)
+        });
+    }
+
+    #[test]
+    fn test_lobby_displays_the_ping() {
+
+        let server = MockServer::start(get_lobby_script());
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        let mut client = join_lobby(&server, &mut renderer, None);
+
+        /* measured by the network event loop once the server answers the pings */
+        client.shared_resources.ping.lock().unwrap().add_measurement(Duration::from_millis(42));
+
+        wait_until(&mut client, &mut renderer, |_, renderer| {
+            renderer.get_texts().contains(&"Ping: 42 ms")
+        });
+    }
+
+    #[test]
+    fn test_client_is_reset_when_disconnected() {
+
+        let mut script = get_lobby_script();
+        script.push(MockServerStep::start_game(0, ["JACK", "ANNE"], [0, 1]));
+        script.push(MockServerStep::Disconnect);
+
+        let server = MockServer::start(script);
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        let mut client = join_lobby(&server, &mut renderer, None);
+
+        wait_until(&mut client, &mut renderer, |client, renderer| {
+            client.get_current_screen() == Screen::Connect &&
+                renderer.get_texts().contains(&"The connection to the server has been lost.")
+        });
+
+        assert!(client.receiver.is_some());
+        assert_ne!(client.shared_resources.game_state.lock().unwrap().get_username(1), "ANNE");
+    }
+
+    #[test]
+    fn test_client_displays_connection_failure() {
+
+        /* nothing listens on the port of a closed listener */
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+        let responder = DiscoveryResponder::new(
+            "127.0.0.1:0".parse().unwrap(),
+            "Closed server",
+            address.port(),
+            0,
+        );
+
+        let mut client = Client::new(responder.get_address());
+        spawn(move || responder.respond_once());
+
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        wait_until(&mut client, &mut renderer, |_, renderer| {
+            renderer.get_texts().iter().any(|text| text.starts_with("Closed server"))
+        });
+
+        press(&mut client, Key::S);
+        press(&mut client, Key::Return);
+
+        wait_until(&mut client, &mut renderer, |client, renderer| {
+            client.get_current_screen() == Screen::Connect &&
+                renderer.get_texts().contains(&"Cannot connect to the server.")
+        });
+    }
+
+    #[test]
+    fn test_recorded_session_is_replayed() {
+
+        let path = temp_dir().join(format!("treasure-island-{}-session.rec", process::id()));
+
+        {
+            let mut script = get_lobby_script();
+            script.push(MockServerStep::start_game(1, ["JACK", "ANNE"], [1, 0]));
+            script.push(MockServerStep::move_character(0, 44));
+
+            let server = MockServer::start(script);
+            let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+            let recorder = Recorder::create(&path).unwrap();
+            let mut client = join_lobby(&server, &mut renderer, Some(Arc::new(Mutex::new(recorder))));
+
+            wait_until(&mut client, &mut renderer, |client, _| {
+                client.shared_resources.game_state.lock().unwrap().get_character_positions()[0] == 44
+            });
+        }
+
+        let recording = Recording::load(&path).unwrap();
+        remove_file(&path).unwrap();
+
+        let sent_messages = recording
+            .get_messages()
+            .iter()
+            .filter(|message| message.get_direction() == Direction::Sent);
+        assert!(sent_messages.map(|message| message.get_bytes()[0]).any(|action| action == MESSAGE_ACTION_SEND_USERNAME));
+
+        /* replayed without any server */
+        let mut client = Client::new("127.0.0.1:9".parse().unwrap());
+        client.replay(&recording, Some(10.0)).unwrap();
+
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        wait_until(&mut client, &mut renderer, |client, _| {
+            client.get_current_screen() == Screen::Game &&
+                client.shared_resources.game_state.lock().unwrap().get_character_positions()[0] == 44
+        });
+
+        let game_state = client.shared_resources.game_state.lock().unwrap();
+        assert_eq!(game_state.get_local_player(), Some(1));
+        assert_eq!(game_state.get_username(0), "JACK");
+    }
+
+    #[test]
+    fn test_spectator_watches_the_whole_game() {
+
+        let script = vec![
+            MockServerStep::Expect(MESSAGE_ACTION_SEND_USERNAME),
+            MockServerStep::Expect(MESSAGE_ACTION_CHOOSE_SKIN),
+            MockServerStep::Expect(MESSAGE_ACTION_SPECTATE_ROOM),
+            MockServerStep::push_map(&[0; 400]),
+            MockServerStep::start_game(255, ["JACK", "ANNE"], [0, 1]),
+            MockServerStep::move_character(1, 399),
+        ];
+
+        let server = MockServer::start(script);
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        let mut client = go_to_room_browser(&server, &mut renderer, None);
+
+        /* the join as item is the last one, before the refresh one */
+        press(&mut client, Key::W);
+        press(&mut client, Key::Return);
+        press(&mut client, Key::W);
+        for key in [Key::D1, Key::D2, Key::D3, Key::D4] {
+            press(&mut client, key);
+        }
+        press(&mut client, Key::Return);
+        assert_eq!(client.get_current_screen(), Screen::Lobby);
+
+        wait_until(&mut client, &mut renderer, |client, _| {
+            client.get_current_screen() == Screen::Game &&
+                client.shared_resources.game_state.lock().unwrap().get_character_positions()[1] == 399
+        });
+
+        let spectate = server
+            .get_received_messages()
+            .into_iter()
+            .find(|message| message.action == MESSAGE_ACTION_SPECTATE_ROOM)
+            .unwrap();
+        assert_eq!(&spectate.data[..4], b"1234");
+
+        press(&mut client, Key::D);
+        wait_until(&mut client, &mut renderer, |_, renderer| {
+            renderer.get_texts().contains(&"Following ANNE")
+        });
+        assert!(!renderer.get_texts().iter().any(|text| text.contains("(you)")));
+
+        let game_state = client.shared_resources.game_state.lock().unwrap();
+        assert!(game_state.is_spectating());
+        assert!(!game_state.can_local_player_act());
+        assert!(game_state.get_visibility().get_tile_visibility(399) == TileVisibility::Visible);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_client_displays_stopped_recording() {
+
+        let mut client = Client::new("127.0.0.1:9".parse().unwrap());
+
+        /* every write into this device fails as if the disk was full */
+        let recorder = Arc::new(Mutex::new(Recorder::create(Path::new("/dev/full")).unwrap()));
+        client.set_recorder(recorder.clone());
+
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        client.render(&mut renderer);
+        assert!(!renderer.get_texts().contains(&"Recording stopped"));
+
+        recorder.lock().unwrap().record_or_stop(Direction::Sent, &[1, 2, 3]);
+
+        let mut renderer = RecordingRenderer::new(WINDOW_SIZE);
+        client.render(&mut renderer);
+        assert!(renderer.get_texts().contains(&"Recording stopped"));
+    }
+}
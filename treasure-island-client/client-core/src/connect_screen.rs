@@ -0,0 +1,204 @@
+//! Handles the connect screen, where the player picks the server to play on.
+
+use crate::discovery::ServerDiscovery;
+use crate::renderer::Renderer;
+use crate::input::{
+    Action,
+    Direction,
+};
+
+use piston_window::Transformed;
+
+use std::net::SocketAddr;
+use std::time::Instant;
+
+/* always proposed, even if it does not answer the discovery probes */
+const LOCAL_SERVER_ADDRESS: &str = "127.0.0.1:9500";
+
+pub struct ConnectScreen {
+    discovery: ServerDiscovery,
+    probe_previous_time: Option<Instant>,
+
+    /* the local server comes first, then the discovered servers */
+    selected_server: usize,
+
+    chosen_server: Option<SocketAddr>,
+
+    /* the error of the last connection, if any */
+    error: Option<&'static str>,
+}
+
+impl ConnectScreen {
+
+    /// Constructor.
+    ///
+    /// # Args:
+    ///
+    /// `discovery` - the discovery used to find the servers of the local network
+    pub fn new(discovery: ServerDiscovery) -> ConnectScreen {
+        ConnectScreen {
+            discovery,
+            probe_previous_time: None,
+            selected_server: 0,
+            chosen_server: None,
+            error: None,
+        }
+    }
+
+    /// Renders the screen.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer to draw with
+    pub fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+    ) {
+
+        let transform = renderer.get_transform();
+
+        const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+        const SELECTED_SERVER_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+        const ERROR_COLOR: [f32; 4] = [0.9, 0.1, 0.1, 1.0];
+
+        const TEXT_HORIZONTAL_POSITION: f64 = 435.0;
+
+        const TITLE_FONT_SIZE: u32 = 64;
+        const TITLE_VERTICAL_POSITION: f64 = 150.0;
+        renderer.draw_text(
+            WHITE_COLOR,
+            TITLE_FONT_SIZE,
+            "Servers",
+            transform.trans(
+                TEXT_HORIZONTAL_POSITION,
+                TITLE_VERTICAL_POSITION,
+            ),
+        );
+
+        let mut servers_lines: Vec<String> = vec![
+            format!("Local server   {}", LOCAL_SERVER_ADDRESS),
+        ];
+
+        for server in self.discovery.get_servers() {
+            servers_lines.push(format!(
+                "{}   {}   {} players   {} ms",
+                server.get_name(),
+                server.get_address(),
+                server.get_players(),
+                server.get_ping().as_millis(),
+            ));
+        }
+
+        const SERVER_FONT_SIZE: u32 = 40;
+        const SERVERS_VERTICAL_POSITION: f64 = 240.0;
+        const SERVERS_VERTICAL_DISTANCE: f64 = 50.0;
+        for (index, line) in servers_lines.iter().enumerate() {
+
+            let color = if index == self.selected_server {
+                SELECTED_SERVER_COLOR
+            } else {
+                WHITE_COLOR
+            };
+
+            renderer.draw_text(
+                color,
+                SERVER_FONT_SIZE,
+                line,
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    SERVERS_VERTICAL_POSITION + (index as f64) * SERVERS_VERTICAL_DISTANCE,
+                ),
+            );
+        }
+
+        const ERROR_MESSAGE_VERTICAL_POSITION: f64 = 1020.0;
+        if let Some(error) = self.error {
+            renderer.draw_text(
+                ERROR_COLOR,
+                SERVER_FONT_SIZE,
+                error,
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    ERROR_MESSAGE_VERTICAL_POSITION,
+                ),
+            );
+        }
+    }
+
+    /// Handle the actions of the screen. Mutable as it modifies the selected server.
+    /// Regularly probes the local network and collects the answers while the screen is displayed.
+    ///
+    /// # Args:
+    ///
+    /// `action` - the action to handle, if any
+    pub fn handle_events(
+        &mut self,
+        action: Option<Action>,
+    ) {
+
+        const PROBE_INTERVAL: u64 = 3;
+        let probe = match self.probe_previous_time {
+            Some(time) => time.elapsed().as_secs() >= PROBE_INTERVAL,
+            None => true,
+        };
+
+        /* the selection follows the selected server if the servers before it disappear */
+        let selected_address = self.selected_server
+            .checked_sub(1)
+            .and_then(|index| self.discovery.get_servers().get(index))
+            .map(|server| server.get_address());
+
+        if probe {
+            self.discovery.probe();
+            self.probe_previous_time = Some(Instant::now());
+        }
+
+        self.discovery.poll();
+
+        let servers = self.discovery.get_servers();
+        let servers_amount = servers.len() + 1;
+
+        if let Some(address) = selected_address {
+            if let Some(index) = servers.iter().position(|server| server.get_address() == address) {
+                self.selected_server = index + 1;
+            }
+        }
+
+        /* the selected server may have disappeared since the last probe */
+        if self.selected_server >= servers_amount {
+            self.selected_server = servers_amount - 1;
+        }
+
+        match action {
+            Some(Action::Move(Direction::Up)) => {
+                self.selected_server = (self.selected_server + servers_amount - 1) % servers_amount;
+            },
+            Some(Action::Move(Direction::Down)) => {
+                self.selected_server = (self.selected_server + 1) % servers_amount;
+            },
+            Some(Action::Confirm) => {
+                self.chosen_server = if self.selected_server == 0 {
+                    Some(LOCAL_SERVER_ADDRESS.parse().unwrap())
+                } else {
+                    Some(self.discovery.get_servers()[self.selected_server - 1].get_address())
+                };
+            },
+            _ => {}
+        };
+    }
+
+    /// Returns the server the player chose to connect to, if any; the choice is consumed.
+    pub fn take_chosen_server(&mut self) -> Option<SocketAddr> {
+        self.chosen_server.take()
+    }
+
+    /// Displays an error as the connection to the chosen server failed.
+    pub fn set_connection_failed(&mut self) {
+        self.error = Some("Cannot connect to the server.");
+    }
+
+    /// Displays an error as the connection to the server has been lost.
+    pub fn set_connection_lost(&mut self) {
+        self.error = Some("The connection to the server has been lost.");
+    }
+}
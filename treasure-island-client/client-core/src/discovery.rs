@@ -0,0 +1,300 @@
+//! Discovers the servers of the local network through UDP broadcast.
+//!
+//! The client broadcasts a probe on the discovery port; every server answers
+//! with its name, its game port and its amount of connected players.
+
+use protocol::get_string_from_bytes;
+
+use std::net::{
+    UdpSocket,
+    SocketAddr,
+    Ipv4Addr,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
+pub const DISCOVERY_PORT: u16 = 9501;
+
+const PROBE: &[u8] = b"TREASURE_ISLAND_PROBE";
+
+const SERVER_NAME_LENGTH: usize = 16;
+const RESPONSE_LENGTH: usize = SERVER_NAME_LENGTH + 3;
+
+/// One server that answered the discovery probe.
+pub struct DiscoveredServer {
+    name: String,
+    address: SocketAddr,
+    players: u8,
+    ping: Duration,
+
+    /* the amount of probes sent since the last answer of the server */
+    missed_probes: u8,
+}
+
+impl DiscoveredServer {
+
+    /// Returns the server name.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the address of the game server (TCP).
+    pub fn get_address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Returns the amount of players connected to the server.
+    pub fn get_players(&self) -> u8 {
+        self.players
+    }
+
+    /// Returns the time the server took to answer the probe.
+    pub fn get_ping(&self) -> Duration {
+        self.ping
+    }
+}
+
+/// Sends probes and collects the servers answers. Never blocks, so it can be polled from the main thread.
+pub struct ServerDiscovery {
+    socket: UdpSocket,
+    probe_address: SocketAddr,
+    probe_time: Instant,
+    servers: Vec<DiscoveredServer>,
+}
+
+impl ServerDiscovery {
+
+    /// Constructor. Binds an ephemeral UDP port allowed to broadcast.
+    ///
+    /// # Args:
+    ///
+    /// `probe_address` - the address the probes are sent to; the broadcast address of the discovery port for real games
+    pub fn new(probe_address: SocketAddr) -> ServerDiscovery {
+
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+        socket.set_broadcast(true).unwrap();
+        socket.set_nonblocking(true).unwrap();
+
+        ServerDiscovery {
+            socket,
+            probe_address,
+            probe_time: Instant::now(),
+            servers: Vec::new(),
+        }
+    }
+
+    /// Sends a new probe. The known servers are kept between the probes so the list does not flicker;
+    /// a server is only dropped after it missed several probes in a row.
+    pub fn probe(&mut self) {
+
+        const MAX_MISSED_PROBES: u8 = 3;
+        self.servers.retain(|server| server.missed_probes < MAX_MISSED_PROBES);
+
+        for server in self.servers.iter_mut() {
+            server.missed_probes += 1;
+        }
+
+        /* the network may be unreachable (no interface up),
+           the known servers are dropped after a few probes in that case */
+        let _ = self.socket.send_to(PROBE, self.probe_address);
+
+        self.probe_time = Instant::now();
+    }
+
+    /// Reads all the received answers, if any. Mutable as it adds the answering servers to the list.
+    pub fn poll(&mut self) {
+
+        let mut response: [u8; RESPONSE_LENGTH] = [0; RESPONSE_LENGTH];
+
+        loop {
+
+            let (length, source) = match self.socket.recv_from(&mut response) {
+                Ok(received) => received,
+
+                /* no more answers for now (would block) */
+                Err(_) => return,
+            };
+
+            if length != RESPONSE_LENGTH {
+                continue;
+            }
+
+            let port = u16::from_be_bytes([
+                response[SERVER_NAME_LENGTH],
+                response[SERVER_NAME_LENGTH + 1],
+            ]);
+
+            let address = SocketAddr::new(source.ip(), port);
+            let name = get_string_from_bytes(&response[..SERVER_NAME_LENGTH]);
+            let players = response[SERVER_NAME_LENGTH + 2];
+
+            /* a known server is updated in place, so the servers keep their order in the list;
+               a server may also answer several times to the same probe
+               if it is reachable through several interfaces */
+            if let Some(server) = self.servers.iter_mut().find(|server| server.address == address) {
+
+                if server.missed_probes != 0 {
+                    server.ping = self.probe_time.elapsed();
+                }
+
+                server.name = name;
+                server.players = players;
+                server.missed_probes = 0;
+                continue;
+            }
+
+            self.servers.push(DiscoveredServer {
+                name,
+                address,
+                players,
+                ping: self.probe_time.elapsed(),
+                missed_probes: 0,
+            });
+        }
+    }
+
+    /// Returns the servers that answered one of the last probes.
+    pub fn get_servers(&self) -> &[DiscoveredServer] {
+        &self.servers
+    }
+}
+
+/// Answers the discovery probes like a server would. Used to test the discovery without a real server.
+#[cfg(test)]
+pub struct DiscoveryResponder {
+    socket: UdpSocket,
+    response: [u8; RESPONSE_LENGTH],
+}
+
+#[cfg(test)]
+impl DiscoveryResponder {
+
+    /// Constructor. Binds the given address to listen for probes.
+    ///
+    /// # Args:
+    ///
+    /// `address` - the address to listen on
+    /// `name` - the name of the server
+    /// `port` - the game port of the server
+    /// `players` - the amount of players connected to the server
+    pub fn new(
+        address: SocketAddr,
+        name: &str,
+        port: u16,
+        players: u8,
+    ) -> DiscoveryResponder {
+
+        let mut response: [u8; RESPONSE_LENGTH] = [0; RESPONSE_LENGTH];
+        response[..name.len()].copy_from_slice(name.as_bytes());
+        response[SERVER_NAME_LENGTH..SERVER_NAME_LENGTH + 2].copy_from_slice(&port.to_be_bytes());
+        response[SERVER_NAME_LENGTH + 2] = players;
+
+        DiscoveryResponder {
+            socket: UdpSocket::bind(address).unwrap(),
+            response,
+        }
+    }
+
+    /// Returns the address the responder listens on.
+    pub fn get_address(&self) -> SocketAddr {
+        self.socket.local_addr().unwrap()
+    }
+
+    /// Waits for one probe and answers it; ignores any other received data.
+    pub fn respond_once(&self) {
+
+        let mut probe: [u8; 64] = [0; 64];
+
+        loop {
+            let (length, source) = self.socket.recv_from(&mut probe).unwrap();
+
+            if &probe[..length] == PROBE {
+                self.socket.send_to(&self.response, source).unwrap();
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use std::thread::{
+        sleep,
+        spawn,
+    };
+
+    /// Polls the discovery until the expected amount of servers answered (or gives up after one second).
+    fn wait_for_servers(
+        discovery: &mut ServerDiscovery,
+        expected_servers: usize,
+    ) {
+        let start = Instant::now();
+
+        while discovery.get_servers().len() < expected_servers &&
+            start.elapsed() < Duration::from_secs(1) {
+            discovery.poll();
+            sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_discovery_lists_answering_server() {
+
+        let responder = DiscoveryResponder::new(
+            "127.0.0.1:0".parse().unwrap(),
+            "Skull Rock",
+            9500,
+            3,
+        );
+
+        let mut discovery = ServerDiscovery::new(responder.get_address());
+
+        let responder_thread = spawn(move || responder.respond_once());
+
+        discovery.probe();
+        wait_for_servers(&mut discovery, 1);
+        responder_thread.join().unwrap();
+
+        let servers = discovery.get_servers();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].get_name(), "Skull Rock");
+        assert_eq!(servers[0].get_address(), "127.0.0.1:9500".parse().unwrap());
+        assert_eq!(servers[0].get_players(), 3);
+    }
+
+    #[test]
+    fn test_discovery_keeps_servers_until_several_missed_probes() {
+
+        let responder = DiscoveryResponder::new(
+            "127.0.0.1:0".parse().unwrap(),
+            "Skull Rock",
+            9500,
+            0,
+        );
+
+        let mut discovery = ServerDiscovery::new(responder.get_address());
+
+        let responder_thread = spawn(move || responder.respond_once());
+
+        discovery.probe();
+        wait_for_servers(&mut discovery, 1);
+        responder_thread.join().unwrap();
+
+        /* the responder does not answer anymore */
+        for _ in 0..3 {
+            discovery.probe();
+            discovery.poll();
+            assert_eq!(discovery.get_servers().len(), 1);
+        }
+
+        discovery.probe();
+        discovery.poll();
+
+        assert!(discovery.get_servers().is_empty());
+    }
+}
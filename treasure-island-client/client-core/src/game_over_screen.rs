@@ -0,0 +1,222 @@
+//! Handles the game over screen, displaying the result of the game once the server ends it.
+
+use protocol::{
+    Message,
+    MESSAGE_ACTION_RETURN_TO_LOBBY,
+};
+use crate::network::MessageSender;
+use crate::renderer::Renderer;
+use crate::game_state::GameState;
+use crate::gui::get_player_color;
+use crate::input::{
+    Action,
+    Direction,
+};
+
+use piston_window::Transformed;
+
+use std::sync::{
+    Mutex,
+    Arc,
+};
+
+const RETURN_TO_LOBBY_BUTTON: usize = 0;
+const PLAY_AGAIN_BUTTON: usize = 1;
+const QUIT_BUTTON: usize = 2;
+const BUTTONS_AMOUNT: usize = 3;
+
+/// Choice of the player once the game is over.
+#[derive(PartialEq, Copy, Clone)]
+pub enum GameOverChoice {
+    ReturnToLobby,
+    PlayAgain,
+    Quit,
+}
+
+pub struct GameOverScreen {
+    sender: MessageSender,
+    game_state: Arc<Mutex<GameState>>,
+    selected_button: usize,
+    choice: Option<GameOverChoice>,
+}
+
+impl GameOverScreen {
+
+    /// Constructor.
+    ///
+    /// # Args:
+    ///
+    /// `sender` - the sender to send messages to the server
+    /// `game_state` - thread safe pointer to the game state; shared with the thread that receives the game result from the server
+    pub fn new(
+        sender: MessageSender,
+        game_state: Arc<Mutex<GameState>>,
+    ) -> GameOverScreen {
+        GameOverScreen {
+            sender,
+            game_state,
+            selected_button: RETURN_TO_LOBBY_BUTTON,
+            choice: None,
+        }
+    }
+
+    /// Renders the screen.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer to draw with
+    pub fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+    ) {
+
+        let transform = renderer.get_transform();
+
+        let game_state_mutex_guard = self.game_state.lock().unwrap();
+        let game_state = &*game_state_mutex_guard;
+
+        const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+        const SELECTED_BUTTON_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+
+        const TEXT_HORIZONTAL_POSITION: f64 = 635.0;
+
+        if let Some(result) = game_state.get_result() {
+
+            let (title, title_color) = match result.get_winner() {
+                Some(winner) if game_state.get_local_player() == Some(winner) => {
+                    ("You win!".to_string(), get_player_color(winner))
+                },
+                Some(winner) => {
+                    (format!("{} wins!", game_state.get_username(winner)), get_player_color(winner))
+                },
+                None => ("Draw!".to_string(), WHITE_COLOR),
+            };
+
+            const TITLE_FONT_SIZE: u32 = 96;
+            const TITLE_VERTICAL_POSITION: f64 = 250.0;
+            renderer.draw_text(
+                title_color,
+                TITLE_FONT_SIZE,
+                &title,
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    TITLE_VERTICAL_POSITION,
+                ),
+            );
+
+            const PLAYER_FONT_SIZE: u32 = 48;
+            const PLAYERS_VERTICAL_POSITION: f64 = 400.0;
+            const PLAYERS_VERTICAL_DISTANCE: f64 = 70.0;
+            for (index, treasures) in result.get_treasures().iter().enumerate() {
+
+                renderer.draw_text(
+                    get_player_color(index),
+                    PLAYER_FONT_SIZE,
+                    &format!("{}: {} treasures found", game_state.get_username(index), treasures),
+                    transform.trans(
+                        TEXT_HORIZONTAL_POSITION,
+                        PLAYERS_VERTICAL_POSITION + (index as f64) * PLAYERS_VERTICAL_DISTANCE,
+                    ),
+                );
+            }
+
+            let duration = result.get_duration().as_secs();
+
+            const DURATION_FONT_SIZE: u32 = 48;
+            const DURATION_VERTICAL_POSITION: f64 = 700.0;
+            renderer.draw_text(
+                WHITE_COLOR,
+                DURATION_FONT_SIZE,
+                &format!("Game duration: {}:{:02}", duration / 60, duration % 60),
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    DURATION_VERTICAL_POSITION,
+                ),
+            );
+        }
+
+        let buttons_labels: [&str; BUTTONS_AMOUNT] = [
+            "Back to lobby",
+            "Play again",
+            "Quit",
+        ];
+
+        const BUTTON_FONT_SIZE: u32 = 48;
+        const BUTTONS_VERTICAL_POSITION: f64 = 950.0;
+        const BUTTONS_HORIZONTAL_DISTANCE: f64 = 350.0;
+        for (index, label) in buttons_labels.iter().enumerate() {
+
+            let color = if index == self.selected_button {
+                SELECTED_BUTTON_COLOR
+            } else {
+                WHITE_COLOR
+            };
+
+            renderer.draw_text(
+                color,
+                BUTTON_FONT_SIZE,
+                label,
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION + (index as f64) * BUTTONS_HORIZONTAL_DISTANCE,
+                    BUTTONS_VERTICAL_POSITION,
+                ),
+            );
+        }
+    }
+
+    /// Handle the actions of the screen. Mutable as it modifies the selected button and the choice.
+    ///
+    /// # Args:
+    ///
+    /// `action` - the action triggered by the event, if any
+    pub fn handle_events(
+        &mut self,
+        action: Option<Action>,
+    ) {
+
+        match action {
+            Some(Action::Move(Direction::Left)) => {
+                self.selected_button = (self.selected_button + BUTTONS_AMOUNT - 1) % BUTTONS_AMOUNT;
+            },
+            Some(Action::Move(Direction::Right)) => {
+                self.selected_button = (self.selected_button + 1) % BUTTONS_AMOUNT;
+            },
+            Some(Action::Confirm) if self.selected_button == RETURN_TO_LOBBY_BUTTON => {
+                self.choose(GameOverChoice::ReturnToLobby);
+            },
+            Some(Action::Confirm) if self.selected_button == PLAY_AGAIN_BUTTON => {
+                self.choose(GameOverChoice::PlayAgain);
+            },
+            Some(Action::Confirm) if self.selected_button == QUIT_BUTTON => {
+                self.choose(GameOverChoice::Quit);
+            },
+            Some(Action::Cancel) => {
+                self.choose(GameOverChoice::ReturnToLobby);
+            },
+            _ => {}
+        };
+    }
+
+    /// Returns the choice of the player, if any; the choice is consumed.
+    pub fn take_choice(&mut self) -> Option<GameOverChoice> {
+        self.choice.take()
+    }
+
+    /// Stores the choice of the player; notifies the server when the player goes back to the lobby of the room.
+    ///
+    /// # Args:
+    ///
+    /// `choice` - the choice of the player
+    fn choose(
+        &mut self,
+        choice: GameOverChoice,
+    ) {
+
+        if choice != GameOverChoice::Quit {
+            self.sender.send(Message::new(MESSAGE_ACTION_RETURN_TO_LOBBY)).unwrap();
+        }
+
+        self.selected_button = RETURN_TO_LOBBY_BUTTON;
+        self.choice = Some(choice);
+    }
+}
@@ -0,0 +1,91 @@
+//! Structure and implementation of the result of a game, sent by the server when the game is over.
+
+use crate::game_state::PLAYERS_AMOUNT;
+
+use std::time::Duration;
+
+pub struct GameResult {
+    winner: Option<usize>,
+    treasures: [u8; PLAYERS_AMOUNT],
+    duration: Duration,
+}
+
+impl GameResult {
+
+    /// Creates the result from the server message.
+    ///
+    /// The data contains the index of the winner (255 when nobody wins), the amount of treasures found
+    /// by every player (one byte per player, ordered by character) and the duration of the game in seconds
+    /// on two bytes (big endian).
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the game over message
+    pub fn from_bytes(data: &[u8]) -> GameResult {
+
+        let winner = data[0] as usize;
+
+        let mut treasures = [0; PLAYERS_AMOUNT];
+        const TREASURES_OFFSET: usize = 1;
+        treasures.copy_from_slice(&data[TREASURES_OFFSET..TREASURES_OFFSET + PLAYERS_AMOUNT]);
+
+        const DURATION_OFFSET: usize = TREASURES_OFFSET + PLAYERS_AMOUNT;
+        let duration = u16::from_be_bytes([
+            data[DURATION_OFFSET],
+            data[DURATION_OFFSET + 1],
+        ]);
+
+        GameResult {
+            winner: if winner < PLAYERS_AMOUNT {
+                Some(winner)
+            } else {
+                None
+            },
+            treasures,
+            duration: Duration::from_secs(duration as u64),
+        }
+    }
+
+    /// Returns the index of the winner, none if the game is a draw.
+    pub fn get_winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    /// Returns the amount of treasures found by every player during the game.
+    pub fn get_treasures(&self) -> &[u8; PLAYERS_AMOUNT] {
+        &self.treasures
+    }
+
+    /// Returns the duration of the game.
+    pub fn get_duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_from_bytes_reads_the_winner() {
+
+        let result = GameResult::from_bytes(&[1, 2, 5, 1, 44]);
+
+        assert_eq!(result.get_winner(), Some(1));
+        assert_eq!(result.get_treasures(), &[2, 5]);
+        assert_eq!(result.get_duration(), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_from_bytes_reads_a_draw() {
+
+        let result = GameResult::from_bytes(&[255, 3, 3, 0, 90]);
+
+        assert_eq!(result.get_winner(), None);
+        assert_eq!(result.get_treasures(), &[3, 3]);
+
+        /* an unknown winner is a draw too */
+        assert_eq!(GameResult::from_bytes(&[PLAYERS_AMOUNT as u8, 3, 3, 0, 90]).get_winner(), None);
+    }
+}
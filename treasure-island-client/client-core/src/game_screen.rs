@@ -0,0 +1,531 @@
+//! Handles the main game screen.
+
+use crate::gui::{
+    display_sprites,
+    display_characters,
+    display_characters_labels,
+    display_cursor,
+    display_chest_openings,
+    display_reachable_tiles,
+    display_path,
+    get_tile_screen_position,
+};
+use crate::renderer::Renderer;
+use crate::character::Character;
+use crate::skins::SKINS;
+use crate::chat::ChatLog;
+use crate::chat_panel::ChatPanel;
+use protocol::{
+    Message,
+    MESSAGE_ACTION_DIG,
+    MESSAGE_ACTION_MOVE,
+};
+use crate::network::MessageSender;
+use crate::game_state::GameState;
+use crate::hud::display_hud;
+use crate::ping::Ping;
+use crate::pathfinding::{
+    find_path,
+    get_reachable_tiles,
+    MOVEMENT_RANGE,
+};
+use crate::minimap::{
+    display_minimap,
+    get_camera_origin_from_click,
+};
+
+use crate::input::{
+    Action,
+    Direction,
+};
+
+use piston_window::{
+    Event,
+    Button,
+    MouseButton,
+    PressEvent,
+    MouseCursorEvent,
+    RenderEvent,
+};
+
+use std::sync::{
+    Mutex,
+    Arc,
+};
+use std::time::Instant;
+
+const TILES_AMOUNT: usize = 400;
+const CHARACTERS_AMOUNT: usize = 2;
+
+const SPRITES: [&str; 11] = [
+
+    /* from 0 to 3: sands */
+    "sand_1.png",
+    "sand_2.png",
+    "sand_3.png",
+    "sand_4.png",
+
+    /* from 4 to 7: sands and water links */
+    "sand_water_1.png",
+    "sand_water_2.png",
+    "sand_water_3.png",
+    "sand_water_4.png",
+
+    /* at 8: palm tree */
+    "palm_1.png",
+
+    /* at 9: chest */
+    "chest_1.png",
+
+    /* at 10: water */
+    "water_1.png",
+];
+
+const CHEST_SPRITE_INDEX: usize = 9;
+const CHEST_OPENING_DURATION: u128 = 1000;
+
+type Tiles = Arc<Mutex<[u8; TILES_AMOUNT]>>;
+
+pub struct GameScreen {
+    tiles: Tiles,
+    game_state: Arc<Mutex<GameState>>,
+    ping: Arc<Mutex<Ping>>,
+    sender: MessageSender,
+    characters: [Character; CHARACTERS_AMOUNT],
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+    event_previous_time: Instant,
+    cursor_position: usize,
+    chat_panel: ChatPanel,
+    mouse_position: [f64; 2],
+    window_size: [f64; 2],
+
+    /* a spectator camera stays centered on the followed character until it is moved by hand */
+    camera_following: bool,
+}
+
+impl GameScreen {
+
+    /// Constructor. Creates the characters.
+    ///
+    /// # Args:
+    ///
+    /// `tiles` - thread safe pointer to the tiles; shared with the thread that receives updated tiles map from the server
+    /// `game_state` - thread safe pointer to the game state; shared with the thread that receives the game updates from the server
+    /// `ping` - thread safe pointer to the round trip time to the server; shared with the network thread measuring it
+    /// `sender` - the sender to send messages to the server
+    /// `chat_log` - thread safe pointer to the chat log; shared with the thread that receives the chat messages from the server
+    pub fn new(
+        tiles: Tiles,
+        game_state: Arc<Mutex<GameState>>,
+        ping: Arc<Mutex<Ping>>,
+        sender: MessageSender,
+        chat_log: Arc<Mutex<ChatLog>>,
+    ) -> GameScreen {
+
+        let (positions, characters_skins) = {
+            let game_state_mutex_guard = game_state.lock().unwrap();
+            (
+                *game_state_mutex_guard.get_character_positions(),
+                *game_state_mutex_guard.get_skins(),
+            )
+        };
+
+        let characters: [Character; CHARACTERS_AMOUNT] = [
+            Character::new(
+                characters_skins[0],
+                positions[0]
+            ),
+            Character::new(
+                characters_skins[1],
+                positions[1]
+            ),
+        ];
+
+        GameScreen {
+            tiles,
+            game_state,
+            ping,
+            sender: sender.clone(),
+            characters,
+            origin_horizontal_position: 0.0,
+            origin_vertical_position: 0.0,
+            event_previous_time: Instant::now(),
+            cursor_position: positions[0],
+            chat_panel: ChatPanel::new(
+                chat_log,
+                sender,
+            ),
+            mouse_position: [0.0, 0.0],
+            window_size: [0.0, 0.0],
+            camera_following: true,
+        }
+    }
+
+    /// Renders the screen.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer to draw with
+    pub fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+    ) {
+
+        let transform = renderer.get_transform();
+
+        let tiles_mutex_guard = self.tiles.lock().unwrap();
+        let tiles = &*tiles_mutex_guard;
+
+        let mut game_state_mutex_guard = self.game_state.lock().unwrap();
+        let game_state = &mut *game_state_mutex_guard;
+        game_state.remove_finished_chest_openings(CHEST_OPENING_DURATION);
+
+        let followed_character_position = self.characters[game_state.get_followed_player()].get_position();
+        game_state.update_visibility(followed_character_position);
+
+        display_sprites(
+            renderer,
+            &transform,
+            &SPRITES,
+            tiles,
+            game_state,
+            self.origin_horizontal_position,
+            self.origin_vertical_position,
+        );
+
+        if game_state.can_local_player_act() {
+
+            let reachable_tiles = get_reachable_tiles(
+                tiles,
+                followed_character_position,
+                MOVEMENT_RANGE,
+            );
+
+            display_reachable_tiles(
+                renderer,
+                &transform,
+                &reachable_tiles,
+                self.origin_horizontal_position,
+                self.origin_vertical_position,
+            );
+        }
+
+        if !game_state.is_spectating() {
+            display_cursor(
+                renderer,
+                &transform,
+                self.cursor_position,
+                self.origin_horizontal_position,
+                self.origin_vertical_position,
+            );
+        }
+
+        if let Some(path) = self.get_planned_path(
+            tiles,
+            game_state,
+        ) {
+            display_path(
+                renderer,
+                &transform,
+                &path,
+                self.origin_horizontal_position,
+                self.origin_vertical_position,
+            );
+        }
+
+        display_characters(
+            renderer,
+            &transform,
+            &SKINS.map(|(sprite, _)| sprite),
+            &self.characters,
+            game_state,
+            self.origin_horizontal_position,
+            self.origin_vertical_position,
+        );
+
+        display_chest_openings(
+            renderer,
+            &transform,
+            SPRITES[CHEST_SPRITE_INDEX],
+            game_state.get_chest_openings(),
+            CHEST_OPENING_DURATION,
+            self.origin_horizontal_position,
+            self.origin_vertical_position,
+        );
+
+        display_characters_labels(
+            renderer,
+            &transform,
+            &self.characters,
+            game_state,
+            self.origin_horizontal_position,
+            self.origin_vertical_position,
+        );
+
+        display_minimap(
+            renderer,
+            tiles,
+            &self.characters,
+            game_state,
+            self.origin_horizontal_position,
+            self.origin_vertical_position,
+        );
+
+        let round_trip_time = self.ping.lock().unwrap().get_round_trip_time();
+        display_hud(
+            renderer,
+            game_state,
+            round_trip_time,
+        );
+
+        self.chat_panel.render(
+            renderer,
+        );
+    }
+
+    /// Handle the actions of the screen. Mutable as it modifies the camera and the cursor positions.
+    /// Clicking on the minimap moves the camera to the clicked position;
+    /// confirming moves the local character to the cursor along the planned path.
+    /// Spectators cycle through the followed characters with the left and right moves.
+    ///
+    /// # Args:
+    ///
+    /// `event` - the event to handle, first handled by the chat panel
+    /// `action` - the action triggered by the event, if any
+    pub fn handle_events(
+        &mut self,
+        event: &Event,
+        action: Option<Action>,
+    ) {
+
+        /* the characters follow the positions and the skins sent by the server */
+        {
+            let game_state_mutex_guard = self.game_state.lock().unwrap();
+            let game_state = &*game_state_mutex_guard;
+            for (index, character) in self.characters.iter_mut().enumerate() {
+                character.set_position(game_state.get_character_positions()[index]);
+                character.set_skin(game_state.get_skins()[index]);
+            }
+        }
+
+        if self.chat_panel.handle_events(event) {
+            return;
+        }
+
+        if let Some(args) = event.render_args() {
+            self.window_size = args.window_size;
+        }
+
+        let spectating = self.game_state.lock().unwrap().is_spectating();
+        if spectating && self.camera_following {
+            self.center_camera_on_followed_character();
+        }
+
+        if let Some(position) = event.mouse_cursor_args() {
+            self.mouse_position = position;
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = event.press_args() {
+            if let Some((horizontal_position, vertical_position)) = get_camera_origin_from_click(
+                self.mouse_position,
+                self.window_size,
+            ) {
+                self.origin_horizontal_position = horizontal_position;
+                self.origin_vertical_position = vertical_position;
+                self.camera_following = false;
+            }
+        }
+
+        if spectating {
+            match action {
+                Some(Action::Pan(direction)) => {
+                    self.camera_following = false;
+                    self.move_camera(direction);
+                },
+                Some(Action::Move(Direction::Left)) => self.follow_next_character(false),
+                Some(Action::Move(Direction::Right)) => self.follow_next_character(true),
+                _ => {}
+            };
+            return;
+        }
+
+        match action {
+            Some(Action::Pan(direction)) => self.move_camera(direction),
+            Some(Action::Move(direction)) => self.move_cursor(direction),
+            Some(Action::Dig) => self.dig(),
+            Some(Action::Confirm) => self.move_character(),
+            _ => {}
+        };
+    }
+
+    /// Returns the path from the local character to the cursor, only if the local player can act
+    /// and the cursor is a different tile reachable within the movement range.
+    ///
+    /// # Args:
+    ///
+    /// `tiles` - the tiles of the map
+    /// `game_state` - the game state
+    fn get_planned_path(
+        &self,
+        tiles: &[u8; TILES_AMOUNT],
+        game_state: &GameState,
+    ) -> Option<Vec<usize>> {
+
+        if !game_state.can_local_player_act() {
+            return None;
+        }
+
+        let local_character_position = self.characters[game_state.get_local_player()?].get_position();
+        let path = find_path(
+            tiles,
+            local_character_position,
+            self.cursor_position,
+        )?;
+
+        let steps = path.len() - 1;
+        if steps == 0 || steps > MOVEMENT_RANGE {
+            return None;
+        }
+
+        Some(path)
+    }
+
+    /// Asks the server to move the local character to the cursor, if the planned path allows it.
+    /// The server answers with the character move, applied to the game state.
+    fn move_character(&mut self) {
+
+        let tiles_mutex_guard = self.tiles.lock().unwrap();
+        let tiles = &*tiles_mutex_guard;
+
+        let game_state_mutex_guard = self.game_state.lock().unwrap();
+        let game_state = &*game_state_mutex_guard;
+
+        if self.get_planned_path(
+            tiles,
+            game_state,
+        ).is_none() {
+            return;
+        }
+
+        let mut message = Message::new(MESSAGE_ACTION_MOVE);
+
+        const MESSAGE_DATA_LENGTH: usize = 32;
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[..2].copy_from_slice(&(self.cursor_position as u16).to_be_bytes());
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+    }
+
+    /// Asks the server to dig the tile of the local character (or to open the chest on it),
+    /// only during the local player turn. The server answers with the dig result, applied to the game state.
+    fn dig(&mut self) {
+
+        let game_state_mutex_guard = self.game_state.lock().unwrap();
+        let game_state = &*game_state_mutex_guard;
+
+        let local_player = match game_state.get_local_player() {
+            Some(local_player) if game_state.can_local_player_act() => local_player,
+            _ => return,
+        };
+
+        let position = self.characters[local_player].get_position() as u16;
+
+        let mut message = Message::new(MESSAGE_ACTION_DIG);
+
+        const MESSAGE_DATA_LENGTH: usize = 32;
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[..2].copy_from_slice(&position.to_be_bytes());
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+    }
+
+    /// Moves the camera into the given direction, at most once every interval
+    /// to keep the same speed whatever the input device is.
+    ///
+    /// # Args:
+    ///
+    /// `direction` - the direction of the camera movement
+    fn move_camera(
+        &mut self,
+        direction: Direction,
+    ) {
+
+        const CAMERA_MOVEMENT_INTERVAL: u128 = 25;
+        if Instant::now().duration_since(self.event_previous_time).as_millis() <=
+            CAMERA_MOVEMENT_INTERVAL {
+            return;
+        }
+
+        const CAMERA_MOVEMENT_OFFSET: f64 = 10.0;
+        match direction {
+            Direction::Up => self.origin_vertical_position += CAMERA_MOVEMENT_OFFSET,
+            Direction::Down => self.origin_vertical_position -= CAMERA_MOVEMENT_OFFSET,
+            Direction::Left => self.origin_horizontal_position += CAMERA_MOVEMENT_OFFSET,
+            Direction::Right => self.origin_horizontal_position -= CAMERA_MOVEMENT_OFFSET,
+        };
+
+        self.event_previous_time = Instant::now();
+    }
+
+    /// Makes the spectator camera follow the next or the previous character.
+    ///
+    /// # Args:
+    ///
+    /// `forward` - true to follow the next character, false for the previous one
+    fn follow_next_character(
+        &mut self,
+        forward: bool,
+    ) {
+        self.game_state.lock().unwrap().cycle_followed_player(forward);
+        self.camera_following = true;
+    }
+
+    /// Moves the camera so the followed character stands at the center of the window.
+    fn center_camera_on_followed_character(&mut self) {
+
+        let followed_player = self.game_state.lock().unwrap().get_followed_player();
+        let (
+            horizontal_position,
+            vertical_position,
+        ) = get_tile_screen_position(
+            self.characters[followed_player].get_position(),
+            0.0,
+            0.0,
+        );
+
+        /* from the top left corner of the tile sprite to the center of its diamond */
+        const TILE_CENTER_HORIZONTAL_OFFSET: f64 = 70.0;
+        const TILE_CENTER_VERTICAL_OFFSET: f64 = 100.0;
+
+        let [window_width, window_height] = self.window_size;
+        self.origin_horizontal_position = window_width / 2.0 - horizontal_position - TILE_CENTER_HORIZONTAL_OFFSET;
+        self.origin_vertical_position = window_height / 2.0 - vertical_position - TILE_CENTER_VERTICAL_OFFSET;
+    }
+
+    /// Moves the tile cursor by one tile into the given direction; the cursor cannot leave the map.
+    ///
+    /// # Args:
+    ///
+    /// `direction` - the direction of the cursor movement
+    fn move_cursor(
+        &mut self,
+        direction: Direction,
+    ) {
+
+        const TILES_PER_LINE: usize = 20;
+        let line = self.cursor_position / TILES_PER_LINE;
+        let column = self.cursor_position % TILES_PER_LINE;
+
+        /* on the isometric projection, lines go from the top left to the bottom right
+           and columns go from the top right to the bottom left */
+        const LAST_INDEX: usize = TILES_PER_LINE - 1;
+        match direction {
+            Direction::Up if line > 0 => self.cursor_position -= TILES_PER_LINE,
+            Direction::Down if line < LAST_INDEX => self.cursor_position += TILES_PER_LINE,
+            Direction::Left if column < LAST_INDEX => self.cursor_position += 1,
+            Direction::Right if column > 0 => self.cursor_position -= 1,
+            _ => {}
+        };
+    }
+}
@@ -0,0 +1,507 @@
+//! Structure and implementation of the game state, filled by the server messages during the game.
+
+use crate::turn::Turn;
+use crate::game_result::GameResult;
+use crate::visibility::Visibility;
+use protocol::get_string_from_bytes;
+use crate::skins::SKINS_AMOUNT;
+
+use std::array::from_fn;
+use std::time::Instant;
+
+const TILES_AMOUNT: usize = 400;
+pub const PLAYERS_AMOUNT: usize = 2;
+
+/// Chest currently opening, animated for a short time after the server revealed it.
+pub struct ChestOpening {
+    position: usize,
+    start: Instant,
+}
+
+impl ChestOpening {
+
+    /// Returns the index of the tile of the chest.
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the moment the chest started to open.
+    pub fn get_start(&self) -> Instant {
+        self.start
+    }
+}
+
+pub struct GameState {
+
+    /* none for a spectator */
+    local_player: Option<usize>,
+
+    /* the character the camera follows: the local one for a player, chosen by a spectator */
+    followed_player: usize,
+
+    usernames: [String; PLAYERS_AMOUNT],
+    skins: [usize; PLAYERS_AMOUNT],
+    dug_tiles: [bool; TILES_AMOUNT],
+    chest_openings: Vec<ChestOpening>,
+    treasures: [u8; PLAYERS_AMOUNT],
+    turn: Option<Turn>,
+    visibility: Visibility,
+    character_positions: [usize; PLAYERS_AMOUNT],
+    result: Option<GameResult>,
+}
+
+impl Default for GameState {
+
+    fn default() -> GameState {
+        GameState::new()
+    }
+}
+
+impl GameState {
+
+    pub fn new() -> GameState {
+        GameState {
+            local_player: Some(0),
+            followed_player: 0,
+            usernames: Default::default(),
+
+            /* every character has its own skin until the server sends the chosen ones */
+            skins: from_fn(|index| index % SKINS_AMOUNT),
+
+            dug_tiles: [false; TILES_AMOUNT],
+            chest_openings: Vec::new(),
+            treasures: [0; PLAYERS_AMOUNT],
+            turn: None,
+            visibility: Visibility::new(),
+
+            /* FIXME: the characters start at fixed positions until the server sends their first move */
+            character_positions: [38, 361],
+            result: None,
+        }
+    }
+
+    /// Resets the state for a new game.
+    ///
+    /// The data contains the index of the character controlled by this client (255 for a spectator),
+    /// then the username of every player on 32 bytes (padded with zeros), ordered by character,
+    /// then the index of the skin chosen by every player on one byte, ordered by character.
+    /// Returns false if the message is invalid (unknown player index), the state is left unchanged in that case.
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the start game message
+    pub fn start(
+        &mut self,
+        data: &[u8],
+    ) -> bool {
+
+        const SPECTATOR: u8 = 255;
+        let player = data[0] as usize;
+        if data[0] != SPECTATOR && player >= PLAYERS_AMOUNT {
+            return false;
+        }
+
+        *self = GameState::new();
+
+        if data[0] == SPECTATOR {
+            self.local_player = None;
+        } else {
+            self.local_player = Some(player);
+            self.followed_player = player;
+        }
+
+        const USERNAMES_OFFSET: usize = 1;
+        const USERNAME_LENGTH: usize = 32;
+        let usernames = data[USERNAMES_OFFSET..].chunks_exact(USERNAME_LENGTH);
+
+        for (index, username) in usernames.take(PLAYERS_AMOUNT).enumerate() {
+            self.usernames[index] = get_string_from_bytes(username);
+        }
+
+        const SKINS_OFFSET: usize = USERNAMES_OFFSET + PLAYERS_AMOUNT * USERNAME_LENGTH;
+        for (index, skin) in data[SKINS_OFFSET..SKINS_OFFSET + PLAYERS_AMOUNT].iter().enumerate() {
+
+            /* unknown skins are ignored, the character keeps the default one */
+            if (*skin as usize) < SKINS_AMOUNT {
+                self.skins[index] = *skin as usize;
+            }
+        }
+
+        true
+    }
+
+    /// Applies a dig result sent by the server.
+    ///
+    /// The data contains the index of the player who dug, the index of the dug tile on two bytes (big endian),
+    /// the dig result (0 for an empty hole, 1 when a treasure chest is opened)
+    /// and the new amount of treasures of the player.
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the dig result message
+    pub fn apply_dig_result(
+        &mut self,
+        data: &[u8],
+    ) {
+
+        let player = data[0] as usize;
+        let position = u16::from_be_bytes([data[1], data[2]]) as usize;
+
+        if player >= PLAYERS_AMOUNT ||
+            position >= TILES_AMOUNT {
+            return;
+        }
+
+        self.dug_tiles[position] = true;
+
+        const DIG_RESULT_TREASURE: u8 = 1;
+        if data[3] == DIG_RESULT_TREASURE {
+            self.chest_openings.push(ChestOpening {
+                position,
+                start: Instant::now(),
+            });
+        }
+
+        self.treasures[player] = data[4];
+    }
+
+    /// Moves a character to the tile sent by the server.
+    ///
+    /// The data contains the index of the player and the index of its new tile on two bytes (big endian).
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the character move message
+    pub fn apply_character_move(
+        &mut self,
+        data: &[u8],
+    ) {
+
+        let player = data[0] as usize;
+        let position = u16::from_be_bytes([data[1], data[2]]) as usize;
+
+        if player >= PLAYERS_AMOUNT ||
+            position >= TILES_AMOUNT {
+            return;
+        }
+
+        self.character_positions[player] = position;
+    }
+
+    /// Returns the index of the tile of every character.
+    pub fn get_character_positions(&self) -> &[usize; PLAYERS_AMOUNT] {
+        &self.character_positions
+    }
+
+    /// Replaces the current turn by the one sent by the server, unless its player is unknown.
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the turn message
+    pub fn set_turn(
+        &mut self,
+        data: &[u8],
+    ) {
+        /* a turn with an unknown player is ignored, the previous one stays displayed */
+        if let Some(turn) = Turn::from_bytes(data) {
+            self.turn = Some(turn);
+        }
+    }
+
+    /// Returns the current turn, none until the server sends the first one.
+    pub fn get_turn(&self) -> Option<&Turn> {
+        self.turn.as_ref()
+    }
+
+    /// Indicates if the local player can act: it is its turn and it has remaining actions and time.
+    /// Always true if the server does not play by turns (no turn received); never true for a spectator.
+    pub fn can_local_player_act(&self) -> bool {
+
+        let local_player = match self.local_player {
+            Some(local_player) => local_player,
+            None => return false,
+        };
+
+        match &self.turn {
+            Some(turn) => turn.get_player() == local_player &&
+                turn.get_remaining_actions() > 0 &&
+                turn.get_remaining_seconds() > 0,
+            None => true,
+        }
+    }
+
+    /// Recomputes the tiles in sight of the local character; a spectator sees the whole map.
+    ///
+    /// # Args:
+    ///
+    /// `local_character_position` - the index of the tile of the local character
+    pub fn update_visibility(
+        &mut self,
+        local_character_position: usize,
+    ) {
+
+        if self.is_spectating() {
+            self.visibility.reveal_all();
+            return;
+        }
+
+        const SIGHT_RADIUS: usize = 4;
+        self.visibility.update(
+            local_character_position,
+            SIGHT_RADIUS,
+        );
+    }
+
+    /// Returns the visibility of the tiles for the local player.
+    pub fn get_visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    /// Ends the game with the result sent by the server.
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the game over message
+    pub fn finish(
+        &mut self,
+        data: &[u8],
+    ) {
+        self.turn = None;
+        self.result = Some(GameResult::from_bytes(data));
+    }
+
+    /// Returns the result of the game, none until the game is over.
+    pub fn get_result(&self) -> Option<&GameResult> {
+        self.result.as_ref()
+    }
+
+    /// Removes the chests openings that are over.
+    ///
+    /// # Args:
+    ///
+    /// `duration` - the duration of the opening animation, in milliseconds
+    pub fn remove_finished_chest_openings(
+        &mut self,
+        duration: u128,
+    ) {
+        self.chest_openings.retain(|opening| opening.start.elapsed().as_millis() < duration);
+    }
+
+    /// Returns the index of the character controlled by this client, none for a spectator.
+    pub fn get_local_player(&self) -> Option<usize> {
+        self.local_player
+    }
+
+    /// Indicates if this client only watches the game.
+    pub fn is_spectating(&self) -> bool {
+        self.local_player.is_none()
+    }
+
+    /// Returns the index of the character followed by the camera.
+    pub fn get_followed_player(&self) -> usize {
+        self.followed_player
+    }
+
+    /// Makes a spectator follow the next or the previous character; players always follow their own one.
+    ///
+    /// # Args:
+    ///
+    /// `forward` - true to follow the next character, false for the previous one
+    pub fn cycle_followed_player(
+        &mut self,
+        forward: bool,
+    ) {
+
+        if !self.is_spectating() {
+            return;
+        }
+
+        self.followed_player = if forward {
+            (self.followed_player + 1) % PLAYERS_AMOUNT
+        } else {
+            (self.followed_player + PLAYERS_AMOUNT - 1) % PLAYERS_AMOUNT
+        };
+    }
+
+    /// Returns the username of the given player; a default name if the server did not send it.
+    ///
+    /// # Args:
+    ///
+    /// `player` - the index of the player
+    pub fn get_username(
+        &self,
+        player: usize,
+    ) -> String {
+
+        if self.usernames[player].is_empty() {
+            return format!("Player {}", player + 1);
+        }
+
+        self.usernames[player].clone()
+    }
+
+    /// Returns the index of the skin of every character.
+    pub fn get_skins(&self) -> &[usize; PLAYERS_AMOUNT] {
+        &self.skins
+    }
+
+    /// Returns the dug status of every tile.
+    pub fn get_dug_tiles(&self) -> &[bool; TILES_AMOUNT] {
+        &self.dug_tiles
+    }
+
+    /// Returns the chests currently opening.
+    pub fn get_chest_openings(&self) -> &[ChestOpening] {
+        &self.chest_openings
+    }
+
+    /// Returns the amount of treasures found by every player.
+    pub fn get_treasures(&self) -> &[u8; PLAYERS_AMOUNT] {
+        &self.treasures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::visibility::TileVisibility;
+
+    /// Returns the data of a start game message for the given player index.
+    ///
+    /// # Args:
+    ///
+    /// `player` - the index of the local player (255 for a spectator)
+    fn get_start_data(player: u8) -> [u8; 400] {
+        let mut data = [0; 400];
+        data[0] = player;
+        data[1..5].copy_from_slice(b"JACK");
+        data[33..37].copy_from_slice(b"ANNE");
+        data
+    }
+
+    #[test]
+    fn test_start_sets_the_local_player() {
+
+        let mut game_state = GameState::new();
+
+        assert!(game_state.start(&get_start_data(1)));
+        assert_eq!(game_state.get_followed_player(), 1);
+        assert_eq!(game_state.get_username(0), "JACK");
+    }
+
+    #[test]
+    fn test_start_ignores_unknown_player() {
+
+        let mut game_state = GameState::new();
+        game_state.start(&get_start_data(0));
+
+        assert!(!game_state.start(&get_start_data(2)));
+        assert!(!game_state.start(&get_start_data(254)));
+        assert_eq!(game_state.get_followed_player(), 0);
+    }
+
+    #[test]
+    fn test_dig_result_marks_the_tile_and_opens_the_chest() {
+
+        let mut game_state = GameState::new();
+
+        /* an empty hole */
+        game_state.apply_dig_result(&[0, 0, 21, 0, 0]);
+
+        assert!(game_state.get_dug_tiles()[21]);
+        assert!(game_state.get_chest_openings().is_empty());
+        assert_eq!(game_state.get_treasures(), &[0, 0]);
+
+        /* a chest on the tile 300 */
+        game_state.apply_dig_result(&[1, 1, 44, 1, 3]);
+
+        assert!(game_state.get_dug_tiles()[300]);
+        assert_eq!(game_state.get_chest_openings().len(), 1);
+        assert_eq!(game_state.get_chest_openings()[0].get_position(), 300);
+        assert_eq!(game_state.get_treasures(), &[0, 3]);
+    }
+
+    #[test]
+    fn test_dig_result_ignores_unknown_player_and_tile() {
+
+        let mut game_state = GameState::new();
+
+        game_state.apply_dig_result(&[PLAYERS_AMOUNT as u8, 0, 21, 1, 5]);
+        game_state.apply_dig_result(&[0, 1, 144, 1, 5]);
+
+        assert!(game_state.get_dug_tiles().iter().all(|dug| !dug));
+        assert!(game_state.get_chest_openings().is_empty());
+        assert_eq!(game_state.get_treasures(), &[0, 0]);
+    }
+
+    #[test]
+    fn test_local_player_acts_only_during_its_turn() {
+
+        let mut game_state = GameState::new();
+        game_state.start(&get_start_data(1));
+
+        /* without any turn, the server does not play by turns */
+        assert!(game_state.can_local_player_act());
+
+        game_state.set_turn(&[0, 0, 1, 2, 0, 30]);
+        assert!(!game_state.can_local_player_act());
+
+        game_state.set_turn(&[1, 0, 1, 2, 0, 30]);
+        assert!(game_state.can_local_player_act());
+
+        /* no action left */
+        game_state.set_turn(&[1, 0, 1, 0, 0, 30]);
+        assert!(!game_state.can_local_player_act());
+
+        /* no time left */
+        game_state.set_turn(&[1, 0, 1, 2, 0, 0]);
+        assert!(!game_state.can_local_player_act());
+
+        /* a turn of an unknown player keeps the previous one */
+        game_state.set_turn(&[1, 0, 1, 2, 0, 30]);
+        game_state.set_turn(&[PLAYERS_AMOUNT as u8, 0, 2, 2, 0, 30]);
+        assert_eq!(game_state.get_turn().unwrap().get_number(), 1);
+        assert!(game_state.can_local_player_act());
+    }
+
+    #[test]
+    fn test_spectator_never_acts() {
+
+        let mut game_state = GameState::new();
+        game_state.start(&get_start_data(255));
+
+        assert!(!game_state.can_local_player_act());
+
+        game_state.set_turn(&[0, 0, 1, 2, 0, 30]);
+        assert!(!game_state.can_local_player_act());
+    }
+
+    #[test]
+    fn test_characters_have_their_own_default_skins() {
+
+        let skins = GameState::new().get_skins().to_vec();
+
+        for (index, skin) in skins.iter().enumerate() {
+            assert_eq!(*skin, index % SKINS_AMOUNT);
+        }
+    }
+
+    #[test]
+    fn test_local_character_sees_around_it() {
+
+        let mut game_state = GameState::new();
+        game_state.start(&get_start_data(0));
+        game_state.update_visibility(210);
+
+        let visibility = game_state.get_visibility();
+        assert!(visibility.get_tile_visibility(214) == TileVisibility::Visible);
+        assert!(visibility.get_tile_visibility(215) == TileVisibility::Unexplored);
+
+        /* a spectator is not restricted to the sight of a character */
+        game_state.start(&get_start_data(255));
+        game_state.update_visibility(210);
+
+        assert!(game_state.get_visibility().get_tile_visibility(0) == TileVisibility::Visible);
+    }
+}
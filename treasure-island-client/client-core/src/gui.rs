@@ -0,0 +1,1091 @@
+//! Handles GUI actions.
+
+use crate::character::Character;
+use crate::game_state::{
+    GameState,
+    ChestOpening,
+};
+use crate::visibility::TileVisibility;
+use crate::shoreline::{
+    SHORE_UPPER_LEFT,
+    SHORE_BOTTOM_LEFT,
+    SHORE_BOTTOM_RIGHT,
+    SHORE_EDGES,
+    SHORE_TOP_CORNER,
+    SHORE_LEFT_CORNER,
+    SHORE_BOTTOM_CORNER,
+    SHORE_CORNERS,
+    get_shore_edges,
+    get_shore_sprite,
+    get_shore_corner_sprite,
+    is_water_tile,
+};
+use crate::renderer::Renderer;
+
+use piston_window::Transformed;
+
+/// Display the sprites. Called only once but refactored into a function for readability. Optimized
+/// to not render the sprites if outside of the camera viewport. Water tiles touching the land
+/// are displayed with the shoreline along the touching edges.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the selector
+/// `transform` - transformation to apply on the selector when drawing
+/// `all_sprites` - the list of all availables sprites
+/// `sprites` - the displayed sprites list
+/// `game_state` - the game state; dug tiles are displayed with a hole, unexplored tiles are hidden
+///                and tiles out of sight are dimmed
+/// `origin_horizontal_position` - the origin horizontal position
+/// `origin_vertical_position` - the origin vertical position
+pub fn display_sprites(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    all_sprites: &[&str],
+    sprites: &[u8; 400],
+    game_state: &GameState,
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) {
+
+    let mut column: usize = 0;
+    let mut line: usize = 0;
+
+    let shore_edges = get_shore_edges(sprites);
+
+    for (index, tile) in sprites.iter().enumerate() {
+
+        const TILES_PER_LINE: usize = 20;
+
+        if index != 0 &&
+            index % TILES_PER_LINE == 0 {
+            column = 0;
+            line += 1;
+        }
+
+        const WINDOW_WIDTH: f64 = 1920.0;
+        const WINDOW_HEIGHT: f64 = 1080.0;
+
+        const TILE_HORIZONTAL_OFFSET: f64 = -75.0;
+        const TILE_VERTICAL_OFFSET: f64 = -25.0;
+
+        const TILE_HORIZONTAL_DISTANCE: f64 = 69.0;
+        let sprite_horizontal_position = TILE_HORIZONTAL_OFFSET -
+            TILE_HORIZONTAL_DISTANCE * (column as f64) +
+            TILE_HORIZONTAL_DISTANCE * (line as f64) +
+            origin_horizontal_position;
+
+        const TILE_WIDTH: f64 = 140.0;
+        if !(-TILE_WIDTH..=WINDOW_WIDTH).contains(&sprite_horizontal_position) {
+            column += 1;
+            continue;
+        }
+
+        const TILE_VERTICAL_DISTANCE: f64 = 31.0;
+        let sprite_vertical_position = TILE_VERTICAL_OFFSET +
+            TILE_VERTICAL_DISTANCE * (column as f64) +
+            TILE_VERTICAL_DISTANCE * (line as f64) +
+            origin_vertical_position;
+
+        const TILE_HEIGHT: f64 = 140.0;
+        if !(-TILE_HEIGHT..=WINDOW_HEIGHT).contains(&sprite_vertical_position) {
+            column += 1;
+            continue;
+        }
+
+        let tile_visibility = game_state.get_visibility().get_tile_visibility(index);
+
+        if tile_visibility == TileVisibility::Unexplored {
+
+            const FOG_COLOR: [f32; 4] = [0.15, 0.15, 0.2, 1.0];
+            display_tile_diamond(
+                renderer,
+                transform,
+                FOG_COLOR,
+                sprite_horizontal_position,
+                sprite_vertical_position,
+            );
+
+            column += 1;
+            continue;
+        }
+
+        /* sprites are all identified by a u8 number,
+           as we do not want to store arrays of usize types
+           more used to index others arrays;
+           but here, we have to retrieve the tile image
+           from the sprites array based on its index;
+           so we are required to convert it as usize only here */
+        let mut sprite_index = *tile as usize;
+
+        let dug = game_state.get_dug_tiles()[index];
+
+        /* an opened chest leaves a simple hole into the sand */
+        const CHEST_SPRITE_INDEX: usize = 9;
+        const SAND_SPRITE_INDEX: usize = 0;
+        if dug && sprite_index == CHEST_SPRITE_INDEX {
+            sprite_index = SAND_SPRITE_INDEX;
+        }
+
+        const VISIBLE_TILE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+        const EXPLORED_TILE_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+        let tile_color = if tile_visibility == TileVisibility::Visible {
+            VISIBLE_TILE_COLOR
+        } else {
+            EXPLORED_TILE_COLOR
+        };
+
+        if is_water_tile(*tile) {
+
+            display_water(
+                renderer,
+                &transform.trans(
+                    sprite_horizontal_position,
+                    sprite_vertical_position
+                ),
+                all_sprites,
+                shore_edges[index],
+                tile_color,
+            );
+
+            column += 1;
+            continue;
+        }
+
+        renderer.draw_image(
+            all_sprites[sprite_index],
+            tile_color,
+            None,
+            transform.trans(
+                sprite_horizontal_position,
+                sprite_vertical_position
+            ),
+        );
+
+        if dug {
+
+            const HOLE_COLOR: [f32; 4] = [0.35, 0.25, 0.1, 0.8];
+            const HOLE_HORIZONTAL_OFFSET: f64 = 45.0;
+            const HOLE_VERTICAL_OFFSET: f64 = 88.0;
+            const HOLE_WIDTH: f64 = 50.0;
+            const HOLE_HEIGHT: f64 = 24.0;
+            renderer.draw_ellipse(
+                HOLE_COLOR,
+                [
+                    HOLE_HORIZONTAL_OFFSET,
+                    HOLE_VERTICAL_OFFSET,
+                    HOLE_WIDTH,
+                    HOLE_HEIGHT,
+                ],
+                transform.trans(
+                    sprite_horizontal_position,
+                    sprite_vertical_position
+                ),
+            );
+        }
+
+        column += 1;
+    }
+}
+
+/// Display all the characters sprites, standing on a disc of the color of their player.
+/// Called only once but refactored into a function for readability.
+///
+/// `renderer` - the renderer drawing the selector
+/// `transform` - transformation to apply on the selector when drawing
+/// `all_skins` - the sprites of all the skins
+/// `all_characters` - the list of characters
+/// `game_state` - the game state; the local character is surrounded by a white ring,
+///                the character playing the current turn is highlighted by a golden ring,
+///                the other players characters out of sight are hidden
+/// `origin_horizontal_position` - the origin horizontal position
+/// `origin_vertical_position` - the origin vertical position
+pub fn display_characters(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    all_skins: &[&str],
+    all_characters: &[Character; 2],
+    game_state: &GameState,
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) {
+
+    let active_character = game_state.get_turn().map(|turn| turn.get_player());
+
+    for (index, character) in all_characters.iter().enumerate() {
+
+        if !is_character_visible(
+            game_state,
+            index,
+            character.get_position(),
+        ) {
+            continue;
+        }
+
+        let (
+            horizontal_position,
+            vertical_position,
+        ) = get_tile_screen_position(
+            character.get_position(),
+            origin_horizontal_position,
+            origin_vertical_position,
+        );
+
+        const RING_HORIZONTAL_OFFSET: f64 = 25.0;
+        const RING_VERTICAL_OFFSET: f64 = 80.0;
+        const RING_WIDTH: f64 = 90.0;
+        const RING_HEIGHT: f64 = 40.0;
+        const RING: [f64; 4] = [
+            RING_HORIZONTAL_OFFSET,
+            RING_VERTICAL_OFFSET,
+            RING_WIDTH,
+            RING_HEIGHT,
+        ];
+
+        let character_transform = transform.trans(
+            horizontal_position,
+            vertical_position,
+        );
+
+        const DISC_ALPHA: f32 = 0.6;
+        let mut disc_color = get_player_color(index);
+        disc_color[3] = DISC_ALPHA;
+        renderer.draw_ellipse(
+            disc_color,
+            RING,
+            character_transform,
+        );
+
+        if game_state.get_local_player() == Some(index) {
+
+            const LOCAL_RING_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+            const LOCAL_RING_BORDER_RADIUS: f64 = 1.5;
+            const LOCAL_RING_MARGIN: f64 = 5.0;
+            renderer.draw_ellipse_border(
+                LOCAL_RING_COLOR,
+                LOCAL_RING_BORDER_RADIUS,
+                [
+                    RING_HORIZONTAL_OFFSET - LOCAL_RING_MARGIN,
+                    RING_VERTICAL_OFFSET - LOCAL_RING_MARGIN,
+                    RING_WIDTH + 2.0 * LOCAL_RING_MARGIN,
+                    RING_HEIGHT + 2.0 * LOCAL_RING_MARGIN,
+                ],
+                character_transform,
+            );
+        }
+
+        if active_character == Some(index) {
+
+            const RING_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+            const RING_BORDER_RADIUS: f64 = 3.0;
+            renderer.draw_ellipse_border(
+                RING_COLOR,
+                RING_BORDER_RADIUS,
+                RING,
+                character_transform,
+            );
+        }
+
+        const SKIN_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+        renderer.draw_image(
+            all_skins[character.get_skin()],
+            SKIN_COLOR,
+            None,
+            character_transform,
+        );
+    }
+}
+
+/// Display the username of every visible character above its sprite, on a dark plate for readability.
+/// Labels that would cover already displayed ones are moved up, so they stay readable when characters overlap.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the labels
+/// `transform` - transformation to apply on the labels when drawing
+/// `all_characters` - the list of characters
+/// `game_state` - the game state, the local player label is surrounded by a white border
+/// `origin_horizontal_position` - the origin horizontal position
+/// `origin_vertical_position` - the origin vertical position
+pub fn display_characters_labels(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    all_characters: &[Character; 2],
+    game_state: &GameState,
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) {
+
+    const LABEL_FONT_SIZE: u32 = 24;
+    const LABEL_PADDING: f64 = 6.0;
+    const LABEL_HEIGHT: f64 = LABEL_FONT_SIZE as f64 + 2.0 * LABEL_PADDING;
+    const LABELS_SPACING: f64 = 4.0;
+
+    /* the label is centered above the character sprite */
+    const LABEL_HORIZONTAL_CENTER: f64 = 70.0;
+    const LABEL_BOTTOM: f64 = 50.0;
+
+    const PLATE_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+    const LOCAL_BORDER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+    const LOCAL_BORDER_RADIUS: f64 = 1.0;
+
+    let mut displayed_labels: Vec<[f64; 4]> = Vec::new();
+
+    for (index, character) in all_characters.iter().enumerate() {
+
+        if !is_character_visible(
+            game_state,
+            index,
+            character.get_position(),
+        ) {
+            continue;
+        }
+
+        let (
+            horizontal_position,
+            vertical_position,
+        ) = get_tile_screen_position(
+            character.get_position(),
+            origin_horizontal_position,
+            origin_vertical_position,
+        );
+
+        let username = game_state.get_username(index);
+        let label_width = renderer.get_text_width(LABEL_FONT_SIZE, &username) + 2.0 * LABEL_PADDING;
+
+        let mut label = [
+            horizontal_position + LABEL_HORIZONTAL_CENTER - label_width / 2.0,
+            vertical_position + LABEL_BOTTOM - LABEL_HEIGHT,
+            label_width,
+            LABEL_HEIGHT,
+        ];
+
+        while let Some(covered_label) = displayed_labels.iter().find(|displayed_label| {
+            are_rectangles_overlapping(&label, displayed_label)
+        }) {
+            label[1] = covered_label[1] - LABEL_HEIGHT - LABELS_SPACING;
+        }
+
+        renderer.draw_rectangle(
+            PLATE_COLOR,
+            label,
+            *transform,
+        );
+
+        if game_state.get_local_player() == Some(index) {
+            renderer.draw_rectangle_border(
+                LOCAL_BORDER_COLOR,
+                LOCAL_BORDER_RADIUS,
+                label,
+                *transform,
+            );
+        }
+
+        renderer.draw_text(
+            get_player_color(index),
+            LABEL_FONT_SIZE,
+            &username,
+            transform.trans(
+                label[0] + LABEL_PADDING,
+                label[1] + LABEL_PADDING + LABEL_FONT_SIZE as f64,
+            ),
+        );
+
+        displayed_labels.push(label);
+    }
+}
+
+/// Indicates if two rectangles overlap.
+///
+/// # Args:
+///
+/// `first` - the first rectangle, as [x, y, width, height]
+/// `second` - the second rectangle, as [x, y, width, height]
+fn are_rectangles_overlapping(
+    first: &[f64; 4],
+    second: &[f64; 4],
+) -> bool {
+    first[0] < second[0] + second[2] &&
+        second[0] < first[0] + first[2] &&
+        first[1] < second[1] + second[3] &&
+        second[1] < first[1] + first[3]
+}
+
+/// Display the chests being opened: the chest rises and fades out into a growing golden glow.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the chests
+/// `transform` - transformation to apply on the chests when drawing
+/// `chest_sprite` - the sprite of a chest
+/// `openings` - the chests being opened
+/// `duration` - the duration of the opening animation, in milliseconds
+/// `origin_horizontal_position` - the origin horizontal position
+/// `origin_vertical_position` - the origin vertical position
+pub fn display_chest_openings(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    chest_sprite: &str,
+    openings: &[ChestOpening],
+    duration: u128,
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) {
+
+    for opening in openings.iter() {
+
+        let (
+            horizontal_position,
+            vertical_position,
+        ) = get_tile_screen_position(
+            opening.get_position(),
+            origin_horizontal_position,
+            origin_vertical_position,
+        );
+
+        let progress = (opening.get_start().elapsed().as_millis() as f64 / duration as f64).min(1.0);
+
+        const GLOW_CENTER_HORIZONTAL_OFFSET: f64 = 70.0;
+        const GLOW_CENTER_VERTICAL_OFFSET: f64 = 90.0;
+        const GLOW_MAX_RADIUS: f64 = 80.0;
+        let glow_radius = GLOW_MAX_RADIUS * progress;
+        let glow_color: [f32; 4] = [1.0, 0.85, 0.0, (1.0 - progress) as f32];
+        renderer.draw_ellipse(
+            glow_color,
+            [
+                GLOW_CENTER_HORIZONTAL_OFFSET - glow_radius,
+                GLOW_CENTER_VERTICAL_OFFSET - glow_radius / 2.0,
+                glow_radius * 2.0,
+                glow_radius,
+            ],
+            transform.trans(
+                horizontal_position,
+                vertical_position,
+            ),
+        );
+
+        const CHEST_RISE_HEIGHT: f64 = 60.0;
+        renderer.draw_image(
+            chest_sprite,
+            [1.0, 1.0, 1.0, (1.0 - progress) as f32],
+            None,
+            transform.trans(
+                horizontal_position,
+                vertical_position - CHEST_RISE_HEIGHT * progress,
+            ),
+        );
+    }
+}
+
+/// Display the tile cursor as a translucent diamond over the selected tile.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the cursor
+/// `transform` - transformation to apply on the cursor when drawing
+/// `cursor_position` - the index of the selected tile
+/// `origin_horizontal_position` - the origin horizontal position
+/// `origin_vertical_position` - the origin vertical position
+pub fn display_cursor(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    cursor_position: usize,
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) {
+
+    let (
+        horizontal_position,
+        vertical_position,
+    ) = get_tile_screen_position(
+        cursor_position,
+        origin_horizontal_position,
+        origin_vertical_position,
+    );
+
+    const CURSOR_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.4];
+    display_tile_diamond(
+        renderer,
+        transform,
+        CURSOR_COLOR,
+        horizontal_position,
+        vertical_position,
+    );
+}
+
+/// Display a water tile with a sand strip along every edge touching the land. A tile with one shore
+/// uses the matching sand and water link sprite; a corner is built from one quarter of sprite per edge,
+/// as every edge of the tile diamond is contained into one quarter of the sprite.
+/// The corners only touching the land get the tip of a sand and water link sprite.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the tile
+/// `transform` - transformation to apply on the tile when drawing, already moved to the tile position
+/// `all_sprites` - the list of all availables sprites
+/// `shore_edges` - the shore edges of the tile
+/// `color` - the color to multiply the sprites with
+fn display_water(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    all_sprites: &[&str],
+    shore_edges: u8,
+    color: [f32; 4],
+) {
+
+    const WATER_SPRITE_INDEX: usize = 10;
+
+    let edges = SHORE_EDGES.iter().fold(0, |edges, edge| edges | (shore_edges & edge));
+
+    if edges == 0 || edges.count_ones() == 1 {
+
+        let sprite_index = if edges == 0 {
+            WATER_SPRITE_INDEX
+        } else {
+            get_shore_sprite(edges)
+        };
+
+        renderer.draw_image(
+            all_sprites[sprite_index],
+            color,
+            None,
+            *transform,
+        );
+    }
+    else {
+        display_water_quarters(
+            renderer,
+            transform,
+            all_sprites,
+            edges,
+            color,
+        );
+    }
+
+    /* the tips of the diamond into the sprite, from the corner to the sprite border */
+    const CORNER_TIP_WIDTH: f64 = 42.0;
+    const CORNER_TIP_HEIGHT: f64 = 20.0;
+    const TOP_CORNER_TIP: [f64; 4] = [49.0, 0.0, CORNER_TIP_WIDTH, 88.0];
+    const LEFT_CORNER_TIP: [f64; 4] = [0.0, 98.0, CORNER_TIP_WIDTH / 2.0, CORNER_TIP_HEIGHT];
+    const BOTTOM_CORNER_TIP: [f64; 4] = [49.0, 128.0, CORNER_TIP_WIDTH, 12.0];
+    const RIGHT_CORNER_TIP: [f64; 4] = [119.0, 98.0, CORNER_TIP_WIDTH / 2.0, CORNER_TIP_HEIGHT];
+
+    for corner in SHORE_CORNERS.iter().filter(|corner| shore_edges & *corner != 0) {
+
+        let tip = match *corner {
+            SHORE_TOP_CORNER => TOP_CORNER_TIP,
+            SHORE_LEFT_CORNER => LEFT_CORNER_TIP,
+            SHORE_BOTTOM_CORNER => BOTTOM_CORNER_TIP,
+            /* right */
+            _ => RIGHT_CORNER_TIP,
+        };
+
+        renderer.draw_image(
+            all_sprites[get_shore_corner_sprite(*corner)],
+            color,
+            Some(tip),
+            *transform,
+        );
+    }
+}
+
+/// Display a water tile with a sand strip along several edges, from one quarter of sprite per edge.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the tile
+/// `transform` - transformation to apply on the tile when drawing, already moved to the tile position
+/// `all_sprites` - the list of all availables sprites
+/// `edges` - the shore edges of the tile, without the corners
+/// `color` - the color to multiply the sprites with
+fn display_water_quarters(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    all_sprites: &[&str],
+    edges: u8,
+    color: [f32; 4],
+) {
+
+    const WATER_SPRITE_INDEX: usize = 10;
+
+    /* the top face of a tile is a diamond centered into the bottom part of the sprite */
+    const DIAMOND_CENTER_HORIZONTAL_OFFSET: f64 = 70.0;
+    const DIAMOND_CENTER_VERTICAL_OFFSET: f64 = 100.0;
+    const SPRITE_SIZE: f64 = 140.0;
+
+    const RIGHT_QUARTER_WIDTH: f64 = SPRITE_SIZE - DIAMOND_CENTER_HORIZONTAL_OFFSET;
+    const BOTTOM_QUARTER_HEIGHT: f64 = SPRITE_SIZE - DIAMOND_CENTER_VERTICAL_OFFSET;
+
+    for edge in SHORE_EDGES.iter() {
+
+        let quarter = match *edge {
+            SHORE_UPPER_LEFT => [
+                0.0,
+                0.0,
+                DIAMOND_CENTER_HORIZONTAL_OFFSET,
+                DIAMOND_CENTER_VERTICAL_OFFSET,
+            ],
+            SHORE_BOTTOM_LEFT => [
+                0.0,
+                DIAMOND_CENTER_VERTICAL_OFFSET,
+                DIAMOND_CENTER_HORIZONTAL_OFFSET,
+                BOTTOM_QUARTER_HEIGHT,
+            ],
+            SHORE_BOTTOM_RIGHT => [
+                DIAMOND_CENTER_HORIZONTAL_OFFSET,
+                DIAMOND_CENTER_VERTICAL_OFFSET,
+                RIGHT_QUARTER_WIDTH,
+                BOTTOM_QUARTER_HEIGHT,
+            ],
+            /* upper right */
+            _ => [
+                DIAMOND_CENTER_HORIZONTAL_OFFSET,
+                0.0,
+                RIGHT_QUARTER_WIDTH,
+                DIAMOND_CENTER_VERTICAL_OFFSET,
+            ],
+        };
+
+        let sprite_index = if edges & edge != 0 {
+            get_shore_sprite(*edge)
+        } else {
+            WATER_SPRITE_INDEX
+        };
+
+        renderer.draw_image(
+            all_sprites[sprite_index],
+            color,
+            Some(quarter),
+            *transform,
+        );
+    }
+}
+
+/// Display the tiles the local character can reach as translucent diamonds.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the tiles
+/// `transform` - transformation to apply on the tiles when drawing
+/// `reachable_tiles` - the reachable status of every tile
+/// `origin_horizontal_position` - the origin horizontal position
+/// `origin_vertical_position` - the origin vertical position
+pub fn display_reachable_tiles(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    reachable_tiles: &[bool; 400],
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) {
+
+    const REACHABLE_TILE_COLOR: [f32; 4] = [0.3, 0.6, 1.0, 0.3];
+
+    for (index, reachable) in reachable_tiles.iter().enumerate() {
+
+        if !reachable {
+            continue;
+        }
+
+        let (
+            horizontal_position,
+            vertical_position,
+        ) = get_tile_screen_position(
+            index,
+            origin_horizontal_position,
+            origin_vertical_position,
+        );
+
+        display_tile_diamond(
+            renderer,
+            transform,
+            REACHABLE_TILE_COLOR,
+            horizontal_position,
+            vertical_position,
+        );
+    }
+}
+
+/// Display the planned path of the local character: one dot per step, the start tile excluded.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the path
+/// `transform` - transformation to apply on the path when drawing
+/// `path` - the tiles of the path, from the character tile to the destination
+/// `origin_horizontal_position` - the origin horizontal position
+/// `origin_vertical_position` - the origin vertical position
+pub fn display_path(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    path: &[usize],
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) {
+
+    /* the dot is centered on the top face of the tile */
+    const DOT_HORIZONTAL_OFFSET: f64 = 60.0;
+    const DOT_VERTICAL_OFFSET: f64 = 94.0;
+    const DOT_WIDTH: f64 = 20.0;
+    const DOT_HEIGHT: f64 = 12.0;
+
+    const PATH_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.9];
+
+    for position in path.iter().skip(1) {
+
+        let (
+            horizontal_position,
+            vertical_position,
+        ) = get_tile_screen_position(
+            *position,
+            origin_horizontal_position,
+            origin_vertical_position,
+        );
+
+        renderer.draw_ellipse(
+            PATH_COLOR,
+            [
+                DOT_HORIZONTAL_OFFSET,
+                DOT_VERTICAL_OFFSET,
+                DOT_WIDTH,
+                DOT_HEIGHT,
+            ],
+            transform.trans(
+                horizontal_position,
+                vertical_position,
+            ),
+        );
+    }
+}
+
+/// Display a plain diamond over the top face of a tile.
+///
+/// # Args:
+///
+/// `renderer` - the renderer drawing the diamond
+/// `transform` - transformation to apply on the diamond when drawing
+/// `color` - the color of the diamond
+/// `horizontal_position` - the horizontal position of the tile sprite
+/// `vertical_position` - the vertical position of the tile sprite
+fn display_tile_diamond(
+    renderer: &mut dyn Renderer,
+    transform: &[[f64; 3]; 2],
+    color: [f32; 4],
+    horizontal_position: f64,
+    vertical_position: f64,
+) {
+
+    /* the top face of a tile is a diamond centered into the bottom part of the sprite */
+    const DIAMOND_CENTER_HORIZONTAL_OFFSET: f64 = 70.0;
+    const DIAMOND_CENTER_VERTICAL_OFFSET: f64 = 100.0;
+    const DIAMOND_HALF_WIDTH: f64 = 69.0;
+    const DIAMOND_HALF_HEIGHT: f64 = 31.0;
+
+    renderer.draw_polygon(
+        color,
+        &[
+            [0.0, -DIAMOND_HALF_HEIGHT],
+            [DIAMOND_HALF_WIDTH, 0.0],
+            [0.0, DIAMOND_HALF_HEIGHT],
+            [-DIAMOND_HALF_WIDTH, 0.0],
+        ],
+        transform.trans(
+            horizontal_position + DIAMOND_CENTER_HORIZONTAL_OFFSET,
+            vertical_position + DIAMOND_CENTER_VERTICAL_OFFSET,
+        ),
+    );
+}
+
+/// Indicates if a character is displayed: the local character always is,
+/// the other ones only when standing on a tile in sight.
+///
+/// # Args:
+///
+/// `game_state` - the game state
+/// `player` - the index of the character
+/// `position` - the index of the tile of the character
+pub fn is_character_visible(
+    game_state: &GameState,
+    player: usize,
+    position: usize,
+) -> bool {
+    game_state.get_local_player() == Some(player) ||
+        game_state.get_visibility().get_tile_visibility(position) == TileVisibility::Visible
+}
+
+/// Returns the screen position of the top left corner of the sprite of the given tile.
+///
+/// # Args:
+///
+/// `position` - the index of the tile
+/// `origin_horizontal_position` - the origin horizontal position
+/// `origin_vertical_position` - the origin vertical position
+pub fn get_tile_screen_position(
+    position: usize,
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) -> (f64, f64) {
+
+    const TILES_PER_LINE: usize = 20;
+    let line = position / TILES_PER_LINE;
+    let column = position % TILES_PER_LINE;
+
+    const TILE_HORIZONTAL_OFFSET: f64 = -75.0;
+    const TILE_HORIZONTAL_DISTANCE: f64 = 69.0;
+    let horizontal_position = TILE_HORIZONTAL_OFFSET -
+        (column as f64) * TILE_HORIZONTAL_DISTANCE +
+        (line as f64) * TILE_HORIZONTAL_DISTANCE +
+        origin_horizontal_position;
+
+    const TILE_VERTICAL_OFFSET: f64 = -25.0;
+    const TILE_VERTICAL_DISTANCE: f64 = 31.0;
+    let vertical_position = TILE_VERTICAL_OFFSET +
+        (column as f64) * TILE_VERTICAL_DISTANCE +
+        (line as f64) * TILE_VERTICAL_DISTANCE +
+        origin_vertical_position;
+
+    (
+        horizontal_position,
+        vertical_position,
+    )
+}
+
+/// Returns the color identifying the given player (HUD, markers...).
+///
+/// # Args:
+///
+/// `player` - the index of the player
+pub fn get_player_color(player: usize) -> [f32; 4] {
+
+    const PLAYERS_COLORS: [[f32; 4]; 4] = [
+        [0.9, 0.2, 0.2, 1.0], /* red */
+        [0.2, 0.4, 0.9, 1.0], /* blue */
+        [0.2, 0.8, 0.2, 1.0], /* green */
+        [0.9, 0.6, 0.1, 1.0], /* orange */
+    ];
+
+    PLAYERS_COLORS[player % PLAYERS_COLORS.len()]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::renderer::Renderer;
+    use crate::software_renderer::SoftwareRenderer;
+
+    use image::RgbaImage;
+
+    use std::env;
+    use std::fs::create_dir_all;
+    use std::path::PathBuf;
+
+    const SPRITES: [&str; 11] = [
+        "sand_1.png",
+        "sand_2.png",
+        "sand_3.png",
+        "sand_4.png",
+        "sand_water_1.png",
+        "sand_water_2.png",
+        "sand_water_3.png",
+        "sand_water_4.png",
+        "palm_1.png",
+        "chest_1.png",
+        "water_1.png",
+    ];
+    const SKINS: [&str; 2] = [
+        "character_1.png",
+        "character_2.png",
+    ];
+
+    const SNAPSHOT_WIDTH: u32 = 880;
+    const SNAPSHOT_HEIGHT: u32 = 480;
+
+    /* the camera shows the 6 x 6 tiles of the top corner of the map */
+    const ORIGIN_HORIZONTAL_POSITION: f64 = 440.0;
+    const ORIGIN_VERTICAL_POSITION: f64 = 20.0;
+
+    /* a pixel is different if one of its channels is; the snapshot is different if too many pixels are */
+    const CHANNEL_TOLERANCE: i16 = 8;
+    const DIFFERENT_PIXELS_TOLERANCE: f64 = 0.001;
+
+    /// Builds a map from lines of characters: '.' for sand (the four variants in turn), 'P' for palm trees,
+    /// 'C' for chests and '~' for water; missing tiles are water.
+    fn get_fixture_map(lines: &[&str]) -> [u8; 400] {
+
+        let mut tiles = [10; 400];
+
+        for (line, content) in lines.iter().enumerate() {
+            for (column, character) in content.chars().enumerate() {
+
+                let index = line * 20 + column;
+                tiles[index] = match character {
+                    '.' => (index % 4) as u8,
+                    'P' => 8,
+                    'C' => 9,
+                    _ => 10,
+                };
+            }
+        }
+
+        tiles
+    }
+
+    /// Returns a game state where the top corner of the map is explored and the island is in sight,
+    /// except its bottom left part that stays into the fog.
+    fn get_fixture_game_state() -> GameState {
+
+        let mut game_state = GameState::new();
+        game_state.update_visibility(0);
+        game_state.update_visibility(44);
+        game_state
+    }
+
+    /// Returns the island map of the snapshots.
+    fn get_island() -> [u8; 400] {
+        get_fixture_map(&[
+            "~~~~~~",
+            "~..P.~",
+            "~.C..~",
+            "~..~.~",
+            "~....~",
+            "~~~~~~",
+        ])
+    }
+
+    /// Compares the image with the golden image of the given name, into the tests golden folder.
+    /// The golden image is (re)written instead when the UPDATE_GOLDEN_IMAGES environment variable is set;
+    /// a different image is written into the target folder to be inspected.
+    ///
+    /// # Args:
+    ///
+    /// `name` - the name of the snapshot
+    /// `image` - the rendered image
+    fn assert_snapshot(
+        name: &str,
+        image: &RgbaImage,
+    ) {
+
+        let golden_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(format!("{}.png", name));
+
+        if env::var_os("UPDATE_GOLDEN_IMAGES").is_some() {
+            image.save(&golden_path).unwrap();
+            return;
+        }
+
+        let golden = image::open(&golden_path)
+            .unwrap_or_else(|_| panic!(
+                "missing golden image {:?}, run the tests with UPDATE_GOLDEN_IMAGES=1 to create it",
+                golden_path,
+            ))
+            .to_rgba8();
+
+        assert_eq!(golden.dimensions(), image.dimensions());
+
+        let different_pixels = golden
+            .pixels()
+            .zip(image.pixels())
+            .filter(|(expected, actual)| {
+                expected.0
+                    .iter()
+                    .zip(actual.0.iter())
+                    .any(|(expected, actual)| (*expected as i16 - *actual as i16).abs() > CHANNEL_TOLERANCE)
+            })
+            .count();
+
+        let pixels = (image.width() * image.height()) as f64;
+        if (different_pixels as f64) / pixels <= DIFFERENT_PIXELS_TOLERANCE {
+            return;
+        }
+
+        let actual_folder = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/snapshots");
+        create_dir_all(&actual_folder).unwrap();
+        let actual_path = actual_folder.join(format!("{}.png", name));
+        image.save(&actual_path).unwrap();
+
+        panic!(
+            "snapshot {} differs from the golden image on {} pixels, see {:?}",
+            name,
+            different_pixels,
+            actual_path,
+        );
+    }
+
+    #[test]
+    fn test_map_snapshot() {
+
+        let mut renderer = SoftwareRenderer::new(SNAPSHOT_WIDTH, SNAPSHOT_HEIGHT);
+        let transform = renderer.get_transform();
+
+        display_sprites(
+            &mut renderer,
+            &transform,
+            &SPRITES,
+            &get_island(),
+            &get_fixture_game_state(),
+            ORIGIN_HORIZONTAL_POSITION,
+            ORIGIN_VERTICAL_POSITION,
+        );
+
+        assert_snapshot("map", renderer.get_image());
+    }
+
+    #[test]
+    fn test_characters_snapshot() {
+
+        let mut renderer = SoftwareRenderer::new(SNAPSHOT_WIDTH, SNAPSHOT_HEIGHT);
+        let transform = renderer.get_transform();
+
+        let game_state = get_fixture_game_state();
+        let characters = [
+            Character::new(0, 21),
+            Character::new(1, 43),
+        ];
+
+        display_sprites(
+            &mut renderer,
+            &transform,
+            &SPRITES,
+            &get_island(),
+            &game_state,
+            ORIGIN_HORIZONTAL_POSITION,
+            ORIGIN_VERTICAL_POSITION,
+        );
+
+        display_characters(
+            &mut renderer,
+            &transform,
+            &SKINS,
+            &characters,
+            &game_state,
+            ORIGIN_HORIZONTAL_POSITION,
+            ORIGIN_VERTICAL_POSITION,
+        );
+
+        display_characters_labels(
+            &mut renderer,
+            &transform,
+            &characters,
+            &game_state,
+            ORIGIN_HORIZONTAL_POSITION,
+            ORIGIN_VERTICAL_POSITION,
+        );
+
+        assert_snapshot("characters", renderer.get_image());
+    }
+
+    #[test]
+    fn test_rectangles_overlapping() {
+
+        let label = [100.0, 100.0, 80.0, 20.0];
+
+        assert!(are_rectangles_overlapping(&label, &[150.0, 110.0, 80.0, 20.0]));
+        assert!(are_rectangles_overlapping(&label, &[120.0, 105.0, 10.0, 5.0]));
+
+        /* touching labels do not overlap */
+        assert!(!are_rectangles_overlapping(&label, &[180.0, 100.0, 80.0, 20.0]));
+        assert!(!are_rectangles_overlapping(&label, &[100.0, 120.0, 80.0, 20.0]));
+        assert!(!are_rectangles_overlapping(&label, &[300.0, 300.0, 80.0, 20.0]));
+    }
+}
@@ -0,0 +1,360 @@
+//! Handles the heads-up display drawn in screen space on top of the map.
+
+use crate::game_state::{
+    GameState,
+    PLAYERS_AMOUNT,
+};
+use crate::gui::get_player_color;
+use crate::renderer::Renderer;
+
+use piston_window::Transformed;
+
+use std::time::Duration;
+
+const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const PANEL_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.4];
+
+const HUD_MARGIN: f64 = 20.0;
+const PANEL_PADDING: f64 = 15.0;
+
+/// Display the HUD: players list with their colors and scores (top left), current turn (top center),
+/// connection ping (top right) and local player status, or followed player for a spectator (bottom right). Positions are computed from
+/// the window size.
+///
+/// # Args:
+///
+/// `renderer` - the renderer to draw with
+/// `game_state` - the game state to display
+/// `ping` - the smoothed round trip time to the server, if measured
+pub fn display_hud(
+    renderer: &mut dyn Renderer,
+    game_state: &GameState,
+    ping: Option<Duration>,
+) {
+
+    let transform = renderer.get_transform();
+
+    let [window_width, window_height] = renderer.get_view_size();
+
+    display_players(
+        renderer,
+        game_state,
+    );
+
+    if let Some(turn) = game_state.get_turn() {
+
+        let banner = if game_state.get_local_player() == Some(turn.get_player()) {
+            "Your turn".to_string()
+        } else {
+            format!("{}'s turn", game_state.get_username(turn.get_player()))
+        };
+
+        const TURN_BANNER_FONT_SIZE: u32 = 48;
+        const TURN_BANNER_VERTICAL_POSITION: f64 = 60.0;
+        draw_centered_text(
+            renderer,
+            &banner,
+            TURN_BANNER_FONT_SIZE,
+            window_width / 2.0,
+            TURN_BANNER_VERTICAL_POSITION,
+        );
+
+        let remaining_seconds = turn.get_remaining_seconds();
+        let turn_status = format!(
+            "Turn {} - {}:{:02}",
+            turn.get_number(),
+            remaining_seconds / 60,
+            remaining_seconds % 60,
+        );
+
+        const TURN_STATUS_FONT_SIZE: u32 = 28;
+        const TURN_STATUS_VERTICAL_POSITION: f64 = 100.0;
+        draw_centered_text(
+            renderer,
+            &turn_status,
+            TURN_STATUS_FONT_SIZE,
+            window_width / 2.0,
+            TURN_STATUS_VERTICAL_POSITION,
+        );
+    }
+
+    display_ping(
+        renderer,
+        ping,
+    );
+
+    let status = get_local_player_status(game_state);
+
+    const STATUS_FONT_SIZE: u32 = 32;
+    let status_width = renderer.get_text_width(STATUS_FONT_SIZE, &status);
+    renderer.draw_text(
+        get_player_color(game_state.get_followed_player()),
+        STATUS_FONT_SIZE,
+        &status,
+        transform.trans(
+            window_width - HUD_MARGIN - status_width,
+            window_height - HUD_MARGIN,
+        ),
+    );
+}
+
+/// Display the connection ping at the top right corner of the window, colored according to the latency.
+/// Shared by the HUD and the lobby screen.
+///
+/// # Args:
+///
+/// `renderer` - the renderer to draw with
+/// `ping` - the smoothed round trip time to the server, if measured
+pub fn display_ping(
+    renderer: &mut dyn Renderer,
+    ping: Option<Duration>,
+) {
+
+    let transform = renderer.get_transform();
+
+    let [window_width, _] = renderer.get_view_size();
+
+    const GOOD_PING_COLOR: [f32; 4] = [0.2, 0.8, 0.2, 1.0];
+    const AVERAGE_PING_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+    const BAD_PING_COLOR: [f32; 4] = [0.9, 0.2, 0.2, 1.0];
+
+    const GOOD_PING: Duration = Duration::from_millis(100);
+    const AVERAGE_PING: Duration = Duration::from_millis(250);
+
+    let (ping_text, color) = match ping {
+        Some(ping) => {
+            let color = if ping < GOOD_PING {
+                GOOD_PING_COLOR
+            } else if ping < AVERAGE_PING {
+                AVERAGE_PING_COLOR
+            } else {
+                BAD_PING_COLOR
+            };
+
+            (format!("Ping: {} ms", ping.as_millis()), color)
+        },
+        None => ("Ping: -".to_string(), WHITE_COLOR),
+    };
+
+    const PING_FONT_SIZE: u32 = 28;
+    const PING_VERTICAL_POSITION: f64 = 50.0;
+    let ping_width = renderer.get_text_width(PING_FONT_SIZE, &ping_text);
+    renderer.draw_text(
+        color,
+        PING_FONT_SIZE,
+        &ping_text,
+        transform.trans(
+            window_width - HUD_MARGIN - ping_width,
+            PING_VERTICAL_POSITION,
+        ),
+    );
+}
+
+/// Display the players list panel: a color marker, the username and the treasures of every player.
+///
+/// # Args:
+///
+/// `renderer` - the renderer to draw with
+/// `game_state` - the game state to display
+fn display_players(
+    renderer: &mut dyn Renderer,
+    game_state: &GameState,
+) {
+
+    let transform = renderer.get_transform();
+
+    const PLAYER_FONT_SIZE: u32 = 28;
+    const PLAYER_LINE_HEIGHT: f64 = 40.0;
+    const PANEL_WIDTH: f64 = 420.0;
+
+    renderer.draw_rectangle(
+        PANEL_COLOR,
+        [
+            HUD_MARGIN,
+            HUD_MARGIN,
+            PANEL_WIDTH,
+            2.0 * PANEL_PADDING + (PLAYERS_AMOUNT as f64) * PLAYER_LINE_HEIGHT,
+        ],
+        transform,
+    );
+
+    const MARKER_SIZE: f64 = 20.0;
+    const TEXT_HORIZONTAL_OFFSET: f64 = 35.0;
+    const TEXT_VERTICAL_OFFSET: f64 = 28.0;
+
+    for (index, treasures) in game_state.get_treasures().iter().enumerate() {
+
+        let line_horizontal_position = HUD_MARGIN + PANEL_PADDING;
+        let line_vertical_position = HUD_MARGIN + PANEL_PADDING + (index as f64) * PLAYER_LINE_HEIGHT;
+
+        renderer.draw_ellipse(
+            get_player_color(index),
+            [
+                line_horizontal_position,
+                line_vertical_position + (PLAYER_LINE_HEIGHT - MARKER_SIZE) / 2.0,
+                MARKER_SIZE,
+                MARKER_SIZE,
+            ],
+            transform,
+        );
+
+        let you = if game_state.get_local_player() == Some(index) {
+            " (you)"
+        } else {
+            ""
+        };
+
+        renderer.draw_text(
+            WHITE_COLOR,
+            PLAYER_FONT_SIZE,
+            &format!("{}{}: {} treasures", game_state.get_username(index), you, treasures),
+            transform.trans(
+                line_horizontal_position + TEXT_HORIZONTAL_OFFSET,
+                line_vertical_position + TEXT_VERTICAL_OFFSET,
+            ),
+        );
+    }
+}
+
+/// Returns the status line of the local player: whether it can act and how many actions are left;
+/// the followed player for a spectator.
+///
+/// # Args:
+///
+/// `game_state` - the game state to display
+fn get_local_player_status(game_state: &GameState) -> String {
+
+    if game_state.is_spectating() {
+        return format!("Following {}", game_state.get_username(game_state.get_followed_player()));
+    }
+
+    let turn = match game_state.get_turn() {
+        Some(turn) => turn,
+        None => return "Free play".to_string(),
+    };
+
+    if game_state.get_local_player() != Some(turn.get_player()) {
+        return format!("Waiting for {}", game_state.get_username(turn.get_player()));
+    }
+
+    if !game_state.can_local_player_act() {
+        return "No action left".to_string();
+    }
+
+    format!("{} actions left", turn.get_remaining_actions())
+}
+
+/// Draws a text horizontally centered on the given position.
+///
+/// # Args:
+///
+/// `renderer` - the renderer to draw with
+/// `text` - the text to draw
+/// `font_size` - the size of the text
+/// `horizontal_center` - the horizontal position of the text center
+/// `vertical_position` - the vertical position of the text baseline
+fn draw_centered_text(
+    renderer: &mut dyn Renderer,
+    text: &str,
+    font_size: u32,
+    horizontal_center: f64,
+    vertical_position: f64,
+) {
+
+    let transform = renderer.get_transform();
+
+    let width = renderer.get_text_width(font_size, text);
+
+    renderer.draw_text(
+        WHITE_COLOR,
+        font_size,
+        text,
+        transform.trans(
+            horizontal_center - width / 2.0,
+            vertical_position,
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::renderer::RecordingRenderer;
+
+    #[test]
+    fn test_hud_without_game_turn() {
+
+        let mut renderer = RecordingRenderer::new([1920.0, 1080.0]);
+
+        display_hud(
+            &mut renderer,
+            &GameState::new(),
+            None,
+        );
+
+        let texts = renderer.get_texts();
+        assert!(texts.contains(&"Ping: -"));
+        assert!(texts.contains(&"Free play"));
+        assert!(!texts.iter().any(|text| text.starts_with("Turn")));
+    }
+
+    #[test]
+    fn test_hud_displays_the_ping() {
+
+        let mut renderer = RecordingRenderer::new([1920.0, 1080.0]);
+
+        display_hud(
+            &mut renderer,
+            &GameState::new(),
+            Some(Duration::from_millis(42)),
+        );
+
+        assert!(renderer.get_texts().contains(&"Ping: 42 ms"));
+    }
+
+    #[test]
+    fn test_hud_hides_the_player_interface_to_spectators() {
+
+        let mut start_data = [0; 400];
+        start_data[0] = 255;
+
+        let mut game_state = GameState::new();
+        game_state.start(&start_data);
+        game_state.set_turn(&[0, 0, 1, 2, 0, 30]);
+        game_state.cycle_followed_player(true);
+
+        let mut renderer = RecordingRenderer::new([1920.0, 1080.0]);
+
+        display_hud(
+            &mut renderer,
+            &game_state,
+            None,
+        );
+
+        let texts = renderer.get_texts();
+        assert!(texts.contains(&"Following Player 2"));
+        assert!(texts.contains(&"Player 1's turn"));
+        assert!(!texts.iter().any(|text| text.contains("(you)")));
+    }
+
+    #[test]
+    fn test_local_player_status_follows_the_turn() {
+
+        let mut start_data = [0; 400];
+        start_data[33..37].copy_from_slice(b"ANNE");
+
+        let mut game_state = GameState::new();
+        game_state.start(&start_data);
+        assert_eq!(get_local_player_status(&game_state), "Free play");
+
+        game_state.set_turn(&[1, 0, 1, 2, 0, 30]);
+        assert_eq!(get_local_player_status(&game_state), "Waiting for ANNE");
+
+        game_state.set_turn(&[0, 0, 2, 2, 0, 30]);
+        assert_eq!(get_local_player_status(&game_state), "2 actions left");
+
+        game_state.set_turn(&[0, 0, 2, 0, 0, 30]);
+        assert_eq!(get_local_player_status(&game_state), "No action left");
+    }
+}
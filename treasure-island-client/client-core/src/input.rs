@@ -0,0 +1,332 @@
+//! Maps keyboard and controller inputs to the game actions.
+
+use piston_window::{
+    Event,
+    Key,
+    Button,
+    PressEvent,
+    UpdateEvent,
+    ControllerAxisEvent,
+    ControllerButton,
+    ControllerHat,
+    HatState,
+};
+
+/// Direction of a panning or moving action.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Every action a player can trigger, whatever the used input device is.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Action {
+
+    /* moves the camera (arrows keys or controller left stick) */
+    Pan(Direction),
+
+    /* moves the tile cursor or the menus selection
+       (W/A/S/D keys or controller directional pad) */
+    Move(Direction),
+
+    Confirm,
+    Cancel,
+
+    /* digs or opens the chest on the character tile (space key or controller X button) */
+    Dig,
+}
+
+/// Converts the raw window events into actions.
+/// Keeps the controller stick position as axis events are only sent when the stick moves,
+/// but the camera has to keep panning as long as the stick is held.
+pub struct InputMapper {
+    stick_horizontal_position: f64,
+    stick_vertical_position: f64,
+}
+
+impl InputMapper {
+
+    pub fn new() -> InputMapper {
+        InputMapper {
+            stick_horizontal_position: 0.0,
+            stick_vertical_position: 0.0,
+        }
+    }
+
+    /// Returns the action triggered by the given event, if any. Mutable as it stores the stick position.
+    ///
+    /// # Args:
+    ///
+    /// `event` - the event to convert
+    pub fn get_action(
+        &mut self,
+        event: &Event,
+    ) -> Option<Action> {
+
+        if let Some(axis) = event.controller_axis_args() {
+
+            const LEFT_STICK_HORIZONTAL_AXIS: u8 = 0;
+            const LEFT_STICK_VERTICAL_AXIS: u8 = 1;
+
+            if axis.axis == LEFT_STICK_HORIZONTAL_AXIS {
+                self.stick_horizontal_position = axis.position;
+            }
+            else if axis.axis == LEFT_STICK_VERTICAL_AXIS {
+                self.stick_vertical_position = axis.position;
+            }
+
+            return None;
+        }
+
+        if event.update_args().is_some() {
+            return self.get_stick_action();
+        }
+
+        match event.press_args() {
+            Some(Button::Keyboard(key)) => get_keyboard_action(key),
+            Some(Button::Controller(button)) => get_controller_button_action(button),
+            Some(Button::Hat(hat)) => get_controller_hat_action(hat),
+            _ => None,
+        }
+    }
+
+    /// Returns the panning action of the stick according to its dominant axis,
+    /// ignores small movements around the stick center.
+    fn get_stick_action(&self) -> Option<Action> {
+
+        const STICK_DEAD_ZONE: f64 = 0.3;
+
+        let horizontal = self.stick_horizontal_position;
+        let vertical = self.stick_vertical_position;
+
+        if horizontal.abs() < STICK_DEAD_ZONE &&
+            vertical.abs() < STICK_DEAD_ZONE {
+            return None;
+        }
+
+        if horizontal.abs() > vertical.abs() {
+            if horizontal < 0.0 {
+                return Some(Action::Pan(Direction::Left));
+            }
+
+            return Some(Action::Pan(Direction::Right));
+        }
+
+        if vertical < 0.0 {
+            return Some(Action::Pan(Direction::Up));
+        }
+
+        Some(Action::Pan(Direction::Down))
+    }
+}
+
+/// Returns the action of a pressed keyboard key.
+///
+/// # Args:
+///
+/// `key` - the pressed key
+fn get_keyboard_action(key: Key) -> Option<Action> {
+    match key {
+        Key::Up => Some(Action::Pan(Direction::Up)),
+        Key::Down => Some(Action::Pan(Direction::Down)),
+        Key::Left => Some(Action::Pan(Direction::Left)),
+        Key::Right => Some(Action::Pan(Direction::Right)),
+        Key::W => Some(Action::Move(Direction::Up)),
+        Key::S => Some(Action::Move(Direction::Down)),
+        Key::A => Some(Action::Move(Direction::Left)),
+        Key::D => Some(Action::Move(Direction::Right)),
+        Key::Return => Some(Action::Confirm),
+        Key::Backspace => Some(Action::Cancel),
+        Key::Space => Some(Action::Dig),
+        _ => None,
+    }
+}
+
+/// Returns the action of a pressed controller button.
+///
+/// # Args:
+///
+/// `button` - the pressed controller button
+fn get_controller_button_action(button: ControllerButton) -> Option<Action> {
+
+    /* buttons ids follow the SDL game controller layout */
+    const BUTTON_A: u8 = 0;
+    const BUTTON_B: u8 = 1;
+    const BUTTON_X: u8 = 2;
+    const BUTTON_DPAD_UP: u8 = 11;
+    const BUTTON_DPAD_DOWN: u8 = 12;
+    const BUTTON_DPAD_LEFT: u8 = 13;
+    const BUTTON_DPAD_RIGHT: u8 = 14;
+
+    match button.button {
+        BUTTON_A => Some(Action::Confirm),
+        BUTTON_B => Some(Action::Cancel),
+        BUTTON_X => Some(Action::Dig),
+        BUTTON_DPAD_UP => Some(Action::Move(Direction::Up)),
+        BUTTON_DPAD_DOWN => Some(Action::Move(Direction::Down)),
+        BUTTON_DPAD_LEFT => Some(Action::Move(Direction::Left)),
+        BUTTON_DPAD_RIGHT => Some(Action::Move(Direction::Right)),
+        _ => None,
+    }
+}
+
+/// Returns the action of a pressed controller hat (directional pad reported as a hat by some controllers).
+///
+/// # Args:
+///
+/// `hat` - the pressed controller hat
+fn get_controller_hat_action(hat: ControllerHat) -> Option<Action> {
+    match hat.state {
+        HatState::Up => Some(Action::Move(Direction::Up)),
+        HatState::Down => Some(Action::Move(Direction::Down)),
+        HatState::Left => Some(Action::Move(Direction::Left)),
+        HatState::Right => Some(Action::Move(Direction::Right)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use piston_window::{
+        Input,
+        Motion,
+        Loop,
+        ButtonArgs,
+        ButtonState,
+        ControllerAxisArgs,
+        UpdateArgs,
+    };
+
+    /// Returns the event of a pressed button.
+    fn get_press_event(button: Button) -> Event {
+        Event::Input(
+            Input::Button(ButtonArgs {
+                state: ButtonState::Press,
+                button,
+                scancode: None,
+            }),
+            None,
+        )
+    }
+
+    /// Returns the event of a moved controller axis.
+    fn get_axis_event(
+        axis: u8,
+        position: f64,
+    ) -> Event {
+        Event::Input(
+            Input::Move(Motion::ControllerAxis(ControllerAxisArgs {
+                id: 0,
+                axis,
+                position,
+            })),
+            None,
+        )
+    }
+
+    /// Returns the action of the stick on the next frame.
+    fn get_update_action(mapper: &mut InputMapper) -> Option<Action> {
+        mapper.get_action(&Event::Loop(Loop::Update(UpdateArgs { dt: 0.016 })))
+    }
+
+    #[test]
+    fn test_stick_dead_zone_is_ignored() {
+
+        let mut mapper = InputMapper::new();
+
+        assert_eq!(mapper.get_action(&get_axis_event(0, 0.29)), None);
+        assert_eq!(mapper.get_action(&get_axis_event(1, -0.29)), None);
+        assert_eq!(get_update_action(&mut mapper), None);
+
+        mapper.get_action(&get_axis_event(0, 0.3));
+        assert_eq!(get_update_action(&mut mapper), Some(Action::Pan(Direction::Right)));
+
+        /* the camera keeps panning as long as the stick is held */
+        assert_eq!(get_update_action(&mut mapper), Some(Action::Pan(Direction::Right)));
+
+        mapper.get_action(&get_axis_event(0, 0.0));
+        assert_eq!(get_update_action(&mut mapper), None);
+    }
+
+    #[test]
+    fn test_stick_dominant_axis_is_selected() {
+
+        let mut mapper = InputMapper::new();
+
+        mapper.get_action(&get_axis_event(0, -0.5));
+        mapper.get_action(&get_axis_event(1, 0.8));
+        assert_eq!(get_update_action(&mut mapper), Some(Action::Pan(Direction::Down)));
+
+        mapper.get_action(&get_axis_event(1, -0.4));
+        assert_eq!(get_update_action(&mut mapper), Some(Action::Pan(Direction::Left)));
+
+        mapper.get_action(&get_axis_event(0, 0.1));
+        assert_eq!(get_update_action(&mut mapper), Some(Action::Pan(Direction::Up)));
+    }
+
+    #[test]
+    fn test_controller_buttons_and_hats_are_mapped() {
+
+        let mut mapper = InputMapper::new();
+
+        let get_button_action = |mapper: &mut InputMapper, button: u8| {
+            mapper.get_action(&get_press_event(Button::Controller(ControllerButton::new(0, button))))
+        };
+
+        assert_eq!(get_button_action(&mut mapper, 0), Some(Action::Confirm));
+        assert_eq!(get_button_action(&mut mapper, 1), Some(Action::Cancel));
+        assert_eq!(get_button_action(&mut mapper, 2), Some(Action::Dig));
+        assert_eq!(get_button_action(&mut mapper, 11), Some(Action::Move(Direction::Up)));
+        assert_eq!(get_button_action(&mut mapper, 14), Some(Action::Move(Direction::Right)));
+        assert_eq!(get_button_action(&mut mapper, 5), None);
+
+        let get_hat_action = |mapper: &mut InputMapper, state: HatState| {
+            mapper.get_action(&get_press_event(Button::Hat(ControllerHat::new(0, 0, state))))
+        };
+
+        assert_eq!(get_hat_action(&mut mapper, HatState::Left), Some(Action::Move(Direction::Left)));
+        assert_eq!(get_hat_action(&mut mapper, HatState::Down), Some(Action::Move(Direction::Down)));
+        assert_eq!(get_hat_action(&mut mapper, HatState::Centered), None);
+    }
+
+    #[test]
+    fn test_keyboard_and_controller_trigger_the_same_actions() {
+
+        let mut mapper = InputMapper::new();
+
+        /* every action of the controller buttons has its keyboard key */
+        let pairs = [
+            (Key::Return, 0),
+            (Key::Backspace, 1),
+            (Key::Space, 2),
+            (Key::W, 11),
+            (Key::S, 12),
+            (Key::A, 13),
+            (Key::D, 14),
+        ];
+
+        for (key, button) in pairs {
+
+            let keyboard_action = mapper.get_action(&get_press_event(Button::Keyboard(key)));
+            let controller_action = mapper.get_action(
+                &get_press_event(Button::Controller(ControllerButton::new(0, button)))
+            );
+
+            assert!(keyboard_action.is_some());
+            assert_eq!(keyboard_action, controller_action);
+        }
+
+        /* the arrows keys pan the camera like the stick */
+        mapper.get_action(&get_axis_event(1, -1.0));
+        assert_eq!(
+            mapper.get_action(&get_press_event(Button::Keyboard(Key::Up))),
+            get_update_action(&mut mapper),
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Treasure Island client core: the screens logic, the game state, the isometric projection and the network code
+//! of the game, independent from the window; shared by the game binary and the tools (load testing...).
+
+extern crate piston_window;
+
+pub mod gui;
+pub mod character;
+mod threads;
+mod network;
+mod ping;
+mod screen;
+mod input;
+mod lobby;
+mod rooms;
+pub mod discovery;
+mod chat;
+mod chat_panel;
+pub mod game_state;
+pub mod game_result;
+mod skins;
+pub mod turn;
+mod hud;
+mod minimap;
+pub mod visibility;
+pub mod pathfinding;
+pub mod shoreline;
+mod shared_resources;
+pub mod renderer;
+pub mod client;
+pub mod recording;
+pub mod bot;
+pub mod load_test;
+
+#[cfg(test)]
+mod mock_server;
+#[cfg(test)]
+mod software_renderer;
+
+mod connect_screen;
+mod username_prompt_screen;
+mod skin_selection_screen;
+mod room_browser_screen;
+mod lobby_screen;
+mod game_screen;
+mod game_over_screen;
@@ -0,0 +1,553 @@
+//! Load testing of a server: many headless clients connect at the same time through the game network core
+//! (the network threads or the event loop), each one sending its username, its skin,
+//! then a scripted series of rooms requests.
+//!
+//! The round trip time of every rooms request (until the rooms list is received) is measured;
+//! the report gives the connections success, the latency percentiles and the errors.
+//! Running the same test on both network cores compares their latencies.
+
+use crate::screen::Screen;
+use protocol::{
+    Message,
+    MESSAGE_DATA_LENGTH,
+    SERVER_MESSAGE_LENGTH,
+    MESSAGE_ACTION_SEND_USERNAME,
+    MESSAGE_ACTION_REQUEST_ROOMS,
+    MESSAGE_ACTION_CHOOSE_SKIN,
+    MESSAGE_ACTION_ROOMS_LIST,
+};
+use crate::shared_resources::SharedResources;
+use crate::threads::start_network_threads;
+use crate::network::{
+    MessageSender,
+    MessageReceiver,
+    ConnectionStatus,
+    NetworkOptions,
+    message_channel,
+    start_network_event_loop,
+};
+
+use std::fmt;
+use std::io::{
+    self,
+    Read,
+};
+use std::net::{
+    SocketAddr,
+    TcpStream,
+};
+use std::sync::mpsc::{
+    Sender,
+    Receiver,
+    RecvTimeoutError,
+    channel,
+};
+use std::thread::{
+    sleep,
+    spawn,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
+/// Network core the clients of a load test run on.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum NetworkCore {
+
+    /* one thread receiving and one thread sending the messages of every client */
+    Threads,
+
+    /* one non-blocking event loop thread handling the whole connection of every client */
+    EventLoop,
+}
+
+impl NetworkCore {
+
+    /// Returns the network core with the given name ("threads" or "event-loop"), if any.
+    ///
+    /// # Args:
+    ///
+    /// `name` - the name of the network core
+    pub fn from_name(name: &str) -> Option<NetworkCore> {
+        match name {
+            "threads" => Some(NetworkCore::Threads),
+            "event-loop" => Some(NetworkCore::EventLoop),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for NetworkCore {
+
+    fn fmt(
+        &self,
+        formatter: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        match self {
+            NetworkCore::Threads => write!(formatter, "threads"),
+            NetworkCore::EventLoop => write!(formatter, "event-loop"),
+        }
+    }
+}
+
+/// Options of a load test.
+#[derive(Copy, Clone)]
+pub struct LoadTestOptions {
+
+    /* the network core of the clients */
+    pub network: NetworkCore,
+
+    /* the amount of clients connected at the same time */
+    pub clients: usize,
+
+    /* the amount of rooms requests sent by every client */
+    pub requests: usize,
+
+    /* the delay between two requests of one client */
+    pub interval: Duration,
+
+    /* the time a client waits for an answer before counting the request as timed out */
+    pub timeout: Duration,
+}
+
+/// Result of a load test.
+pub struct LoadTestReport {
+    clients: usize,
+    connected_clients: usize,
+    connection_times: Vec<Duration>,
+    latencies: Vec<Duration>,
+    errors: Vec<String>,
+}
+
+impl LoadTestReport {
+
+    /// Returns the amount of clients which connected to the server.
+    pub fn get_connected_clients(&self) -> usize {
+        self.connected_clients
+    }
+
+    /// Returns the round trip time of every answered request, sorted from the quickest.
+    pub fn get_latencies(&self) -> &[Duration] {
+        &self.latencies
+    }
+
+    /// Returns the errors of the clients.
+    pub fn get_errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// Returns the given percentile of the latencies (nearest rank), none if no request has been answered.
+    ///
+    /// # Args:
+    ///
+    /// `percentile` - the percentile, from 0 to 100
+    pub fn get_latency_percentile(
+        &self,
+        percentile: f64,
+    ) -> Option<Duration> {
+
+        if self.latencies.is_empty() {
+            return None;
+        }
+
+        let rank = ((percentile / 100.0) * self.latencies.len() as f64).ceil() as usize;
+        Some(self.latencies[rank.clamp(1, self.latencies.len()) - 1])
+    }
+}
+
+impl fmt::Display for LoadTestReport {
+
+    fn fmt(
+        &self,
+        formatter: &mut fmt::Formatter,
+    ) -> fmt::Result {
+
+        writeln!(formatter, "Connected clients: {}/{}", self.connected_clients, self.clients)?;
+
+        if let Some(slowest) = self.connection_times.iter().max() {
+            writeln!(formatter, "Slowest connection: {} ms", slowest.as_millis())?;
+        }
+
+        writeln!(formatter, "Answered requests: {}", self.latencies.len())?;
+
+        const PERCENTILES: [f64; 4] = [50.0, 90.0, 99.0, 100.0];
+        for percentile in PERCENTILES.iter() {
+            if let Some(latency) = self.get_latency_percentile(*percentile) {
+                writeln!(
+                    formatter,
+                    "Latency p{}: {:.1} ms",
+                    percentile,
+                    latency.as_secs_f64() * 1000.0,
+                )?;
+            }
+        }
+
+        writeln!(formatter, "Errors: {}", self.errors.len())?;
+        for error in self.errors.iter() {
+            writeln!(formatter, "  {}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Result of one client.
+struct ClientResult {
+    connection_time: Option<Duration>,
+    latencies: Vec<Duration>,
+    errors: Vec<String>,
+}
+
+/// Stream reading the server messages for the receiving thread,
+/// and notifying the action of every complete message as soon as it is received.
+struct MessageProbe {
+    stream: TcpStream,
+    actions_sender: Sender<u8>,
+    message_position: usize,
+    action: u8,
+}
+
+impl Read for MessageProbe {
+
+    fn read(
+        &mut self,
+        buffer: &mut [u8],
+    ) -> io::Result<usize> {
+
+        let length = self.stream.read(buffer)?;
+
+        for byte in buffer[..length].iter() {
+
+            if self.message_position == 0 {
+                self.action = *byte;
+            }
+
+            self.message_position += 1;
+
+            if self.message_position == SERVER_MESSAGE_LENGTH {
+                self.message_position = 0;
+
+                /* the client may have given up waiting */
+                let _ = self.actions_sender.send(self.action);
+            }
+        }
+
+        Ok(length)
+    }
+}
+
+/// Runs the load test: connects every client at the same time and waits for all of them to finish their script.
+///
+/// # Args:
+///
+/// `address` - the address of the tested server
+/// `options` - the options of the test
+pub fn run_load_test(
+    address: SocketAddr,
+    options: LoadTestOptions,
+) -> LoadTestReport {
+
+    let clients: Vec<_> = (0..options.clients)
+        .map(|index| spawn(move || run_client(address, index, options)))
+        .collect();
+
+    let mut report = LoadTestReport {
+        clients: options.clients,
+        connected_clients: 0,
+        connection_times: Vec::new(),
+        latencies: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for client in clients {
+
+        let result = client.join().unwrap();
+
+        if let Some(connection_time) = result.connection_time {
+            report.connected_clients += 1;
+            report.connection_times.push(connection_time);
+        }
+
+        report.latencies.extend(result.latencies);
+        report.errors.extend(result.errors);
+    }
+
+    report.latencies.sort();
+    report
+}
+
+/// Plays the script of one client: connects, sends the username and the skin, then sends the rooms requests
+/// one after the other, each one once the previous one is answered or timed out.
+///
+/// # Args:
+///
+/// `address` - the address of the tested server
+/// `index` - the index of the client, used into its username
+/// `options` - the options of the test
+fn run_client(
+    address: SocketAddr,
+    index: usize,
+    options: LoadTestOptions,
+) -> ClientResult {
+
+    let username = format!("Load {}", index + 1);
+
+    let mut result = ClientResult {
+        connection_time: None,
+        latencies: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let (sender, receiver) = message_channel();
+    let (actions_sender, actions_receiver) = channel();
+
+    let start = Instant::now();
+    let connection = match options.network {
+        NetworkCore::Threads => connect_with_threads(
+            address,
+            options.timeout,
+            receiver,
+            actions_sender,
+        ),
+        NetworkCore::EventLoop => connect_with_event_loop(
+            address,
+            options.timeout,
+            receiver,
+            actions_sender,
+        ),
+    };
+
+    if let Err(error) = connection {
+        result.errors.push(format!("{}: cannot connect: {}", username, error));
+        return result;
+    }
+    result.connection_time = Some(start.elapsed());
+
+    let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+    bytes[..username.len()].copy_from_slice(username.as_bytes());
+    send(&sender, MESSAGE_ACTION_SEND_USERNAME, bytes);
+
+    send(&sender, MESSAGE_ACTION_CHOOSE_SKIN, [0; MESSAGE_DATA_LENGTH]);
+
+    for request in 0..options.requests {
+
+        if request > 0 {
+            sleep(options.interval);
+        }
+
+        let request_time = Instant::now();
+        send(&sender, MESSAGE_ACTION_REQUEST_ROOMS, [0; MESSAGE_DATA_LENGTH]);
+
+        match wait_for_action(&actions_receiver, MESSAGE_ACTION_ROOMS_LIST, request_time + options.timeout) {
+            Ok(()) => result.latencies.push(request_time.elapsed()),
+            Err(RecvTimeoutError::Timeout) => {
+                result.errors.push(format!("{}: request {} timed out", username, request + 1));
+            },
+            Err(RecvTimeoutError::Disconnected) => {
+                result.errors.push(format!("{}: disconnected by the server", username));
+                break;
+            },
+        };
+    }
+
+    result
+}
+
+/// Connects one client to the server and starts its network threads.
+///
+/// # Args:
+///
+/// `address` - the address of the tested server
+/// `timeout` - the time the connection is given
+/// `receiver` - receives the messages of the client
+/// `actions_sender` - notified of the action of every message from the server
+fn connect_with_threads(
+    address: SocketAddr,
+    timeout: Duration,
+    receiver: MessageReceiver,
+    actions_sender: Sender<u8>,
+) -> io::Result<()> {
+
+    let stream = TcpStream::connect_timeout(&address, timeout)?;
+
+    let probe = MessageProbe {
+        stream: stream.try_clone()?,
+        actions_sender,
+        message_position: 0,
+        action: 0,
+    };
+
+    start_network_threads(
+        probe,
+        stream,
+        receiver,
+        SharedResources::new(Screen::UsernamePrompt),
+        None,
+    );
+
+    Ok(())
+}
+
+/// Starts the network event loop of one client and waits until it is connected to the server.
+/// The client is not reconnected once disconnected, so that the disconnections are reported.
+///
+/// # Args:
+///
+/// `address` - the address of the tested server
+/// `timeout` - the time the connection is given
+/// `receiver` - receives the messages of the client
+/// `actions_sender` - notified of the action of every message from the server
+fn connect_with_event_loop(
+    address: SocketAddr,
+    timeout: Duration,
+    receiver: MessageReceiver,
+    actions_sender: Sender<u8>,
+) -> io::Result<()> {
+
+    let status = start_network_event_loop(
+        address,
+        receiver,
+        SharedResources::new(Screen::UsernamePrompt),
+        None,
+        NetworkOptions {
+            connect_timeout: timeout,
+            reconnection_attempts: 0,
+            ..NetworkOptions::default()
+        },
+        Some(actions_sender),
+    );
+
+    const STATUS_CHECK_INTERVAL: Duration = Duration::from_millis(1);
+
+    loop {
+        match *status.lock().unwrap() {
+            ConnectionStatus::Connecting => {},
+            ConnectionStatus::Connected => return Ok(()),
+            _ => return Err(io::Error::other("connection failed")),
+        };
+
+        sleep(STATUS_CHECK_INTERVAL);
+    }
+}
+
+/// Waits for a message with the given action; the other messages are skipped.
+///
+/// # Args:
+///
+/// `actions_receiver` - receives the action of every message from the server
+/// `action` - the expected action
+/// `deadline` - the moment the wait times out
+fn wait_for_action(
+    actions_receiver: &Receiver<u8>,
+    action: u8,
+    deadline: Instant,
+) -> Result<(), RecvTimeoutError> {
+
+    loop {
+
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        if actions_receiver.recv_timeout(timeout)? == action {
+            return Ok(());
+        }
+    }
+}
+
+/// Sends one message to the server.
+///
+/// # Args:
+///
+/// `sender` - the sender of the messages to the server
+/// `action` - the action of the message
+/// `bytes` - the data of the message
+fn send(
+    sender: &MessageSender,
+    action: u8,
+    bytes: [u8; MESSAGE_DATA_LENGTH],
+) {
+    let mut message = Message::new(action);
+    message.set_data(bytes);
+    sender.send(message).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use protocol::{
+        MESSAGE_ACTION_PING,
+    };
+    use crate::mock_server::{
+        MockServer,
+        MockServerStep,
+    };
+
+    use std::net::TcpListener;
+
+    /// Returns the options of a test with one client.
+    fn get_options(
+        network: NetworkCore,
+        requests: usize,
+    ) -> LoadTestOptions {
+        LoadTestOptions {
+            network,
+            clients: 1,
+            requests,
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(500),
+        }
+    }
+
+    #[test]
+    fn test_load_test_measures_every_request() {
+
+        for network in [NetworkCore::Threads, NetworkCore::EventLoop] {
+
+            let mut script = Vec::new();
+            for _ in 0..3 {
+                script.push(MockServerStep::Expect(MESSAGE_ACTION_REQUEST_ROOMS));
+                script.push(MockServerStep::rooms_list(&[]));
+            }
+
+            let server = MockServer::start(script);
+            let report = run_load_test(server.get_address(), get_options(network, 3));
+
+            assert_eq!(report.get_connected_clients(), 1);
+            assert_eq!(report.get_latencies().len(), 3);
+            assert!(report.get_errors().is_empty());
+            assert!(report.get_latency_percentile(50.0) <= report.get_latency_percentile(100.0));
+
+            /* the pings aside, the username comes first */
+            let username = server
+                .get_received_messages()
+                .into_iter()
+                .find(|message| message.action != MESSAGE_ACTION_PING)
+                .unwrap();
+            assert_eq!(&username.data[..7], b"Load 1\0");
+        }
+    }
+
+    #[test]
+    fn test_load_test_reports_the_errors() {
+
+        for network in [NetworkCore::Threads, NetworkCore::EventLoop] {
+
+            /* the second request is never answered */
+            let server = MockServer::start(vec![
+                MockServerStep::Expect(MESSAGE_ACTION_REQUEST_ROOMS),
+                MockServerStep::rooms_list(&[]),
+            ]);
+
+            let report = run_load_test(server.get_address(), get_options(network, 2));
+            assert_eq!(report.get_latencies().len(), 1);
+            assert_eq!(report.get_errors(), &["Load 1: request 2 timed out".to_string()]);
+
+            /* nothing listens on the port of a closed listener */
+            let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+            let report = run_load_test(address, get_options(network, 1));
+            assert_eq!(report.get_connected_clients(), 0);
+            assert_eq!(report.get_errors().len(), 1);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Structure and implementation of the lobby, filled by the server messages before the game starts.
+
+use protocol::get_string_from_bytes;
+
+/// One player connected to the lobby.
+pub struct LobbyPlayer {
+    username: String,
+    ready: bool,
+}
+
+impl LobbyPlayer {
+
+    /// Returns the player username.
+    pub fn get_username(&self) -> &str {
+        &self.username
+    }
+
+    /// Indicates if the player is ready to start.
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+}
+
+/// Players connected to the lobby and start countdown.
+pub struct Lobby {
+    players: Vec<LobbyPlayer>,
+    required_players: u8,
+    countdown: Option<u8>,
+}
+
+impl Lobby {
+
+    pub fn new() -> Lobby {
+        Lobby {
+            players: Vec::new(),
+            required_players: 0,
+            countdown: None,
+        }
+    }
+
+    /// Replaces the lobby content by the one sent by the server.
+    ///
+    /// The data contains the amount of players required to start,
+    /// the amount of connected players, then for each player one byte
+    /// for the ready status followed by the username on 32 bytes (padded with zeros).
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the lobby update message
+    pub fn update(
+        &mut self,
+        data: &[u8],
+    ) {
+
+        /* a message too short to hold the players amounts is ignored; a truncated player is dropped */
+        const PLAYERS_OFFSET: usize = 2;
+        const PLAYER_LENGTH: usize = 33;
+        if data.len() < PLAYERS_OFFSET {
+            return;
+        }
+
+        self.required_players = data[0];
+
+        let players_amount = data[1] as usize;
+
+        self.players = data[PLAYERS_OFFSET..]
+            .chunks_exact(PLAYER_LENGTH)
+            .take(players_amount)
+            .map(|player| LobbyPlayer {
+                username: get_string_from_bytes(&player[1..]),
+                ready: player[0] != 0,
+            })
+            .collect();
+    }
+
+    /// Sets the amount of seconds before the game starts; zero cancels the countdown.
+    ///
+    /// # Args:
+    ///
+    /// `seconds` - the remaining seconds
+    pub fn set_countdown(
+        &mut self,
+        seconds: u8,
+    ) {
+        self.countdown = if seconds == 0 {
+            None
+        } else {
+            Some(seconds)
+        };
+    }
+
+    /// Empties the lobby and cancels the countdown, when the player leaves the room or the game starts;
+    /// the next room lobby is sent again by the server.
+    pub fn clear(&mut self) {
+        self.players.clear();
+        self.required_players = 0;
+        self.countdown = None;
+    }
+
+    /// Returns the connected players.
+    pub fn get_players(&self) -> &[LobbyPlayer] {
+        &self.players
+    }
+
+    /// Returns the amount of players required to start the game.
+    pub fn get_required_players(&self) -> u8 {
+        self.required_players
+    }
+
+    /// Returns the remaining seconds before the game starts, if all the players are ready.
+    pub fn get_countdown(&self) -> Option<u8> {
+        self.countdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const PLAYER_LENGTH: usize = 33;
+
+    /// Returns the bytes of one lobby player.
+    fn get_player_bytes(
+        username: &str,
+        ready: bool,
+    ) -> Vec<u8> {
+
+        let mut bytes = vec![0; PLAYER_LENGTH];
+        bytes[0] = ready as u8;
+        bytes[1..username.len() + 1].copy_from_slice(username.as_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_update_reads_the_players() {
+
+        let mut data = vec![2, 2];
+        data.extend(get_player_bytes("Alice", true));
+        data.extend(get_player_bytes("Bob", false));
+
+        /* the message is padded with zeros after the players */
+        data.resize(400, 0);
+
+        let mut lobby = Lobby::new();
+        lobby.update(&data);
+
+        assert_eq!(lobby.get_required_players(), 2);
+        assert_eq!(lobby.get_players().len(), 2);
+        assert_eq!(lobby.get_players()[0].get_username(), "Alice");
+        assert!(lobby.get_players()[0].is_ready());
+        assert_eq!(lobby.get_players()[1].get_username(), "Bob");
+        assert!(!lobby.get_players()[1].is_ready());
+    }
+
+    #[test]
+    fn test_update_drops_the_truncated_players() {
+
+        let mut data = vec![2, 2];
+        data.extend(get_player_bytes("Alice", true));
+        data.extend(&get_player_bytes("Bob", true)[..PLAYER_LENGTH / 2]);
+
+        let mut lobby = Lobby::new();
+        lobby.update(&data);
+
+        assert_eq!(lobby.get_players().len(), 1);
+        assert_eq!(lobby.get_players()[0].get_username(), "Alice");
+
+        /* a message without the players amounts does not change the lobby */
+        lobby.update(&[3]);
+
+        assert_eq!(lobby.get_required_players(), 2);
+        assert_eq!(lobby.get_players().len(), 1);
+    }
+
+    #[test]
+    fn test_clear_empties_the_lobby() {
+
+        let mut data = vec![2, 1];
+        data.extend(get_player_bytes("Alice", true));
+
+        let mut lobby = Lobby::new();
+        lobby.update(&data);
+        lobby.set_countdown(5);
+
+        lobby.clear();
+
+        assert!(lobby.get_players().is_empty());
+        assert_eq!(lobby.get_required_players(), 0);
+        assert_eq!(lobby.get_countdown(), None);
+    }
+}
@@ -0,0 +1,300 @@
+//! Handles the lobby screen, where players wait for each other before the game starts.
+
+use crate::screen::Screen;
+use crate::renderer::Renderer;
+use protocol::{
+    Message,
+    MESSAGE_ACTION_SET_READY,
+    MESSAGE_ACTION_LEAVE_LOBBY,
+};
+use crate::network::MessageSender;
+use crate::lobby::Lobby;
+use crate::chat::ChatLog;
+use crate::chat_panel::ChatPanel;
+use crate::ping::Ping;
+use crate::hud::display_ping;
+use crate::input::{
+    Action,
+    Direction,
+};
+
+use piston_window::{
+    Transformed,
+    Event,
+};
+
+use std::sync::{
+    Mutex,
+    Arc,
+};
+
+const READY_BUTTON: usize = 0;
+const LEAVE_BUTTON: usize = 1;
+const BUTTONS_AMOUNT: usize = 2;
+
+pub struct LobbyScreen {
+    sender: MessageSender,
+    lobby: Arc<Mutex<Lobby>>,
+    ping: Arc<Mutex<Ping>>,
+    ready: bool,
+    spectating: bool,
+    selected_button: usize,
+    chat_panel: ChatPanel,
+}
+
+impl LobbyScreen {
+
+    /// Constructor.
+    ///
+    /// # Args:
+    ///
+    /// `sender` - the sender to send messages to the server
+    /// `lobby` - thread safe pointer to the lobby; shared with the thread that receives the lobby updates from the server
+    /// `chat_log` - thread safe pointer to the chat log; shared with the thread that receives the chat messages from the server
+    /// `ping` - thread safe pointer to the round trip time to the server; shared with the network thread measuring it
+    pub fn new(
+        sender: MessageSender,
+        lobby: Arc<Mutex<Lobby>>,
+        chat_log: Arc<Mutex<ChatLog>>,
+        ping: Arc<Mutex<Ping>>,
+    ) -> LobbyScreen {
+        LobbyScreen {
+            sender: sender.clone(),
+            lobby,
+            ping,
+            ready: false,
+            spectating: false,
+            selected_button: READY_BUTTON,
+            chat_panel: ChatPanel::new(
+                chat_log,
+                sender,
+            ),
+        }
+    }
+
+    /// Renders the screen.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer to draw with
+    pub fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+    ) {
+
+        let transform = renderer.get_transform();
+
+        let lobby_mutex_guard = self.lobby.lock().unwrap();
+        let lobby = &*lobby_mutex_guard;
+
+        const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+        const READY_COLOR: [f32; 4] = [0.2, 0.8, 0.2, 1.0];
+        const SELECTED_BUTTON_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+
+        const TEXT_HORIZONTAL_POSITION: f64 = 635.0;
+
+        const PLAYERS_AMOUNT_MESSAGE_FONT_SIZE: u32 = 64;
+        const PLAYERS_AMOUNT_MESSAGE_VERTICAL_POSITION: f64 = 200.0;
+        let players_amount_message = format!(
+            "Waiting for players... {}/{}",
+            lobby.get_players().len(),
+            lobby.get_required_players(),
+        );
+        renderer.draw_text(
+            WHITE_COLOR,
+            PLAYERS_AMOUNT_MESSAGE_FONT_SIZE,
+            &players_amount_message,
+            transform.trans(
+                TEXT_HORIZONTAL_POSITION,
+                PLAYERS_AMOUNT_MESSAGE_VERTICAL_POSITION,
+            ),
+        );
+
+        const PLAYER_FONT_SIZE: u32 = 48;
+        const PLAYERS_VERTICAL_POSITION: f64 = 300.0;
+        const PLAYERS_VERTICAL_DISTANCE: f64 = 60.0;
+        for (index, player) in lobby.get_players().iter().enumerate() {
+
+            let (color, status) = if player.is_ready() {
+                (READY_COLOR, "ready")
+            } else {
+                (WHITE_COLOR, "not ready")
+            };
+
+            renderer.draw_text(
+                color,
+                PLAYER_FONT_SIZE,
+                &format!("{} - {}", player.get_username(), status),
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    PLAYERS_VERTICAL_POSITION + (index as f64) * PLAYERS_VERTICAL_DISTANCE,
+                ),
+            );
+        }
+
+        const COUNTDOWN_MESSAGE_FONT_SIZE: u32 = 64;
+        const COUNTDOWN_MESSAGE_VERTICAL_POSITION: f64 = 850.0;
+        if let Some(seconds) = lobby.get_countdown() {
+            renderer.draw_text(
+                READY_COLOR,
+                COUNTDOWN_MESSAGE_FONT_SIZE,
+                &format!("Starting in {}...", seconds),
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    COUNTDOWN_MESSAGE_VERTICAL_POSITION,
+                ),
+            );
+        }
+
+        let ready_button_label = if self.ready {
+            "Not ready"
+        } else {
+            "Ready"
+        };
+        let buttons_labels: [&str; BUTTONS_AMOUNT] = [
+            ready_button_label,
+            "Leave",
+        ];
+
+        /* spectators do not play, they can only leave */
+        let displayed_buttons = if self.spectating {
+            LEAVE_BUTTON..BUTTONS_AMOUNT
+        } else {
+            READY_BUTTON..BUTTONS_AMOUNT
+        };
+
+        const BUTTON_FONT_SIZE: u32 = 48;
+        const BUTTONS_VERTICAL_POSITION: f64 = 950.0;
+        const BUTTONS_HORIZONTAL_DISTANCE: f64 = 350.0;
+        for (column, index) in displayed_buttons.enumerate() {
+
+            let color = if index == self.selected_button {
+                SELECTED_BUTTON_COLOR
+            } else {
+                WHITE_COLOR
+            };
+
+            renderer.draw_text(
+                color,
+                BUTTON_FONT_SIZE,
+                buttons_labels[index],
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION + (column as f64) * BUTTONS_HORIZONTAL_DISTANCE,
+                    BUTTONS_VERTICAL_POSITION,
+                ),
+            );
+        }
+
+        let round_trip_time = self.ping.lock().unwrap().get_round_trip_time();
+        display_ping(
+            renderer,
+            round_trip_time,
+        );
+
+        self.chat_panel.render(
+            renderer,
+        );
+    }
+
+    /// Handle the actions of the screen. Mutable as it modifies the selected button and the ready status.
+    ///
+    /// # Args:
+    ///
+    /// `event` - the event to handle, first handled by the chat panel
+    /// `action` - the action triggered by the event, if any
+    /// `current_screen` - reference to the current screen; expected to be mutable to be changed when the player leaves the lobby
+    pub fn handle_events(
+        &mut self,
+        event: &Event,
+        action: Option<Action>,
+        current_screen: &mut Screen,
+    ) {
+
+        if self.chat_panel.handle_events(event) {
+            return;
+        }
+
+        match action {
+            Some(Action::Move(_)) if self.spectating => {},
+            Some(Action::Move(Direction::Left)) => {
+                self.selected_button = (self.selected_button + BUTTONS_AMOUNT - 1) % BUTTONS_AMOUNT;
+            },
+            Some(Action::Move(Direction::Right)) => {
+                self.selected_button = (self.selected_button + 1) % BUTTONS_AMOUNT;
+            },
+            Some(Action::Confirm) if self.selected_button == READY_BUTTON => {
+                self.set_ready(!self.ready);
+            },
+            Some(Action::Confirm) if self.selected_button == LEAVE_BUTTON => {
+                self.leave(current_screen);
+            },
+            Some(Action::Cancel) => {
+                self.leave(current_screen);
+            },
+            _ => {}
+        };
+    }
+
+    /// Sets whether the room is only watched; spectators cannot be ready, only the leave button is displayed.
+    ///
+    /// # Args:
+    ///
+    /// `spectating` - true if the room is joined as a spectator
+    pub fn set_spectating(
+        &mut self,
+        spectating: bool,
+    ) {
+
+        self.spectating = spectating;
+        self.ready = false;
+        self.selected_button = if spectating {
+            LEAVE_BUTTON
+        } else {
+            READY_BUTTON
+        };
+    }
+
+    /// Sets the ready status of the player and sends it to the server; ignored for a spectator.
+    ///
+    /// # Args:
+    ///
+    /// `ready` - the new ready status of the player
+    pub fn set_ready(
+        &mut self,
+        ready: bool,
+    ) {
+
+        if self.spectating {
+            return;
+        }
+
+        self.ready = ready;
+
+        let mut message = Message::new(MESSAGE_ACTION_SET_READY);
+
+        const MESSAGE_DATA_LENGTH: usize = 32;
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[0] = self.ready as u8;
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+    }
+
+    /// Notifies the server the player leaves the lobby and goes back to the room browser.
+    ///
+    /// # Args:
+    ///
+    /// `current_screen` - reference to the current screen; expected to be mutable to go back to the room browser
+    fn leave(
+        &mut self,
+        current_screen: &mut Screen,
+    ) {
+
+        self.sender.send(Message::new(MESSAGE_ACTION_LEAVE_LOBBY)).unwrap();
+
+        self.ready = false;
+        self.selected_button = READY_BUTTON;
+        self.lobby.lock().unwrap().clear();
+
+        *current_screen = Screen::RoomBrowser;
+    }
+}
@@ -0,0 +1,297 @@
+//! Handles the minimap: the whole island in miniature, displayed in the top right corner of the game screen.
+//!
+//! The minimap uses the same isometric projection as the map, scaled down;
+//! so the camera viewport is a simple rectangle on it.
+
+use crate::character::Character;
+use crate::game_state::GameState;
+use crate::visibility::TileVisibility;
+use crate::gui::{
+    get_tile_screen_position,
+    get_player_color,
+    is_character_visible,
+};
+use crate::renderer::Renderer;
+use crate::shoreline::is_water_tile;
+
+const MINIMAP_SCALE: f64 = 0.12;
+
+/* extent of the whole projected map when the camera origin is (0, 0):
+   the first and last columns are 19 tiles away from the map center line,
+   the first and last tiles are 38 tiles away vertically; tiles sprites are 140 pixels large */
+const MAP_LEFT: f64 = -75.0 - 19.0 * 69.0;
+const MAP_TOP: f64 = -25.0;
+const MAP_WIDTH: f64 = 2.0 * 19.0 * 69.0 + 140.0;
+const MAP_HEIGHT: f64 = 38.0 * 31.0 + 140.0;
+
+const MINIMAP_WIDTH: f64 = MAP_WIDTH * MINIMAP_SCALE;
+const MINIMAP_HEIGHT: f64 = MAP_HEIGHT * MINIMAP_SCALE;
+
+const MINIMAP_MARGIN: f64 = 20.0;
+const MINIMAP_VERTICAL_POSITION: f64 = 80.0;
+
+/* the top face of a tile is a diamond centered into the bottom part of the sprite */
+const TILE_CENTER_HORIZONTAL_OFFSET: f64 = 70.0;
+const TILE_CENTER_VERTICAL_OFFSET: f64 = 100.0;
+
+/// Display the minimap: one block per tile colored by tile type, the characters and the camera viewport.
+/// Follows the fog of war of the map: unexplored tiles and characters out of sight are not displayed.
+///
+/// # Args:
+///
+/// `renderer` - the renderer to draw with
+/// `tiles` - the tiles of the map
+/// `characters` - the characters to display
+/// `game_state` - the game state, used for the fog of war
+/// `origin_horizontal_position` - the camera origin horizontal position
+/// `origin_vertical_position` - the camera origin vertical position
+pub fn display_minimap(
+    renderer: &mut dyn Renderer,
+    tiles: &[u8; 400],
+    characters: &[Character; 2],
+    game_state: &GameState,
+    origin_horizontal_position: f64,
+    origin_vertical_position: f64,
+) {
+
+    let transform = renderer.get_transform();
+
+    let window_size = renderer.get_view_size();
+    let (
+        minimap_horizontal_position,
+        minimap_vertical_position,
+    ) = get_minimap_position(window_size);
+
+    const BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.4];
+    renderer.draw_rectangle(
+        BACKGROUND_COLOR,
+        [
+            minimap_horizontal_position,
+            minimap_vertical_position,
+            MINIMAP_WIDTH,
+            MINIMAP_HEIGHT,
+        ],
+        transform,
+    );
+
+    const BLOCK_WIDTH: f64 = 69.0 * MINIMAP_SCALE;
+    const BLOCK_HEIGHT: f64 = 31.0 * MINIMAP_SCALE;
+
+    for (index, tile) in tiles.iter().enumerate() {
+
+        if game_state.get_visibility().get_tile_visibility(index) == TileVisibility::Unexplored {
+            continue;
+        }
+
+        let (
+            horizontal_position,
+            vertical_position,
+        ) = get_minimap_tile_center(index, window_size);
+
+        renderer.draw_rectangle(
+            get_tile_color(*tile),
+            [
+                horizontal_position - BLOCK_WIDTH / 2.0,
+                vertical_position - BLOCK_HEIGHT / 2.0,
+                BLOCK_WIDTH,
+                BLOCK_HEIGHT,
+            ],
+            transform,
+        );
+    }
+
+    const MARKER_SIZE: f64 = 8.0;
+    for (index, character) in characters.iter().enumerate() {
+
+        if !is_character_visible(
+            game_state,
+            index,
+            character.get_position(),
+        ) {
+            continue;
+        }
+
+        let (
+            horizontal_position,
+            vertical_position,
+        ) = get_minimap_tile_center(character.get_position(), window_size);
+
+        renderer.draw_ellipse(
+            get_player_color(index),
+            [
+                horizontal_position - MARKER_SIZE / 2.0,
+                vertical_position - MARKER_SIZE / 2.0,
+                MARKER_SIZE,
+                MARKER_SIZE,
+            ],
+            transform,
+        );
+    }
+
+    /* the viewport is the window, from the camera origin */
+    let [window_width, window_height] = window_size;
+
+    const VIEWPORT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+    const VIEWPORT_BORDER_RADIUS: f64 = 1.0;
+    renderer.draw_rectangle_border(
+        VIEWPORT_COLOR,
+        VIEWPORT_BORDER_RADIUS,
+        [
+            minimap_horizontal_position + (-origin_horizontal_position - MAP_LEFT) * MINIMAP_SCALE,
+            minimap_vertical_position + (-origin_vertical_position - MAP_TOP) * MINIMAP_SCALE,
+            window_width * MINIMAP_SCALE,
+            window_height * MINIMAP_SCALE,
+        ],
+        transform,
+    );
+}
+
+/// Returns the camera origin that centers the window on the clicked minimap point,
+/// or none if the click is outside of the minimap.
+///
+/// # Args:
+///
+/// `click_position` - the position of the mouse click into the window
+/// `window_size` - the size of the window
+pub fn get_camera_origin_from_click(
+    click_position: [f64; 2],
+    window_size: [f64; 2],
+) -> Option<(f64, f64)> {
+
+    let (
+        minimap_horizontal_position,
+        minimap_vertical_position,
+    ) = get_minimap_position(window_size);
+
+    let [click_horizontal_position, click_vertical_position] = click_position;
+
+    let minimap_horizontal_click = click_horizontal_position - minimap_horizontal_position;
+    let minimap_vertical_click = click_vertical_position - minimap_vertical_position;
+
+    if !(0.0..=MINIMAP_WIDTH).contains(&minimap_horizontal_click) ||
+        !(0.0..=MINIMAP_HEIGHT).contains(&minimap_vertical_click) {
+        return None;
+    }
+
+    let map_horizontal_position = MAP_LEFT + minimap_horizontal_click / MINIMAP_SCALE;
+    let map_vertical_position = MAP_TOP + minimap_vertical_click / MINIMAP_SCALE;
+
+    let [window_width, window_height] = window_size;
+    Some((
+        window_width / 2.0 - map_horizontal_position,
+        window_height / 2.0 - map_vertical_position,
+    ))
+}
+
+/// Returns the position of the top left corner of the minimap, into the top right corner of the window.
+///
+/// # Args:
+///
+/// `window_size` - the size of the window
+fn get_minimap_position(window_size: [f64; 2]) -> (f64, f64) {
+    (
+        window_size[0] - MINIMAP_MARGIN - MINIMAP_WIDTH,
+        MINIMAP_VERTICAL_POSITION,
+    )
+}
+
+/// Returns the position of the center of the given tile on the minimap.
+///
+/// # Args:
+///
+/// `position` - the index of the tile
+/// `window_size` - the size of the window
+fn get_minimap_tile_center(
+    position: usize,
+    window_size: [f64; 2],
+) -> (f64, f64) {
+
+    let (
+        minimap_horizontal_position,
+        minimap_vertical_position,
+    ) = get_minimap_position(window_size);
+
+    let (
+        horizontal_position,
+        vertical_position,
+    ) = get_tile_screen_position(position, 0.0, 0.0);
+
+    (
+        minimap_horizontal_position +
+            (horizontal_position + TILE_CENTER_HORIZONTAL_OFFSET - MAP_LEFT) * MINIMAP_SCALE,
+        minimap_vertical_position +
+            (vertical_position + TILE_CENTER_VERTICAL_OFFSET - MAP_TOP) * MINIMAP_SCALE,
+    )
+}
+
+/// Returns the minimap color of the given tile type; the shores are water, as on the map.
+///
+/// # Args:
+///
+/// `tile` - the tile sprite index
+fn get_tile_color(tile: u8) -> [f32; 4] {
+
+    const WATER_COLOR: [f32; 4] = [0.1, 0.5, 0.85, 1.0];
+    if is_water_tile(tile) {
+        return WATER_COLOR;
+    }
+
+    match tile {
+        0..=3 => [0.93, 0.84, 0.6, 1.0], /* sand */
+        8 => [0.2, 0.6, 0.2, 1.0], /* palm tree */
+        9 => [0.55, 0.35, 0.15, 1.0], /* chest */
+        _ => WATER_COLOR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const WINDOW_SIZE: [f64; 2] = [1920.0, 1080.0];
+
+    #[test]
+    fn test_click_on_a_tile_centers_the_camera_on_it() {
+
+        for position in [0, 19, 210, 380, 399] {
+
+            let (horizontal_click, vertical_click) = get_minimap_tile_center(position, WINDOW_SIZE);
+
+            let (
+                origin_horizontal_position,
+                origin_vertical_position,
+            ) = get_camera_origin_from_click(
+                [horizontal_click, vertical_click],
+                WINDOW_SIZE,
+            ).unwrap();
+
+            let (
+                horizontal_position,
+                vertical_position,
+            ) = get_tile_screen_position(position, origin_horizontal_position, origin_vertical_position);
+
+            /* the clicked tile center is displayed at the window center */
+            const PRECISION: f64 = 0.001;
+            assert!((horizontal_position + TILE_CENTER_HORIZONTAL_OFFSET - WINDOW_SIZE[0] / 2.0).abs() < PRECISION);
+            assert!((vertical_position + TILE_CENTER_VERTICAL_OFFSET - WINDOW_SIZE[1] / 2.0).abs() < PRECISION);
+        }
+    }
+
+    #[test]
+    fn test_click_outside_of_the_minimap_is_ignored() {
+
+        let (minimap_horizontal_position, minimap_vertical_position) = get_minimap_position(WINDOW_SIZE);
+        let is_on_minimap = |horizontal_position: f64, vertical_position: f64| {
+            get_camera_origin_from_click(
+                [horizontal_position, vertical_position],
+                WINDOW_SIZE,
+            ).is_some()
+        };
+
+        assert!(is_on_minimap(minimap_horizontal_position, minimap_vertical_position));
+        assert!(!is_on_minimap(minimap_horizontal_position - 1.0, minimap_vertical_position));
+        assert!(!is_on_minimap(minimap_horizontal_position, minimap_vertical_position - 1.0));
+        assert!(!is_on_minimap(WINDOW_SIZE[0] / 2.0, WINDOW_SIZE[1] / 2.0));
+    }
+}
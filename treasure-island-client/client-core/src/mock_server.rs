@@ -0,0 +1,328 @@
+//! Scriptable game server running into the tests process, used to test the client end to end without the real server.
+//!
+//! The server accepts one client and plays its script step by step: it sends messages to the client,
+//! waits for the messages of the client or closes the connection. Every message received from the client is recorded.
+
+use protocol::{
+    decode_message,
+    MESSAGE_DATA_LENGTH,
+    CLIENT_MESSAGE_LENGTH,
+    SERVER_MESSAGE_LENGTH,
+    MESSAGE_ACTION_PUSH_MAP,
+    MESSAGE_ACTION_START_GAME,
+    MESSAGE_ACTION_ROOMS_LIST,
+    MESSAGE_ACTION_CHARACTER_MOVE,
+    MESSAGE_ACTION_GAME_OVER,
+    MESSAGE_ACTION_PONG,
+};
+
+use std::io::{
+    Read,
+    Write,
+};
+use std::net::{
+    TcpListener,
+    TcpStream,
+    SocketAddr,
+    Shutdown,
+};
+use std::sync::{
+    Mutex,
+    Arc,
+};
+use std::sync::mpsc::{
+    Sender,
+    channel,
+};
+use std::thread::{
+    spawn,
+    panicking,
+    JoinHandle,
+};
+use std::time::Duration;
+
+const USERNAME_LENGTH: usize = 32;
+
+/// One step of the server script.
+#[derive(Clone)]
+pub enum MockServerStep {
+
+    /* sends a message with the given action and data to the client; the data is padded with zeros */
+    Send(u8, Vec<u8>),
+
+    /* waits for a message with the given action from the client; the other messages are skipped */
+    Expect(u8),
+
+    /* closes the connection with the client */
+    Disconnect,
+}
+
+impl MockServerStep {
+
+    /// Returns the step pushing the given map to the client.
+    ///
+    /// # Args:
+    ///
+    /// `tiles` - the tiles of the map
+    pub fn push_map(tiles: &[u8; 400]) -> MockServerStep {
+
+        MockServerStep::Send(
+            MESSAGE_ACTION_PUSH_MAP,
+            tiles.to_vec(),
+        )
+    }
+
+    /// Returns the step sending the rooms list, every room waiting for players.
+    ///
+    /// # Args:
+    ///
+    /// `rooms` - the name, the code, the amount of players and the maximum amount of players of every room
+    pub fn rooms_list(rooms: &[(&str, &str, u8, u8)]) -> MockServerStep {
+
+        let mut data = vec![rooms.len() as u8];
+
+        const ROOM_NAME_LENGTH: usize = 16;
+        for (name, code, players, max_players) in rooms.iter() {
+            let mut bytes = [0; ROOM_NAME_LENGTH];
+            bytes[..name.len()].copy_from_slice(name.as_bytes());
+            data.extend_from_slice(&bytes);
+            data.extend_from_slice(code.as_bytes());
+            data.extend_from_slice(&[*players, *max_players, 0]);
+        }
+
+        MockServerStep::Send(
+            MESSAGE_ACTION_ROOMS_LIST,
+            data,
+        )
+    }
+
+    /// Returns the step answering the ping with the given sequence number.
+    ///
+    /// # Args:
+    ///
+    /// `sequence` - the sequence number of the ping
+    pub fn pong(sequence: u16) -> MockServerStep {
+
+        MockServerStep::Send(
+            MESSAGE_ACTION_PONG,
+            sequence.to_be_bytes().to_vec(),
+        )
+    }
+
+    /// Returns the step starting the game.
+    ///
+    /// # Args:
+    ///
+    /// `local_player` - the index of the player of the client
+    /// `usernames` - the usernames of the players
+    /// `skins` - the skins of the players
+    pub fn start_game(
+        local_player: u8,
+        usernames: [&str; 2],
+        skins: [u8; 2],
+    ) -> MockServerStep {
+
+        let mut data = vec![local_player];
+
+        for username in usernames.iter() {
+            let mut bytes = [0; USERNAME_LENGTH];
+            bytes[..username.len()].copy_from_slice(username.as_bytes());
+            data.extend_from_slice(&bytes);
+        }
+
+        data.extend_from_slice(&skins);
+
+        MockServerStep::Send(
+            MESSAGE_ACTION_START_GAME,
+            data,
+        )
+    }
+
+    /// Returns the step moving the character of the given player.
+    ///
+    /// # Args:
+    ///
+    /// `player` - the index of the moved player
+    /// `tile` - the destination tile
+    pub fn move_character(
+        player: u8,
+        tile: u16,
+    ) -> MockServerStep {
+
+        let mut data = vec![player];
+        data.extend_from_slice(&tile.to_be_bytes());
+
+        MockServerStep::Send(
+            MESSAGE_ACTION_CHARACTER_MOVE,
+            data,
+        )
+    }
+
+    /// Returns the step ending the game.
+    ///
+    /// # Args:
+    ///
+    /// `winner` - the index of the winner, 255 for a draw
+    /// `treasures` - the amount of treasures found by every player
+    /// `duration` - the duration of the game in seconds
+    pub fn game_over(
+        winner: u8,
+        treasures: [u8; 2],
+        duration: u16,
+    ) -> MockServerStep {
+
+        let mut data = vec![winner];
+        data.extend_from_slice(&treasures);
+        data.extend_from_slice(&duration.to_be_bytes());
+
+        MockServerStep::Send(
+            MESSAGE_ACTION_GAME_OVER,
+            data,
+        )
+    }
+}
+
+/// One message received from the client.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReceivedMessage {
+    pub action: u8,
+    pub data: [u8; MESSAGE_DATA_LENGTH],
+}
+
+pub struct MockServer {
+    address: SocketAddr,
+    received_messages: Arc<Mutex<Vec<ReceivedMessage>>>,
+    thread: Option<JoinHandle<()>>,
+
+    /* the connection is kept open once the script is played, until the server is dropped */
+    stop_sender: Option<Sender<()>>,
+}
+
+impl MockServer {
+
+    /// Starts the server on an ephemeral port of the loopback interface; the script is played once a client is connected.
+    ///
+    /// # Args:
+    ///
+    /// `script` - the steps to play
+    pub fn start(script: Vec<MockServerStep>) -> MockServer {
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let received_messages = Arc::new(Mutex::new(Vec::new()));
+        let thread_received_messages = received_messages.clone();
+
+        let (stop_sender, stop_receiver) = channel();
+
+        let thread = spawn(move || {
+
+            let (mut stream, _) = listener.accept().unwrap();
+
+            /* a script waiting for a message that never comes fails the test instead of blocking it */
+            const READ_TIMEOUT: Duration = Duration::from_secs(10);
+            stream.set_read_timeout(Some(READ_TIMEOUT)).unwrap();
+
+            for step in script {
+                match step {
+                    MockServerStep::Send(action, data) => send(&mut stream, action, &data),
+                    MockServerStep::Expect(action) => expect(&mut stream, action, &thread_received_messages),
+                    MockServerStep::Disconnect => {
+                        stream.shutdown(Shutdown::Both).unwrap();
+                        return;
+                    },
+                };
+            }
+
+            /* returns once the server is dropped */
+            let _ = stop_receiver.recv();
+        });
+
+        MockServer {
+            address,
+            received_messages,
+            thread: Some(thread),
+            stop_sender: Some(stop_sender),
+        }
+    }
+
+    /// Returns the address the server listens on.
+    pub fn get_address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Returns the messages received from the client so far.
+    pub fn get_received_messages(&self) -> Vec<ReceivedMessage> {
+        self.received_messages.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+
+    /// Closes the connection and waits for the server thread; fails the test if the script failed.
+    /// Does not wait if the test already failed, as the client may never have connected.
+    fn drop(&mut self) {
+
+        self.stop_sender.take();
+
+        if panicking() {
+            return;
+        }
+
+        if self.thread.take().unwrap().join().is_err() {
+            panic!("the mock server script failed");
+        }
+    }
+}
+
+/// Sends one message to the client.
+///
+/// # Args:
+///
+/// `stream` - the stream connected to the client
+/// `action` - the action of the message
+/// `data` - the data of the message, at most 400 bytes long
+fn send(
+    stream: &mut TcpStream,
+    action: u8,
+    data: &[u8],
+) {
+
+    let mut message = [0; SERVER_MESSAGE_LENGTH];
+    message[0] = action;
+    message[1..data.len() + 1].copy_from_slice(data);
+
+    stream.write_all(&message).unwrap();
+}
+
+/// Reads the messages of the client until one has the expected action; records every read message.
+///
+/// # Args:
+///
+/// `stream` - the stream connected to the client
+/// `action` - the expected action
+/// `received_messages` - the messages received from the client
+fn expect(
+    stream: &mut TcpStream,
+    action: u8,
+    received_messages: &Mutex<Vec<ReceivedMessage>>,
+) {
+
+    loop {
+
+        let mut bytes = [0; CLIENT_MESSAGE_LENGTH];
+        stream.read_exact(&mut bytes).unwrap();
+
+        /* decoded as the server does */
+        let message = decode_message(&bytes).unwrap();
+
+        received_messages.lock().unwrap().push(ReceivedMessage {
+            action: message.get_action(),
+            data: *message.get_data(),
+        });
+
+        if message.get_action() == action {
+            return;
+        }
+    }
+}
@@ -0,0 +1,905 @@
+//! Asynchronous networking core: one thread runs a non-blocking I/O event loop handling the whole connection
+//! to the server (connection timeout, reads, writes, heartbeats and reconnection).
+//!
+//! The main thread only sends its messages through a `MessageSender`, which wakes the event loop up,
+//! and polls the shared resources (updated by the received messages) and the connection status every frame.
+//!
+//! The heartbeats are ping messages the server answers with the same data (a sequence number):
+//! the round trip time of every answered ping is measured, and a server silent for too long
+//! is considered disconnected, even if the connection is not closed.
+//!
+//! The server forgets a session with its connection: once reconnected, the username and the skin
+//! are sent again and the player goes back to the room browser if they were in a room.
+
+use crate::screen::Screen;
+use protocol::{
+    Message,
+    encode_message,
+    MESSAGE_DATA_LENGTH,
+    SERVER_MESSAGE_LENGTH,
+    MESSAGE_ACTION_SEND_USERNAME,
+    MESSAGE_ACTION_CHOOSE_SKIN,
+    MESSAGE_ACTION_PING,
+    MESSAGE_ACTION_PONG,
+};
+use crate::shared_resources::SharedResources;
+use crate::threads::apply_server_message;
+use crate::recording::{
+    Recorder,
+    Direction,
+};
+
+use mio::{
+    Poll,
+    Events,
+    Interest,
+    Token,
+    Waker,
+};
+use mio::net::TcpStream;
+
+use std::io::{
+    ErrorKind,
+    Read,
+    Write,
+};
+use std::net::SocketAddr;
+use std::sync::{
+    Mutex,
+    Arc,
+};
+use std::sync::mpsc::{
+    Sender,
+    Receiver,
+    SendError,
+    RecvError,
+    TryRecvError,
+    channel,
+};
+use std::thread::{
+    sleep,
+    spawn,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
+const WAKER_TOKEN: Token = Token(0);
+const STREAM_TOKEN: Token = Token(1);
+
+/// Sends the messages of the screens to the network; wakes the event loop up for every message.
+#[derive(Clone)]
+pub struct MessageSender {
+    sender: Sender<Message>,
+    waker: Arc<Waker>,
+}
+
+impl MessageSender {
+
+    /// Sends one message to the server.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to send
+    pub fn send(
+        &self,
+        message: Message,
+    ) -> Result<(), SendError<Message>> {
+
+        self.sender.send(message)?;
+
+        /* the message is sent on the next loop anyway, even if the loop cannot be woken up */
+        let _ = self.waker.wake();
+
+        Ok(())
+    }
+}
+
+/// Receives the messages of the screens, into the event loop or into a sending thread.
+pub struct MessageReceiver {
+    receiver: Receiver<Message>,
+
+    /* the poll the waker of the senders is registered to */
+    poll: Poll,
+}
+
+impl MessageReceiver {
+
+    /// Waits for the next message to send; fails once every sender is dropped.
+    pub fn recv(&self) -> Result<Message, RecvError> {
+        self.receiver.recv()
+    }
+}
+
+/// Creates the channel the screens send their messages to the network through.
+pub fn message_channel() -> (MessageSender, MessageReceiver) {
+
+    let poll = Poll::new().unwrap();
+    let waker = Waker::new(
+        poll.registry(),
+        WAKER_TOKEN,
+    ).unwrap();
+
+    let (sender, receiver) = channel();
+
+    (
+        MessageSender {
+            sender,
+            waker: Arc::new(waker),
+        },
+        MessageReceiver {
+            receiver,
+            poll,
+        },
+    )
+}
+
+/// Status of the connection handled by the event loop.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ConnectionStatus {
+    Connecting,
+    Connected,
+    Reconnecting,
+
+    /* the first connection did not succeed */
+    Failed,
+
+    /* the connection has been lost and could not be established again */
+    Lost,
+}
+
+/// Options of the event loop.
+#[derive(Copy, Clone)]
+pub struct NetworkOptions {
+
+    /* the time a connection attempt is given before failing */
+    pub connect_timeout: Duration,
+
+    /* the delay between two pings */
+    pub ping_interval: Duration,
+
+    /* the connection is considered lost once nothing has been received from the server for this time */
+    pub ping_timeout: Duration,
+
+    /* the amount of connection attempts once the connection is lost, none for no reconnection */
+    pub reconnection_attempts: usize,
+
+    /* the delay before every reconnection attempt */
+    pub reconnection_delay: Duration,
+}
+
+impl Default for NetworkOptions {
+
+    fn default() -> NetworkOptions {
+        NetworkOptions {
+            connect_timeout: Duration::from_secs(3),
+            ping_interval: Duration::from_secs(2),
+            ping_timeout: Duration::from_secs(6),
+            reconnection_attempts: 3,
+            reconnection_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Handles the connection to the server from the event loop thread.
+struct EventLoop {
+    address: SocketAddr,
+    options: NetworkOptions,
+    poll: Poll,
+    receiver: Receiver<Message>,
+    shared_resources: SharedResources,
+    recorder: Option<Arc<Mutex<Recorder>>>,
+    status: Arc<Mutex<ConnectionStatus>>,
+
+    /* notified of the action of every received message, if any */
+    actions_sender: Option<Sender<u8>>,
+
+    stream: Option<TcpStream>,
+    read_buffer: Vec<u8>,
+    write_buffer: Vec<u8>,
+
+    /* the stream is only watched for writing while some bytes are waiting to be written */
+    watching_writes: bool,
+
+    next_ping_time: Instant,
+    ping_sequence: u16,
+
+    /* the sequence number and the sending time of the ping waiting for its answer, if any */
+    pending_ping: Option<(u16, Instant)>,
+
+    last_received_time: Instant,
+
+    /* the last username and skin messages, sent again once reconnected */
+    introduction: Vec<Message>,
+}
+
+/// Starts the event loop thread connecting to the server and exchanging the messages with it.
+/// Returns the status of the connection, updated by the event loop.
+/// The current screen goes back to the connect screen once the connection fails or is lost for good.
+///
+/// # Args:
+///
+/// `address` - the address of the server
+/// `receiver` - receives messages sent from sender from the different screens
+/// `shared_resources` - thread-safe pointers to the resources updated by the server messages
+/// `recorder` - records every received and sent message, if any
+/// `options` - the timeouts, pings and reconnection options
+/// `actions_sender` - notified of the action of every received message, if any (latency measurements)
+pub fn start_network_event_loop(
+    address: SocketAddr,
+    receiver: MessageReceiver,
+    shared_resources: SharedResources,
+    recorder: Option<Arc<Mutex<Recorder>>>,
+    options: NetworkOptions,
+    actions_sender: Option<Sender<u8>>,
+) -> Arc<Mutex<ConnectionStatus>> {
+
+    let status = Arc::new(Mutex::new(ConnectionStatus::Connecting));
+
+    let event_loop = EventLoop {
+        address,
+        options,
+        poll: receiver.poll,
+        receiver: receiver.receiver,
+        shared_resources,
+        recorder,
+        status: status.clone(),
+        actions_sender,
+        stream: None,
+        read_buffer: Vec::new(),
+        write_buffer: Vec::new(),
+        watching_writes: false,
+        next_ping_time: Instant::now(),
+        ping_sequence: 0,
+        pending_ping: None,
+        last_received_time: Instant::now(),
+        introduction: Vec::new(),
+    };
+
+    spawn(move || event_loop.run());
+
+    status
+}
+
+impl EventLoop {
+
+    /// Contains the whole code of the event loop thread.
+    /// Runs until the connection is lost for good or every sender is dropped (the client has been reset).
+    fn run(mut self) {
+
+        if !self.connect() {
+            println!("Cannot connect to the server.");
+            self.stop(ConnectionStatus::Failed);
+            return;
+        }
+
+        self.set_status(ConnectionStatus::Connected);
+        println!("Listening for messages from the server...");
+
+        const EVENTS_CAPACITY: usize = 16;
+        let mut events = Events::with_capacity(EVENTS_CAPACITY);
+
+        loop {
+
+            /* wakes up for the next ping or the ping timeout if nothing happens before */
+            let timeout_time = self.last_received_time + self.options.ping_timeout;
+            let timeout = self.next_ping_time
+                .min(timeout_time)
+                .saturating_duration_since(Instant::now());
+
+            let mut connected = true;
+
+            /* the events cannot be watched anymore, the connection is handled as lost */
+            if let Err(error) = self.poll.poll(&mut events, Some(timeout)) {
+                if error.kind() == ErrorKind::Interrupted {
+                    continue;
+                }
+                println!("Cannot wait for the network events: {}", error);
+                events.clear();
+                connected = false;
+            }
+
+            for event in events.iter() {
+
+                if event.token() != STREAM_TOKEN {
+                    continue;
+                }
+
+                if event.is_readable() || event.is_read_closed() || event.is_error() {
+                    connected &= self.read();
+                }
+            }
+
+            /* the messages are checked even if the waker did not wake the loop up,
+               as the loop may have been woken up by the stream at the same time */
+            if self.queue_screens_messages().is_err() {
+
+                /* the messages sent right before every sender has been dropped are still sent */
+                self.flush();
+                return;
+            }
+
+            if Instant::now() >= self.next_ping_time {
+                self.ping();
+            }
+
+            if self.last_received_time.elapsed() >= self.options.ping_timeout {
+                println!("The server does not answer anymore.");
+                connected = false;
+            }
+
+            connected = connected && self.flush();
+
+            if !connected && !self.reconnect() {
+                println!("Disconnected from the server.");
+                self.stop(ConnectionStatus::Lost);
+                return;
+            }
+        }
+    }
+
+    /// Connects to the server, within the connection timeout. Returns false if the connection failed.
+    fn connect(&mut self) -> bool {
+
+        self.stream = None;
+        self.read_buffer.clear();
+        self.write_buffer.clear();
+        self.watching_writes = false;
+
+        let mut stream = match TcpStream::connect(self.address) {
+            Ok(stream) => stream,
+            Err(_) => return false,
+        };
+
+        /* the stream becomes writable once connected */
+        if self.poll.registry().register(&mut stream, STREAM_TOKEN, Interest::WRITABLE).is_err() {
+            return false;
+        }
+
+        const EVENTS_CAPACITY: usize = 4;
+        let mut events = Events::with_capacity(EVENTS_CAPACITY);
+        let deadline = Instant::now() + self.options.connect_timeout;
+
+        loop {
+
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if timeout == Duration::from_secs(0) {
+                return false;
+            }
+
+            /* the screens messages waking the loop up are sent once connected */
+            if let Err(error) = self.poll.poll(&mut events, Some(timeout)) {
+                if error.kind() == ErrorKind::Interrupted {
+                    continue;
+                }
+                return false;
+            }
+
+            if !events.iter().any(|event| event.token() == STREAM_TOKEN) {
+                continue;
+            }
+
+            match stream.take_error() {
+                Ok(None) => {},
+                _ => return false,
+            };
+
+            match stream.peer_addr() {
+                Ok(_) => break,
+                Err(error) if error.kind() == ErrorKind::NotConnected => continue,
+                Err(_) => return false,
+            };
+        }
+
+        /* the messages are small and sent one by one, they must not wait for the next ones */
+        let _ = stream.set_nodelay(true);
+
+        if self.poll.registry().reregister(&mut stream, STREAM_TOKEN, Interest::READABLE).is_err() {
+            return false;
+        }
+
+        self.stream = Some(stream);
+
+        /* pinged right away, the previous measurements are outdated */
+        self.next_ping_time = Instant::now();
+        self.pending_ping = None;
+        self.last_received_time = Instant::now();
+        self.shared_resources.ping.lock().unwrap().reset();
+
+        true
+    }
+
+    /// Reconnects to the server after the connection has been lost, introduces the player again
+    /// and goes back to the room browser if the player was in a room. Returns false if every attempt failed.
+    fn reconnect(&mut self) -> bool {
+
+        self.stream = None;
+        self.set_status(ConnectionStatus::Reconnecting);
+
+        for attempt in 0..self.options.reconnection_attempts {
+
+            println!("Connection lost, reconnecting (attempt {})...", attempt + 1);
+            sleep(self.options.reconnection_delay);
+
+            if !self.connect() {
+                continue;
+            }
+
+            /* the messages of the new connection follow the marker in the recording */
+            if let Some(recorder) = &self.recorder {
+                recorder.lock().unwrap().record_or_stop(Direction::Reconnected, &[]);
+            }
+
+            for message in self.introduction.clone().iter() {
+                self.queue(message);
+            }
+
+            /* the current screen tells if the player was in a room, which the new session is not in */
+            {
+                let mut current_screen_mutex_guard = self.shared_resources.current_screen.lock().unwrap();
+                let current_screen = &mut *current_screen_mutex_guard;
+
+                if let Screen::Lobby | Screen::Game | Screen::GameOver = *current_screen {
+                    *current_screen = Screen::RoomBrowser;
+                }
+            }
+
+            self.set_status(ConnectionStatus::Connected);
+            return true;
+        }
+
+        false
+    }
+
+    /// Reads every available byte from the server and applies every complete message.
+    /// Returns false if the server is disconnected.
+    fn read(&mut self) -> bool {
+
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => return false,
+        };
+
+        const READ_BUFFER_LENGTH: usize = 4096;
+        let mut buffer = [0; READ_BUFFER_LENGTH];
+        let mut connected = true;
+
+        /* the events are edge-triggered: the stream is read until it would block */
+        loop {
+            match stream.read(&mut buffer) {
+                Ok(0) => {
+                    connected = false;
+                    break;
+                },
+                Ok(length) => {
+                    self.read_buffer.extend_from_slice(&buffer[..length]);
+                    self.last_received_time = Instant::now();
+                },
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => {
+                    connected = false;
+                    break;
+                },
+            };
+        }
+
+        /* every message sent by the server has the same length,
+           the messages received before a disconnection are applied too */
+        while self.read_buffer.len() >= SERVER_MESSAGE_LENGTH {
+
+            let mut message: [u8; SERVER_MESSAGE_LENGTH] = [0; SERVER_MESSAGE_LENGTH];
+            message.copy_from_slice(&self.read_buffer[..SERVER_MESSAGE_LENGTH]);
+            self.read_buffer.drain(..SERVER_MESSAGE_LENGTH);
+
+            if let Some(recorder) = &self.recorder {
+                recorder.lock().unwrap().record_or_stop(Direction::Received, &message);
+            }
+
+            if let Some(actions_sender) = &self.actions_sender {
+                let _ = actions_sender.send(message[0]);
+            }
+
+            /* the pings only concern the network */
+            if message[0] == MESSAGE_ACTION_PONG {
+                self.handle_pong(&message);
+                continue;
+            }
+
+            apply_server_message(
+                &message,
+                &self.shared_resources,
+            );
+        }
+
+        connected
+    }
+
+    /// Queues every message sent by the screens so far. Fails once every sender is dropped.
+    fn queue_screens_messages(&mut self) -> Result<(), TryRecvError> {
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(message) => self.queue(&message),
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(error) => return Err(error),
+            };
+        }
+    }
+
+    /// Queues one message to be written to the server; keeps the introduction messages.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the message to send
+    fn queue(
+        &mut self,
+        message: &Message,
+    ) {
+
+        let action = message.get_action();
+        if action == MESSAGE_ACTION_SEND_USERNAME || action == MESSAGE_ACTION_CHOOSE_SKIN {
+            self.introduction.retain(|introduction| introduction.get_action() != action);
+            self.introduction.push(*message);
+        }
+
+        let data: Vec<u8> = encode_message(message);
+
+        if let Some(recorder) = &self.recorder {
+            recorder.lock().unwrap().record_or_stop(Direction::Sent, &data);
+        }
+
+        self.write_buffer.extend_from_slice(&data);
+    }
+
+    /// Queues a ping with the next sequence number; the previous ping is given up if not answered yet.
+    fn ping(&mut self) {
+
+        self.ping_sequence = self.ping_sequence.wrapping_add(1);
+
+        let mut data = [0; MESSAGE_DATA_LENGTH];
+        data[..2].copy_from_slice(&self.ping_sequence.to_be_bytes());
+
+        let mut message = Message::new(MESSAGE_ACTION_PING);
+        message.set_data(data);
+        self.queue(&message);
+
+        let now = Instant::now();
+        self.pending_ping = Some((self.ping_sequence, now));
+        self.next_ping_time = now + self.options.ping_interval;
+    }
+
+    /// Measures the round trip time of the pending ping if the answer matches it.
+    ///
+    /// # Args:
+    ///
+    /// `message` - the answer of the server, action included
+    fn handle_pong(
+        &mut self,
+        message: &[u8; SERVER_MESSAGE_LENGTH],
+    ) {
+
+        let (sequence, sending_time) = match self.pending_ping {
+            Some(pending_ping) => pending_ping,
+            None => return,
+        };
+
+        if message[1..3] != sequence.to_be_bytes() {
+            return;
+        }
+
+        self.pending_ping = None;
+        self.shared_resources.ping.lock().unwrap().add_measurement(sending_time.elapsed());
+    }
+
+    /// Writes the queued bytes until the stream would block. Returns false if the server is disconnected.
+    fn flush(&mut self) -> bool {
+
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => return false,
+        };
+
+        while !self.write_buffer.is_empty() {
+            match stream.write(&self.write_buffer) {
+                Ok(0) => return false,
+                Ok(length) => {
+                    self.write_buffer.drain(..length);
+                },
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            };
+        }
+
+        /* the remaining bytes are written once the stream is writable again */
+        let watch_writes = !self.write_buffer.is_empty();
+        if watch_writes == self.watching_writes {
+            return true;
+        }
+
+        let interest = if watch_writes {
+            Interest::READABLE | Interest::WRITABLE
+        } else {
+            Interest::READABLE
+        };
+
+        self.watching_writes = watch_writes;
+        self.poll.registry().reregister(stream, STREAM_TOKEN, interest).is_ok()
+    }
+
+    /// Updates the connection status.
+    ///
+    /// # Args:
+    ///
+    /// `status` - the new status
+    fn set_status(
+        &self,
+        status: ConnectionStatus,
+    ) {
+        *self.status.lock().unwrap() = status;
+    }
+
+    /// Stops the connection for good: the main thread resets the client when it goes back to the connect screen.
+    ///
+    /// # Args:
+    ///
+    /// `status` - the final status
+    fn stop(
+        &self,
+        status: ConnectionStatus,
+    ) {
+
+        /* set before going back to the connect screen, so the main thread knows why the client is reset */
+        self.set_status(status);
+
+        let mut current_screen_mutex_guard = self.shared_resources.current_screen.lock().unwrap();
+        let current_screen = &mut *current_screen_mutex_guard;
+        *current_screen = Screen::Connect;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use protocol::{
+        decode_message,
+        CLIENT_MESSAGE_LENGTH,
+        MESSAGE_ACTION_PUSH_MAP,
+    };
+    use crate::mock_server::{
+        MockServer,
+        MockServerStep,
+    };
+    use crate::recording::Recording;
+
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+    use std::net::TcpListener;
+    use std::process;
+
+    /// Waits until the condition is true; fails after five seconds.
+    #[track_caller]
+    fn wait_until(condition: impl Fn() -> bool) {
+
+        let start = Instant::now();
+
+        while !condition() {
+            assert!(start.elapsed() < Duration::from_secs(5), "condition not met in time");
+            sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Sends one message with the given action and text to the server.
+    fn send(
+        sender: &MessageSender,
+        action: u8,
+        text: &str,
+    ) {
+        let mut data = [0; MESSAGE_DATA_LENGTH];
+        data[..text.len()].copy_from_slice(text.as_bytes());
+
+        let mut message = Message::new(action);
+        message.set_data(data);
+        sender.send(message).unwrap();
+    }
+
+    /// Returns quick reconnection options for the tests.
+    fn get_options() -> NetworkOptions {
+        NetworkOptions {
+            reconnection_attempts: 1,
+            reconnection_delay: Duration::from_millis(10),
+            ..NetworkOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_event_loop_exchanges_messages() {
+
+        let server = MockServer::start(vec![
+            MockServerStep::Expect(MESSAGE_ACTION_SEND_USERNAME),
+            MockServerStep::push_map(&[7; 400]),
+        ]);
+
+        let shared_resources = SharedResources::new(Screen::UsernamePrompt);
+        let (sender, receiver) = message_channel();
+        let (actions_sender, actions_receiver) = channel();
+
+        let status = start_network_event_loop(
+            server.get_address(),
+            receiver,
+            shared_resources.clone(),
+            None,
+            get_options(),
+            Some(actions_sender),
+        );
+
+        /* sent before being connected, queued until then */
+        send(&sender, MESSAGE_ACTION_SEND_USERNAME, "JACK");
+
+        wait_until(|| shared_resources.tiles.lock().unwrap()[399] == 7);
+
+        assert_eq!(actions_receiver.recv().unwrap(), MESSAGE_ACTION_PUSH_MAP);
+        assert_eq!(*status.lock().unwrap(), ConnectionStatus::Connected);
+        let username = server
+            .get_received_messages()
+            .into_iter()
+            .find(|message| message.action == MESSAGE_ACTION_SEND_USERNAME)
+            .unwrap();
+        assert_eq!(&username.data[..5], b"JACK\0");
+    }
+
+    #[test]
+    fn test_ping_round_trip_time_is_measured() {
+
+        /* the first ping is sent as soon as connected */
+        let server = MockServer::start(vec![
+            MockServerStep::Expect(MESSAGE_ACTION_PING),
+            MockServerStep::pong(1),
+        ]);
+
+        let shared_resources = SharedResources::new(Screen::UsernamePrompt);
+        let (_sender, receiver) = message_channel();
+
+        start_network_event_loop(
+            server.get_address(),
+            receiver,
+            shared_resources.clone(),
+            None,
+            get_options(),
+            None,
+        );
+
+        wait_until(|| shared_resources.ping.lock().unwrap().get_round_trip_time().is_some());
+
+        let ping = server.get_received_messages()[0];
+        assert_eq!(ping.action, MESSAGE_ACTION_PING);
+        assert_eq!(ping.data[..2], [0, 1]);
+    }
+
+    #[test]
+    fn test_silent_server_is_disconnected() {
+
+        /* the connection stays open, but the pings are never answered */
+        let server = MockServer::start(Vec::new());
+
+        let shared_resources = SharedResources::new(Screen::Game);
+        let (_sender, receiver) = message_channel();
+
+        let status = start_network_event_loop(
+            server.get_address(),
+            receiver,
+            shared_resources.clone(),
+            None,
+            NetworkOptions {
+                ping_interval: Duration::from_millis(20),
+                ping_timeout: Duration::from_millis(100),
+                reconnection_attempts: 0,
+                ..NetworkOptions::default()
+            },
+            None,
+        );
+
+        wait_until(|| *status.lock().unwrap() == ConnectionStatus::Lost);
+        assert_eq!(*shared_resources.current_screen.lock().unwrap(), Screen::Connect);
+    }
+
+    #[test]
+    fn test_connection_failure_goes_back_to_connect_screen() {
+
+        /* nothing listens on the port of a closed listener */
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+        let shared_resources = SharedResources::new(Screen::UsernamePrompt);
+        let (_sender, receiver) = message_channel();
+
+        let status = start_network_event_loop(
+            address,
+            receiver,
+            shared_resources.clone(),
+            None,
+            get_options(),
+            None,
+        );
+
+        wait_until(|| *status.lock().unwrap() == ConnectionStatus::Failed);
+        assert_eq!(*shared_resources.current_screen.lock().unwrap(), Screen::Connect);
+    }
+
+    #[test]
+    fn test_player_is_introduced_again_once_reconnected() {
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        /* reads the username and the skin of two connections in a row;
+           the first one is closed right after, the second one is kept open */
+        let server = spawn(move || {
+
+            let mut introductions = Vec::new();
+            let mut open_stream = None;
+
+            for connection in 0..2 {
+
+                let (mut stream, _) = listener.accept().unwrap();
+                stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+                for _ in 0..2 {
+                    let mut bytes = [0; CLIENT_MESSAGE_LENGTH];
+                    stream.read_exact(&mut bytes).unwrap();
+                    introductions.push(decode_message(&bytes).unwrap());
+                }
+
+                if connection == 1 {
+                    open_stream = Some(stream);
+                }
+            }
+
+            (introductions, open_stream)
+        });
+
+        let shared_resources = SharedResources::new(Screen::UsernamePrompt);
+        let (sender, receiver) = message_channel();
+
+        let path = temp_dir().join(format!("treasure-island-{}-reconnection.rec", process::id()));
+        let recorder = Recorder::create(&path).unwrap();
+
+        let status = start_network_event_loop(
+            address,
+            receiver,
+            shared_resources.clone(),
+            Some(Arc::new(Mutex::new(recorder))),
+            get_options(),
+            None,
+        );
+
+        send(&sender, MESSAGE_ACTION_SEND_USERNAME, "JACK");
+        send(&sender, MESSAGE_ACTION_CHOOSE_SKIN, "");
+        *shared_resources.current_screen.lock().unwrap() = Screen::Lobby;
+
+        let (introductions, _open_stream) = server.join().unwrap();
+        assert_eq!(introductions[..2], introductions[2..]);
+        assert_eq!(&introductions[2].get_data()[..5], b"JACK\0");
+
+        wait_until(|| *status.lock().unwrap() == ConnectionStatus::Connected);
+        assert_eq!(*shared_resources.current_screen.lock().unwrap(), Screen::RoomBrowser);
+
+        /* the introduction sent again follows the reconnection marker */
+        let recording = Recording::load(&path).unwrap();
+        remove_file(&path).unwrap();
+
+        let messages = recording.get_messages();
+        let marker = messages
+            .iter()
+            .position(|message| message.get_direction() == Direction::Reconnected)
+            .unwrap();
+
+        let introduction: Vec<u8> = messages[marker + 1..marker + 3]
+            .iter()
+            .map(|message| message.get_bytes()[0])
+            .collect();
+        assert_eq!(introduction, [MESSAGE_ACTION_SEND_USERNAME, MESSAGE_ACTION_CHOOSE_SKIN]);
+    }
+}
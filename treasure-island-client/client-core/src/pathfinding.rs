@@ -0,0 +1,356 @@
+//! Handles the paths of the characters on the map: which tiles can be walked on,
+//! the shortest path between two tiles and the tiles reachable within a given distance.
+//!
+//! Characters move from one tile to one of its four neighbours (same line or same column).
+
+use crate::shoreline::is_water_tile;
+
+use std::cmp::Reverse;
+use std::collections::{
+    BinaryHeap,
+    VecDeque,
+};
+
+const TILES_AMOUNT: usize = 400;
+const TILES_PER_LINE: usize = 20;
+
+/* maximum amount of steps of one character move */
+pub const MOVEMENT_RANGE: usize = 5;
+
+/// Indicates if a character can walk on the given tile type: sands and chests are walkable,
+/// palm trees block the way and water is impassable; the shores are water, as for the shoreline autotiling.
+///
+/// # Args:
+///
+/// `tile` - the tile sprite index
+pub fn is_tile_walkable(tile: u8) -> bool {
+    const PALM_TILE: u8 = 8;
+    const CHEST_TILE: u8 = 9;
+    tile <= CHEST_TILE && tile != PALM_TILE && !is_water_tile(tile)
+}
+
+/// Returns the shortest path from the start tile to the destination tile, both included,
+/// or none if the destination cannot be reached.
+///
+/// # Args:
+///
+/// `tiles` - the tiles of the map
+/// `start` - the index of the tile of the character
+/// `destination` - the index of the destination tile
+pub fn find_path(
+    tiles: &[u8; TILES_AMOUNT],
+    start: usize,
+    destination: usize,
+) -> Option<Vec<usize>> {
+
+    if !is_tile_walkable(tiles[destination]) {
+        return None;
+    }
+
+    /* A* search: the distance without obstacles is never greater than the real one */
+    let mut costs: [Option<usize>; TILES_AMOUNT] = [None; TILES_AMOUNT];
+    let mut previous_tiles: [Option<usize>; TILES_AMOUNT] = [None; TILES_AMOUNT];
+    let mut open_tiles = BinaryHeap::new();
+
+    costs[start] = Some(0);
+    open_tiles.push(Reverse((get_distance(start, destination), start)));
+
+    while let Some(Reverse((_, position))) = open_tiles.pop() {
+
+        if position == destination {
+
+            let mut path = vec![destination];
+            let mut current = destination;
+            while let Some(previous) = previous_tiles[current] {
+                path.push(previous);
+                current = previous;
+            }
+
+            path.reverse();
+            return Some(path);
+        }
+
+        let neighbour_cost = costs[position].unwrap() + 1;
+
+        for neighbour in get_walkable_neighbours(tiles, position) {
+
+            if costs[neighbour].is_some_and(|cost| cost <= neighbour_cost) {
+                continue;
+            }
+
+            costs[neighbour] = Some(neighbour_cost);
+            previous_tiles[neighbour] = Some(position);
+            open_tiles.push(Reverse((
+                neighbour_cost + get_distance(neighbour, destination),
+                neighbour,
+            )));
+        }
+    }
+
+    None
+}
+
+/// Returns the tiles a character can reach from the start tile within the given amount of steps,
+/// the start tile included.
+///
+/// # Args:
+///
+/// `tiles` - the tiles of the map
+/// `start` - the index of the tile of the character
+/// `range` - the maximum amount of steps
+pub fn get_reachable_tiles(
+    tiles: &[u8; TILES_AMOUNT],
+    start: usize,
+    range: usize,
+) -> [bool; TILES_AMOUNT] {
+
+    let mut reachable_tiles = [false; TILES_AMOUNT];
+    reachable_tiles[start] = true;
+
+    let mut next_tiles = VecDeque::new();
+    next_tiles.push_back((start, 0));
+
+    while let Some((position, steps)) = next_tiles.pop_front() {
+
+        if steps == range {
+            continue;
+        }
+
+        for neighbour in get_walkable_neighbours(tiles, position) {
+
+            if reachable_tiles[neighbour] {
+                continue;
+            }
+
+            reachable_tiles[neighbour] = true;
+            next_tiles.push_back((neighbour, steps + 1));
+        }
+    }
+
+    reachable_tiles
+}
+
+/// Returns the walkable neighbours of the given tile.
+///
+/// # Args:
+///
+/// `tiles` - the tiles of the map
+/// `position` - the index of the tile
+fn get_walkable_neighbours(
+    tiles: &[u8; TILES_AMOUNT],
+    position: usize,
+) -> Vec<usize> {
+
+    let line = position / TILES_PER_LINE;
+    let column = position % TILES_PER_LINE;
+
+    const LAST_INDEX: usize = TILES_PER_LINE - 1;
+    let mut neighbours = Vec::new();
+
+    if line > 0 {
+        neighbours.push(position - TILES_PER_LINE);
+    }
+
+    if line < LAST_INDEX {
+        neighbours.push(position + TILES_PER_LINE);
+    }
+
+    if column > 0 {
+        neighbours.push(position - 1);
+    }
+
+    if column < LAST_INDEX {
+        neighbours.push(position + 1);
+    }
+
+    neighbours.retain(|neighbour| is_tile_walkable(tiles[*neighbour]));
+    neighbours
+}
+
+/// Returns the amount of steps between two tiles when there is no obstacle.
+///
+/// # Args:
+///
+/// `first` - the index of the first tile
+/// `second` - the index of the second tile
+pub fn get_distance(
+    first: usize,
+    second: usize,
+) -> usize {
+
+    let line_distance = (first / TILES_PER_LINE) as isize - (second / TILES_PER_LINE) as isize;
+    let column_distance = (first % TILES_PER_LINE) as isize - (second % TILES_PER_LINE) as isize;
+
+    (line_distance.abs() + column_distance.abs()) as usize
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const SAND: u8 = 0;
+    const PALM: u8 = 8;
+    const WATER: u8 = 10;
+
+    /// Builds a map from lines of characters: '.' for sand, 'P' for palm trees and '~' for water;
+    /// missing tiles are water.
+    fn get_fixture_map(lines: &[&str]) -> [u8; TILES_AMOUNT] {
+
+        let mut tiles = [WATER; TILES_AMOUNT];
+
+        for (line, content) in lines.iter().enumerate() {
+            for (column, character) in content.chars().enumerate() {
+                tiles[line * TILES_PER_LINE + column] = match character {
+                    '.' => SAND,
+                    'P' => PALM,
+                    _ => WATER,
+                };
+            }
+        }
+
+        tiles
+    }
+
+    fn get_index(
+        line: usize,
+        column: usize,
+    ) -> usize {
+        line * TILES_PER_LINE + column
+    }
+
+    #[test]
+    fn test_tiles_walkability() {
+        assert!(is_tile_walkable(SAND));
+        assert!(!is_tile_walkable(5));
+        assert!(is_tile_walkable(9));
+        assert!(!is_tile_walkable(PALM));
+        assert!(!is_tile_walkable(WATER));
+    }
+
+    #[test]
+    fn test_water_tiles_match_shoreline() {
+
+        /* the tiles autotiled as water must never be walkable, and the other sand tiles always are */
+        for tile in 0..=u8::MAX {
+            if is_water_tile(tile) {
+                assert!(!is_tile_walkable(tile), "the water tile {} is walkable", tile);
+            }
+        }
+
+        for tile in 0..=3 {
+            assert!(!is_water_tile(tile));
+            assert!(is_tile_walkable(tile));
+        }
+    }
+
+    #[test]
+    fn test_straight_path() {
+
+        let tiles = get_fixture_map(&[
+            ".....",
+        ]);
+
+        let path = find_path(&tiles, get_index(0, 0), get_index(0, 4)).unwrap();
+
+        assert_eq!(path, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_path_to_the_start_tile() {
+
+        let tiles = get_fixture_map(&[
+            "..",
+        ]);
+
+        assert_eq!(find_path(&tiles, 1, 1).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_path_goes_around_obstacles() {
+
+        let tiles = get_fixture_map(&[
+            ".....",
+            ".PPP.",
+            ".~.~.",
+            "...~.",
+        ]);
+
+        let start = get_index(3, 2);
+        let destination = get_index(0, 2);
+        let path = find_path(&tiles, start, destination).unwrap();
+
+        /* the shortest way goes left, then up the first column and along the first line */
+        assert_eq!(path.len(), 8);
+        assert_eq!(path[0], start);
+        assert_eq!(path[path.len() - 1], destination);
+
+        for (index, position) in path.iter().enumerate() {
+
+            assert!(is_tile_walkable(tiles[*position]));
+
+            if index > 0 {
+                assert_eq!(get_distance(path[index - 1], *position), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_path_does_not_wrap_lines() {
+
+        let mut tiles = [WATER; TILES_AMOUNT];
+        tiles[get_index(0, 19)] = SAND;
+        tiles[get_index(1, 0)] = SAND;
+
+        assert!(find_path(&tiles, get_index(0, 19), get_index(1, 0)).is_none());
+    }
+
+    #[test]
+    fn test_unreachable_destinations() {
+
+        let tiles = get_fixture_map(&[
+            "..~..",
+            "..~.P",
+        ]);
+
+        assert!(find_path(&tiles, get_index(0, 0), get_index(0, 4)).is_none());
+        assert!(find_path(&tiles, get_index(0, 3), get_index(1, 4)).is_none());
+        assert!(find_path(&tiles, get_index(0, 0), get_index(0, 2)).is_none());
+    }
+
+    #[test]
+    fn test_reachable_tiles() {
+
+        let tiles = get_fixture_map(&[
+            "....P.",
+            ".~~.P.",
+        ]);
+
+        let reachable_tiles = get_reachable_tiles(&tiles, get_index(0, 0), 3);
+
+        let expected_tiles = [
+            get_index(0, 0),
+            get_index(0, 1),
+            get_index(0, 2),
+            get_index(0, 3),
+            get_index(1, 0),
+        ];
+
+        for (index, reachable) in reachable_tiles.iter().enumerate() {
+            assert_eq!(*reachable, expected_tiles.contains(&index));
+        }
+    }
+
+    #[test]
+    fn test_no_reachable_tile_without_range() {
+
+        let tiles = get_fixture_map(&[
+            "...",
+        ]);
+
+        let reachable_tiles = get_reachable_tiles(&tiles, 1, 0);
+
+        assert_eq!(reachable_tiles.iter().filter(|reachable| **reachable).count(), 1);
+        assert!(reachable_tiles[1]);
+    }
+}
@@ -0,0 +1,69 @@
+//! Round trip time to the server, measured by the network event loop with the ping messages.
+
+use std::time::Duration;
+
+pub struct Ping {
+    round_trip_time: Option<Duration>,
+}
+
+impl Ping {
+
+    /// Constructor. Nothing is measured yet.
+    pub fn new() -> Ping {
+        Ping {
+            round_trip_time: None,
+        }
+    }
+
+    /// Adds one round trip time measurement. The round trip time is smoothed the way TCP does it,
+    /// so that one late answer does not make the displayed ping jump.
+    ///
+    /// # Args:
+    ///
+    /// `measurement` - the time between a ping and its answer
+    pub fn add_measurement(
+        &mut self,
+        measurement: Duration,
+    ) {
+
+        /* every measurement weighs one eighth */
+        const SMOOTHING_FACTOR: u32 = 8;
+
+        self.round_trip_time = Some(match self.round_trip_time {
+            Some(round_trip_time) => (round_trip_time * (SMOOTHING_FACTOR - 1) + measurement) / SMOOTHING_FACTOR,
+            None => measurement,
+        });
+    }
+
+    /// Forgets the measurements, as they are outdated once reconnected.
+    pub fn reset(&mut self) {
+        self.round_trip_time = None;
+    }
+
+    /// Returns the smoothed round trip time, if measured.
+    pub fn get_round_trip_time(&self) -> Option<Duration> {
+        self.round_trip_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip_time_is_smoothed() {
+
+        let mut ping = Ping::new();
+        assert_eq!(ping.get_round_trip_time(), None);
+
+        ping.add_measurement(Duration::from_millis(80));
+        assert_eq!(ping.get_round_trip_time(), Some(Duration::from_millis(80)));
+
+        ping.add_measurement(Duration::from_millis(160));
+        assert_eq!(ping.get_round_trip_time(), Some(Duration::from_millis(90)));
+
+        ping.reset();
+        assert_eq!(ping.get_round_trip_time(), None);
+    }
+}
@@ -0,0 +1,621 @@
+//! Records the traffic of a game session into a file and replays it in place of the server connection.
+//!
+//! A recording is a sequence of entries, one per message:
+//! the direction (1 byte: 0 for a message received from the server, 1 for a message sent to the server,
+//! 2 for an empty marker written every time the client reconnects to the server),
+//! the time since the start of the session (8 bytes, big endian milliseconds),
+//! the length of the message (2 bytes, big endian) and the message itself.
+//!
+//! A session spans the reconnections: the server messages of every connection are replayed one after the other,
+//! the client is not sent back to the room browser as when it reconnected.
+//!
+//! A recording is replayed by a local server the client connects to, through the same network event loop
+//! as for a real server.
+
+use protocol::{
+    decode_message,
+    CLIENT_MESSAGE_LENGTH,
+    SERVER_MESSAGE_LENGTH,
+    MESSAGE_ACTION_PING,
+    MESSAGE_ACTION_PONG,
+};
+
+use std::fs::File;
+use std::io::{
+    BufReader,
+    BufWriter,
+    Read,
+    Write,
+    Result,
+    Error,
+    ErrorKind,
+};
+use std::path::Path;
+use std::net::{
+    SocketAddr,
+    Ipv4Addr,
+    TcpListener,
+    TcpStream,
+};
+use std::sync::{
+    Mutex,
+    Arc,
+};
+use std::sync::mpsc::Receiver;
+use std::thread::{
+    sleep,
+    park,
+    spawn,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
+const DIRECTION_RECEIVED: u8 = 0;
+const DIRECTION_SENT: u8 = 1;
+const DIRECTION_RECONNECTED: u8 = 2;
+
+/// Direction of a recorded message.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Direction {
+    Received,
+    Sent,
+
+    /* marks a reconnection, without any message */
+    Reconnected,
+}
+
+/// One recorded message.
+pub struct RecordedMessage {
+    direction: Direction,
+    time: Duration,
+    bytes: Vec<u8>,
+}
+
+impl RecordedMessage {
+
+    /// Returns the direction of the message.
+    pub fn get_direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns the time of the message since the start of the session.
+    pub fn get_time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns the message bytes, as sent through the stream.
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Writes every message of the session into a file, timestamped from the creation of the recorder.
+/// Shared between the network threads.
+pub struct Recorder {
+    writer: BufWriter<File>,
+    start: Instant,
+
+    /* set once a message cannot be written, nothing is recorded anymore */
+    stopped: bool,
+}
+
+impl Recorder {
+
+    /// Constructor. Creates (or truncates) the recording file.
+    ///
+    /// # Args:
+    ///
+    /// `path` - the path of the recording file
+    pub fn create(path: &Path) -> Result<Recorder> {
+        Ok(Recorder {
+            writer: BufWriter::new(File::create(path)?),
+            start: Instant::now(),
+            stopped: false,
+        })
+    }
+
+    /// Records one message; the file is flushed after every message, so a crash does not lose the recording.
+    ///
+    /// # Args:
+    ///
+    /// `direction` - the direction of the message
+    /// `bytes` - the message bytes
+    pub fn record(
+        &mut self,
+        direction: Direction,
+        bytes: &[u8],
+    ) -> Result<()> {
+
+        let direction = match direction {
+            Direction::Received => DIRECTION_RECEIVED,
+            Direction::Sent => DIRECTION_SENT,
+            Direction::Reconnected => DIRECTION_RECONNECTED,
+        };
+
+        let time = self.start.elapsed().as_millis() as u64;
+
+        self.writer.write_all(&[direction])?;
+        self.writer.write_all(&time.to_be_bytes())?;
+        self.writer.write_all(&(bytes.len() as u16).to_be_bytes())?;
+        self.writer.write_all(bytes)?;
+        self.writer.flush()
+    }
+
+    /// Records one message, unless the recording stopped. A writing error (disk full...) is reported
+    /// and stops the recording, so the session goes on without it.
+    ///
+    /// # Args:
+    ///
+    /// `direction` - the direction of the message
+    /// `bytes` - the message bytes
+    pub fn record_or_stop(
+        &mut self,
+        direction: Direction,
+        bytes: &[u8],
+    ) {
+
+        if self.stopped {
+            return;
+        }
+
+        if let Err(error) = self.record(direction, bytes) {
+            println!("The recording stopped, the message cannot be written: {}", error);
+            self.stopped = true;
+        }
+    }
+
+    /// Indicates if the recording stopped after a writing error.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+/// Every message of a recorded session, in order.
+pub struct Recording {
+    messages: Vec<RecordedMessage>,
+}
+
+impl Recording {
+
+    /// Loads a recording file.
+    ///
+    /// # Args:
+    ///
+    /// `path` - the path of the recording file
+    pub fn load(path: &Path) -> Result<Recording> {
+
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut messages = Vec::new();
+
+        loop {
+
+            let mut direction: [u8; 1] = [0; 1];
+            if reader.read(&mut direction)? == 0 {
+                break;
+            }
+
+            let direction = match direction[0] {
+                DIRECTION_RECEIVED => Direction::Received,
+                DIRECTION_SENT => Direction::Sent,
+                DIRECTION_RECONNECTED => Direction::Reconnected,
+                _ => return Err(Error::new(ErrorKind::InvalidData, "unknown message direction")),
+            };
+
+            let mut time: [u8; 8] = [0; 8];
+            reader.read_exact(&mut time)?;
+
+            let mut length: [u8; 2] = [0; 2];
+            reader.read_exact(&mut length)?;
+
+            let mut bytes = vec![0; u16::from_be_bytes(length) as usize];
+            reader.read_exact(&mut bytes)?;
+
+            messages.push(RecordedMessage {
+                direction,
+                time: Duration::from_millis(u64::from_be_bytes(time)),
+                bytes,
+            });
+        }
+
+        Ok(Recording {
+            messages,
+        })
+    }
+
+    /// Returns the recorded messages.
+    pub fn get_messages(&self) -> &[RecordedMessage] {
+        &self.messages
+    }
+}
+
+/// Speed of a replay.
+pub enum ReplaySpeed {
+
+    /* the recorded delays between the messages are divided by the factor: 1.0 for the real speed */
+    Factor(f64),
+
+    /* one message is replayed every time a step is received */
+    Step(Receiver<()>),
+}
+
+/// Stream replaying the messages received from the server during a recorded session,
+/// read by the client in place of the server connection. Once every message is replayed, the stream blocks
+/// like an idle connection, so the client keeps the last state of the session.
+pub struct ReplayStream {
+    messages: Vec<Vec<u8>>,
+    times: Vec<Duration>,
+    speed: ReplaySpeed,
+    start: Instant,
+    next_message: usize,
+    buffer: Vec<u8>,
+    buffer_position: usize,
+}
+
+impl ReplayStream {
+
+    /// Constructor. The replay starts when the stream is created.
+    ///
+    /// # Args:
+    ///
+    /// `recording` - the replayed recording; only the messages received from the server are replayed,
+    ///               except the answers to the pings of the recorded session
+    /// `speed` - the speed of the replay
+    pub fn new(
+        recording: &Recording,
+        speed: ReplaySpeed,
+    ) -> ReplayStream {
+
+        let received_messages = recording
+            .get_messages()
+            .iter()
+            .filter(|message| message.get_direction() == Direction::Received)
+            .filter(|message| message.get_bytes().first() != Some(&MESSAGE_ACTION_PONG));
+
+        let mut messages = Vec::new();
+        let mut times = Vec::new();
+        for message in received_messages {
+            messages.push(message.get_bytes().to_vec());
+            times.push(message.get_time());
+        }
+
+        ReplayStream {
+            messages,
+            times,
+            speed,
+            start: Instant::now(),
+            next_message: 0,
+            buffer: Vec::new(),
+            buffer_position: 0,
+        }
+    }
+
+    /// Waits until the next message has to be replayed, according to the replay speed.
+    fn wait_for_next_message(&self) {
+
+        match &self.speed {
+            ReplaySpeed::Factor(factor) => {
+                let time = self.times[self.next_message].div_f64(*factor);
+                if let Some(delay) = time.checked_sub(self.start.elapsed()) {
+                    sleep(delay);
+                }
+            },
+            ReplaySpeed::Step(steps) => {
+
+                /* without any stepper left, the replay stays on the current message */
+                if steps.recv().is_err() {
+                    wait_forever();
+                }
+            },
+        };
+    }
+}
+
+impl Read for ReplayStream {
+
+    fn read(
+        &mut self,
+        buffer: &mut [u8],
+    ) -> Result<usize> {
+
+        if self.buffer_position == self.buffer.len() {
+
+            if self.next_message == self.messages.len() {
+                wait_forever();
+            }
+
+            self.wait_for_next_message();
+
+            self.buffer = self.messages[self.next_message].clone();
+            self.buffer_position = 0;
+            self.next_message += 1;
+        }
+
+        let length = buffer.len().min(self.buffer.len() - self.buffer_position);
+        buffer[..length].copy_from_slice(&self.buffer[self.buffer_position..self.buffer_position + length]);
+        self.buffer_position += length;
+
+        Ok(length)
+    }
+}
+
+/// Starts a local server replaying the recording to the first client connecting to it, in place of the game server.
+/// The messages sent by the client are dropped, except the pings which are answered,
+/// so the replay is never considered as disconnected. Returns the address of the replay server.
+///
+/// # Args:
+///
+/// `recording` - the replayed recording
+/// `speed` - the speed of the replay
+pub fn start_replay_server(
+    recording: &Recording,
+    speed: ReplaySpeed,
+) -> Result<SocketAddr> {
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let address = listener.local_addr()?;
+
+    /* the client connects right after the server is started, the replay starts at the same time */
+    let mut replay_stream = ReplayStream::new(recording, speed);
+
+    spawn(move || {
+
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(_) => return,
+        };
+
+        let read_stream = match stream.try_clone() {
+            Ok(read_stream) => read_stream,
+            Err(_) => return,
+        };
+
+        /* the replayed messages and the answers to the pings are written by different threads */
+        let write_stream = Arc::new(Mutex::new(stream));
+        let pong_stream = write_stream.clone();
+        spawn(move || answer_pings(read_stream, pong_stream));
+
+        let mut message: [u8; SERVER_MESSAGE_LENGTH] = [0; SERVER_MESSAGE_LENGTH];
+        while replay_stream.read_exact(&mut message).is_ok() {
+            if write_stream.lock().unwrap().write_all(&message).is_err() {
+                return;
+            }
+        }
+    });
+
+    Ok(address)
+}
+
+/// Contains the whole code of a dedicated thread of the replay server.
+/// Reads every message sent by the client and answers the pings, until the client is disconnected.
+///
+/// # Args:
+///
+/// `read_stream` - the stream the client messages are read from
+/// `write_stream` - the stream the answers are written to, shared with the replay
+fn answer_pings(
+    mut read_stream: TcpStream,
+    write_stream: Arc<Mutex<TcpStream>>,
+) {
+
+    let mut bytes: [u8; CLIENT_MESSAGE_LENGTH] = [0; CLIENT_MESSAGE_LENGTH];
+
+    while read_stream.read_exact(&mut bytes).is_ok() {
+
+        let message = match decode_message(&bytes) {
+            Some(message) if message.get_action() == MESSAGE_ACTION_PING => message,
+            _ => continue,
+        };
+
+        let mut pong: [u8; SERVER_MESSAGE_LENGTH] = [0; SERVER_MESSAGE_LENGTH];
+        pong[0] = MESSAGE_ACTION_PONG;
+        pong[1..CLIENT_MESSAGE_LENGTH].copy_from_slice(message.get_data());
+
+        if write_stream.lock().unwrap().write_all(&pong).is_err() {
+            return;
+        }
+    }
+}
+
+/// Blocks the current thread forever.
+fn wait_forever() -> ! {
+    loop {
+        park();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use protocol::{
+        Message,
+        encode_message,
+        MESSAGE_DATA_LENGTH,
+    };
+
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+    use std::path::PathBuf;
+    use std::process;
+    use std::sync::mpsc::channel;
+    use std::thread::spawn;
+
+    /// Returns a path into the temporary folder, unique for the given test.
+    fn get_temporary_path(name: &str) -> PathBuf {
+        temp_dir().join(format!("treasure-island-{}-{}.rec", process::id(), name))
+    }
+
+    /// Returns a recording of the given received messages, at the given times in milliseconds.
+    fn get_recording(messages: &[(u64, &[u8])]) -> Recording {
+        Recording {
+            messages: messages
+                .iter()
+                .map(|(time, bytes)| RecordedMessage {
+                    direction: Direction::Received,
+                    time: Duration::from_millis(*time),
+                    bytes: bytes.to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_recording_is_loaded_as_recorded() {
+
+        let path = get_temporary_path("load");
+
+        {
+            let mut recorder = Recorder::create(&path).unwrap();
+            recorder.record(Direction::Sent, &[1, 2, 3]).unwrap();
+            recorder.record(Direction::Received, &[4; 401]).unwrap();
+            recorder.record(Direction::Reconnected, &[]).unwrap();
+        }
+
+        let recording = Recording::load(&path).unwrap();
+        remove_file(&path).unwrap();
+
+        let messages = recording.get_messages();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].get_direction(), Direction::Sent);
+        assert_eq!(messages[0].get_bytes(), &[1, 2, 3]);
+        assert_eq!(messages[1].get_direction(), Direction::Received);
+        assert_eq!(messages[1].get_bytes(), &[4; 401][..]);
+        assert!(messages[0].get_time() <= messages[1].get_time());
+        assert_eq!(messages[2].get_direction(), Direction::Reconnected);
+        assert!(messages[2].get_bytes().is_empty());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_recording_stops_on_writing_error() {
+
+        /* every write into this device fails as if the disk was full */
+        let mut recorder = Recorder::create(Path::new("/dev/full")).unwrap();
+
+        recorder.record_or_stop(Direction::Received, &[4; 401]);
+        assert!(recorder.is_stopped());
+
+        recorder.record_or_stop(Direction::Sent, &[1, 2, 3]);
+        assert!(recorder.is_stopped());
+    }
+
+    #[test]
+    fn test_truncated_recording_is_refused() {
+
+        let path = get_temporary_path("truncated");
+
+        {
+            let mut recorder = Recorder::create(&path).unwrap();
+            recorder.record(Direction::Received, &[4; 401]).unwrap();
+        }
+
+        let mut file = File::options().append(true).open(&path).unwrap();
+        file.write_all(&[DIRECTION_RECEIVED, 0, 0]).unwrap();
+
+        let result = Recording::load(&path);
+        remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_replay_follows_the_recorded_times() {
+
+        let recording = get_recording(&[
+            (0, &[1, 2]),
+            (200, &[3]),
+        ]);
+
+        let mut stream = ReplayStream::new(
+            &recording,
+            ReplaySpeed::Factor(4.0),
+        );
+
+        let start = Instant::now();
+        let mut bytes: [u8; 3] = [0; 3];
+        stream.read_exact(&mut bytes).unwrap();
+
+        assert_eq!(bytes, [1, 2, 3]);
+
+        /* 200 milliseconds replayed four times faster */
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_step_replay_waits_for_steps() {
+
+        let recording = get_recording(&[
+            (0, &[1]),
+            (0, &[2]),
+        ]);
+
+        let (step_sender, step_receiver) = channel();
+        let (bytes_sender, bytes_receiver) = channel();
+
+        let mut stream = ReplayStream::new(
+            &recording,
+            ReplaySpeed::Step(step_receiver),
+        );
+
+        spawn(move || {
+            loop {
+                let mut byte: [u8; 1] = [0; 1];
+                stream.read_exact(&mut byte).unwrap();
+                bytes_sender.send(byte[0]).unwrap();
+            }
+        });
+
+        const TIMEOUT: Duration = Duration::from_millis(100);
+        assert!(bytes_receiver.recv_timeout(TIMEOUT).is_err());
+
+        step_sender.send(()).unwrap();
+        assert_eq!(bytes_receiver.recv_timeout(Duration::from_secs(1)), Ok(1));
+        assert!(bytes_receiver.recv_timeout(TIMEOUT).is_err());
+
+        step_sender.send(()).unwrap();
+        assert_eq!(bytes_receiver.recv_timeout(Duration::from_secs(1)), Ok(2));
+    }
+
+    #[test]
+    fn test_replay_server_replays_and_answers_the_pings() {
+
+        /* the answer to a ping of the recorded session is not replayed */
+        let recording = get_recording(&[
+            (0, &[MESSAGE_ACTION_PONG; SERVER_MESSAGE_LENGTH]),
+            (0, &[1; SERVER_MESSAGE_LENGTH]),
+        ]);
+
+        let address = start_replay_server(
+            &recording,
+            ReplaySpeed::Factor(1.0),
+        ).unwrap();
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        let mut data = [0; MESSAGE_DATA_LENGTH];
+        data[..2].copy_from_slice(&7u16.to_be_bytes());
+        let mut ping = Message::new(MESSAGE_ACTION_PING);
+        ping.set_data(data);
+        stream.write_all(&encode_message(&ping)).unwrap();
+
+        let mut actions = Vec::new();
+        for _ in 0..2 {
+
+            let mut message: [u8; SERVER_MESSAGE_LENGTH] = [0; SERVER_MESSAGE_LENGTH];
+            stream.read_exact(&mut message).unwrap();
+
+            if message[0] == MESSAGE_ACTION_PONG {
+                assert_eq!(message[1..3], [0, 7]);
+            }
+            actions.push(message[0]);
+        }
+
+        actions.sort();
+        assert_eq!(actions, [1, MESSAGE_ACTION_PONG]);
+    }
+}
@@ -0,0 +1,405 @@
+//! Rendering abstraction: screens and GUI components draw through a renderer instead of calling Piston directly,
+//! so the whole client can run and be tested without a window nor a GPU.
+//!
+//! Positions and sizes are in window coordinates; transformations are the ones of the Piston graphics library.
+
+pub type Transform = [[f64; 3]; 2];
+
+/// Everything the client draws on screen. Sprites are identified by their image file name.
+pub trait Renderer {
+
+    /// Returns the size of the drawn area.
+    fn get_view_size(&self) -> [f64; 2];
+
+    /// Returns the transformation to apply to draw at window coordinates.
+    fn get_transform(&self) -> Transform;
+
+    /// Clears the whole drawn area.
+    ///
+    /// # Args:
+    ///
+    /// `color` - the color to fill the area with
+    fn clear(
+        &mut self,
+        color: [f32; 4],
+    );
+
+    /// Draws a sprite, multiplied by the given color; only the given part of the sprite if any,
+    /// displayed at the same position as into the sprite.
+    ///
+    /// # Args:
+    ///
+    /// `sprite` - the image file name of the sprite
+    /// `color` - the color to multiply the sprite with
+    /// `source` - the drawn part of the sprite as [x, y, width, height], the whole sprite if none
+    /// `transform` - the transformation to apply on the sprite
+    fn draw_image(
+        &mut self,
+        sprite: &str,
+        color: [f32; 4],
+        source: Option<[f64; 4]>,
+        transform: Transform,
+    );
+
+    /// Draws a filled rectangle.
+    ///
+    /// # Args:
+    ///
+    /// `color` - the color of the rectangle
+    /// `rectangle` - the rectangle as [x, y, width, height]
+    /// `transform` - the transformation to apply on the rectangle
+    fn draw_rectangle(
+        &mut self,
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    );
+
+    /// Draws the border of a rectangle.
+    ///
+    /// # Args:
+    ///
+    /// `color` - the color of the border
+    /// `radius` - the radius of the border
+    /// `rectangle` - the rectangle as [x, y, width, height]
+    /// `transform` - the transformation to apply on the rectangle
+    fn draw_rectangle_border(
+        &mut self,
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    );
+
+    /// Draws a filled ellipse.
+    ///
+    /// # Args:
+    ///
+    /// `color` - the color of the ellipse
+    /// `rectangle` - the rectangle the ellipse fits into as [x, y, width, height]
+    /// `transform` - the transformation to apply on the ellipse
+    fn draw_ellipse(
+        &mut self,
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    );
+
+    /// Draws the border of an ellipse.
+    ///
+    /// # Args:
+    ///
+    /// `color` - the color of the border
+    /// `radius` - the radius of the border
+    /// `rectangle` - the rectangle the ellipse fits into as [x, y, width, height]
+    /// `transform` - the transformation to apply on the ellipse
+    fn draw_ellipse_border(
+        &mut self,
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    );
+
+    /// Draws a filled polygon.
+    ///
+    /// # Args:
+    ///
+    /// `color` - the color of the polygon
+    /// `points` - the points of the polygon
+    /// `transform` - the transformation to apply on the polygon
+    fn draw_polygon(
+        &mut self,
+        color: [f32; 4],
+        points: &[[f64; 2]],
+        transform: Transform,
+    );
+
+    /// Draws a text with the game font; the transformation moves the beginning of the text baseline.
+    ///
+    /// # Args:
+    ///
+    /// `color` - the color of the text
+    /// `font_size` - the size of the text
+    /// `text` - the text to draw
+    /// `transform` - the transformation to apply on the text
+    fn draw_text(
+        &mut self,
+        color: [f32; 4],
+        font_size: u32,
+        text: &str,
+        transform: Transform,
+    );
+
+    /// Returns the width of a text drawn with the game font.
+    ///
+    /// # Args:
+    ///
+    /// `font_size` - the size of the text
+    /// `text` - the measured text
+    fn get_text_width(
+        &mut self,
+        font_size: u32,
+        text: &str,
+    ) -> f64;
+}
+
+/// One draw call captured by the recording renderer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    Clear {
+        color: [f32; 4],
+    },
+    Image {
+        sprite: String,
+        color: [f32; 4],
+        source: Option<[f64; 4]>,
+        transform: Transform,
+    },
+    Rectangle {
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    },
+    RectangleBorder {
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    },
+    Ellipse {
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    },
+    EllipseBorder {
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    },
+    Polygon {
+        color: [f32; 4],
+        points: Vec<[f64; 2]>,
+        transform: Transform,
+    },
+    Text {
+        color: [f32; 4],
+        font_size: u32,
+        text: String,
+        transform: Transform,
+    },
+}
+
+/// Renderer that draws nothing but records every draw call, used without a window (headless mode and tests).
+/// Clearing the drawn area also forgets the previously recorded commands, so only the last frame is kept.
+pub struct RecordingRenderer {
+    view_size: [f64; 2],
+    commands: Vec<DrawCommand>,
+}
+
+impl RecordingRenderer {
+
+    /// Constructor.
+    ///
+    /// # Args:
+    ///
+    /// `view_size` - the size of the simulated drawn area
+    pub fn new(view_size: [f64; 2]) -> RecordingRenderer {
+        RecordingRenderer {
+            view_size,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Returns the recorded draw commands, in drawing order.
+    pub fn get_commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Returns the recorded texts, in drawing order.
+    pub fn get_texts(&self) -> Vec<&str> {
+        self.commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Renderer for RecordingRenderer {
+
+    fn get_view_size(&self) -> [f64; 2] {
+        self.view_size
+    }
+
+    fn get_transform(&self) -> Transform {
+
+        /* same transformation as Piston: from window coordinates to the [-1, 1] normalized ones */
+        let [width, height] = self.view_size;
+        [
+            [2.0 / width, 0.0, -1.0],
+            [0.0, -2.0 / height, 1.0],
+        ]
+    }
+
+    fn clear(
+        &mut self,
+        color: [f32; 4],
+    ) {
+        self.commands.clear();
+        self.commands.push(DrawCommand::Clear {
+            color,
+        });
+    }
+
+    fn draw_image(
+        &mut self,
+        sprite: &str,
+        color: [f32; 4],
+        source: Option<[f64; 4]>,
+        transform: Transform,
+    ) {
+        self.commands.push(DrawCommand::Image {
+            sprite: sprite.to_string(),
+            color,
+            source,
+            transform,
+        });
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        self.commands.push(DrawCommand::Rectangle {
+            color,
+            rectangle,
+            transform,
+        });
+    }
+
+    fn draw_rectangle_border(
+        &mut self,
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        self.commands.push(DrawCommand::RectangleBorder {
+            color,
+            radius,
+            rectangle,
+            transform,
+        });
+    }
+
+    fn draw_ellipse(
+        &mut self,
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        self.commands.push(DrawCommand::Ellipse {
+            color,
+            rectangle,
+            transform,
+        });
+    }
+
+    fn draw_ellipse_border(
+        &mut self,
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        self.commands.push(DrawCommand::EllipseBorder {
+            color,
+            radius,
+            rectangle,
+            transform,
+        });
+    }
+
+    fn draw_polygon(
+        &mut self,
+        color: [f32; 4],
+        points: &[[f64; 2]],
+        transform: Transform,
+    ) {
+        self.commands.push(DrawCommand::Polygon {
+            color,
+            points: points.to_vec(),
+            transform,
+        });
+    }
+
+    fn draw_text(
+        &mut self,
+        color: [f32; 4],
+        font_size: u32,
+        text: &str,
+        transform: Transform,
+    ) {
+        self.commands.push(DrawCommand::Text {
+            color,
+            font_size,
+            text: text.to_string(),
+            transform,
+        });
+    }
+
+    fn get_text_width(
+        &mut self,
+        font_size: u32,
+        text: &str,
+    ) -> f64 {
+
+        /* there is no font without a window: every character is considered half as large as high */
+        const CHARACTER_WIDTH_RATIO: f64 = 0.5;
+        text.chars().count() as f64 * font_size as f64 * CHARACTER_WIDTH_RATIO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_recording_renderer_keeps_the_last_frame() {
+
+        let mut renderer = RecordingRenderer::new([1920.0, 1080.0]);
+        let transform = renderer.get_transform();
+
+        renderer.clear([0.0, 0.0, 0.0, 1.0]);
+        renderer.draw_text([1.0, 1.0, 1.0, 1.0], 24, "first frame", transform);
+
+        renderer.clear([0.0, 0.0, 0.0, 1.0]);
+        renderer.draw_rectangle([1.0, 1.0, 1.0, 1.0], [0.0, 0.0, 10.0, 10.0], transform);
+        renderer.draw_text([1.0, 1.0, 1.0, 1.0], 24, "second frame", transform);
+
+        assert_eq!(renderer.get_commands().len(), 3);
+        assert_eq!(renderer.get_texts(), vec!["second frame"]);
+    }
+
+    #[test]
+    fn test_recording_renderer_transform_maps_the_window_corners() {
+
+        let renderer = RecordingRenderer::new([1920.0, 1080.0]);
+        let transform = renderer.get_transform();
+
+        let project = |x: f64, y: f64| [
+            transform[0][0] * x + transform[0][1] * y + transform[0][2],
+            transform[1][0] * x + transform[1][1] * y + transform[1][2],
+        ];
+
+        assert_eq!(project(0.0, 0.0), [-1.0, 1.0]);
+        assert_eq!(project(1920.0, 1080.0), [1.0, -1.0]);
+    }
+}
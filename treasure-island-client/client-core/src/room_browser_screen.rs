@@ -0,0 +1,418 @@
+//! Handles the room browser screen, where the player creates or joins a game room.
+
+use crate::screen::Screen;
+use crate::renderer::Renderer;
+use protocol::{
+    Message,
+    MESSAGE_ACTION_REQUEST_ROOMS,
+    MESSAGE_ACTION_CREATE_ROOM,
+    MESSAGE_ACTION_JOIN_ROOM,
+    MESSAGE_ACTION_SPECTATE_ROOM,
+};
+use crate::network::MessageSender;
+use crate::rooms::{
+    RoomList,
+    ROOM_CODE_LENGTH,
+};
+use crate::game_state::PLAYERS_AMOUNT;
+use crate::input::{
+    Action,
+    Direction,
+};
+
+use piston_window::{
+    Transformed,
+    Event,
+    Key,
+    Button,
+    PressEvent,
+};
+
+use std::sync::{
+    Mutex,
+    Arc,
+};
+use std::time::Instant;
+
+/* the menu items displayed after the rooms list */
+const REFRESH_ITEM: usize = 0;
+const CREATE_ITEM: usize = 1;
+const JOIN_BY_CODE_ITEM: usize = 2;
+const JOIN_AS_ITEM: usize = 3;
+const MENU_ITEMS_AMOUNT: usize = 4;
+
+pub struct RoomBrowserScreen {
+    sender: MessageSender,
+    rooms: Arc<Mutex<RoomList>>,
+
+    /* the rooms come first, then the menu items */
+    selected_item: usize,
+
+    code: Vec<u8>,
+    code_selected_digit: u8,
+    rooms_request_previous_time: Option<Instant>,
+
+    /* the rooms are joined to only watch the games; the created rooms are always joined as a player */
+    spectating: bool,
+}
+
+impl RoomBrowserScreen {
+
+    /// Constructor.
+    ///
+    /// # Args:
+    ///
+    /// `sender` - the sender to send messages to the server
+    /// `rooms` - thread safe pointer to the rooms list; shared with the thread that receives the rooms from the server
+    pub fn new(
+        sender: MessageSender,
+        rooms: Arc<Mutex<RoomList>>,
+    ) -> RoomBrowserScreen {
+        RoomBrowserScreen {
+            sender,
+            rooms,
+            selected_item: 0,
+            code: Vec::new(),
+            code_selected_digit: 0,
+            rooms_request_previous_time: None,
+            spectating: false,
+        }
+    }
+
+    /// Renders the screen.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer to draw with
+    pub fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+    ) {
+
+        let transform = renderer.get_transform();
+
+        let rooms_mutex_guard = self.rooms.lock().unwrap();
+        let rooms = &*rooms_mutex_guard;
+
+        const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+        const SELECTED_ITEM_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+        const ERROR_COLOR: [f32; 4] = [0.9, 0.1, 0.1, 1.0];
+
+        const TEXT_HORIZONTAL_POSITION: f64 = 435.0;
+
+        const TITLE_FONT_SIZE: u32 = 64;
+        const TITLE_VERTICAL_POSITION: f64 = 150.0;
+        renderer.draw_text(
+            WHITE_COLOR,
+            TITLE_FONT_SIZE,
+            "Rooms",
+            transform.trans(
+                TEXT_HORIZONTAL_POSITION,
+                TITLE_VERTICAL_POSITION,
+            ),
+        );
+
+        let get_color = |index: usize| {
+            if index == self.selected_item {
+                SELECTED_ITEM_COLOR
+            } else {
+                WHITE_COLOR
+            }
+        };
+
+        const ITEM_FONT_SIZE: u32 = 40;
+        const ROOMS_VERTICAL_POSITION: f64 = 240.0;
+        const ITEMS_VERTICAL_DISTANCE: f64 = 50.0;
+        for (index, room) in rooms.get_rooms().iter().enumerate() {
+
+            let status = if room.is_playing() {
+                "playing"
+            } else {
+                "waiting"
+            };
+
+            let room_line = format!(
+                "{}   {}/{}   {}   #{}",
+                room.get_name(),
+                room.get_players(),
+                room.get_max_players(),
+                status,
+                String::from_utf8_lossy(&room.get_code()),
+            );
+
+            renderer.draw_text(
+                get_color(index),
+                ITEM_FONT_SIZE,
+                &room_line,
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    ROOMS_VERTICAL_POSITION + (index as f64) * ITEMS_VERTICAL_DISTANCE,
+                ),
+            );
+        }
+
+        let rooms_amount = rooms.get_rooms().len();
+
+        let code = String::from_utf8_lossy(&self.code);
+        let code_line = if self.selected_item == rooms_amount + JOIN_BY_CODE_ITEM &&
+            self.code.len() < ROOM_CODE_LENGTH {
+            format!("Join by code: #{}{}", code, self.code_selected_digit)
+        } else {
+            format!("Join by code: #{}", code)
+        };
+
+        let menu_items: [String; MENU_ITEMS_AMOUNT] = [
+            "Refresh".to_string(),
+            "Create room".to_string(),
+            code_line,
+            format!("Join as: < {} >", if self.spectating { "spectator" } else { "player" }),
+        ];
+
+        const MENU_VERTICAL_POSITION: f64 = 800.0;
+        for (index, item) in menu_items.iter().enumerate() {
+            renderer.draw_text(
+                get_color(rooms_amount + index),
+                ITEM_FONT_SIZE,
+                item,
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    MENU_VERTICAL_POSITION + (index as f64) * ITEMS_VERTICAL_DISTANCE,
+                ),
+            );
+        }
+
+        const ERROR_MESSAGE_VERTICAL_POSITION: f64 = 1020.0;
+        if rooms.is_join_refused() {
+            renderer.draw_text(
+                ERROR_COLOR,
+                ITEM_FONT_SIZE,
+                "The room cannot be joined.",
+                transform.trans(
+                    TEXT_HORIZONTAL_POSITION,
+                    ERROR_MESSAGE_VERTICAL_POSITION,
+                ),
+            );
+        }
+    }
+
+    /// Handle the events of the screen. Mutable as it modifies the selection and the room options.
+    /// Regularly requests the rooms list to the server while the screen is displayed.
+    ///
+    /// # Args:
+    ///
+    /// `event` - the event to handle, used to type the room code digits
+    /// `action` - the action triggered by the event, if any
+    /// `current_screen` - reference to the current screen; expected to be mutable to be changed when a room is joined
+    pub fn handle_events(
+        &mut self,
+        event: &Event,
+        action: Option<Action>,
+        current_screen: &mut Screen,
+    ) {
+
+        const ROOMS_REQUEST_INTERVAL: u64 = 2;
+        let request_rooms = match self.rooms_request_previous_time {
+            Some(time) => time.elapsed().as_secs() >= ROOMS_REQUEST_INTERVAL,
+            None => true,
+        };
+
+        if request_rooms {
+            self.request_rooms();
+        }
+
+        if let Some(Button::Keyboard(key)) = event.press_args() {
+            if let Some(digit) = get_digit_from_key(key) {
+                self.push_code_digit(digit);
+                return;
+            }
+        }
+
+        /* the network thread may update the rooms list at any time,
+           so the amount and the selected room are read under the same guard */
+        let rooms = self.rooms.lock().unwrap();
+        let rooms_amount = rooms.get_rooms().len();
+        let items_amount = rooms_amount + MENU_ITEMS_AMOUNT;
+
+        /* the selected room may have disappeared since the last update */
+        if self.selected_item >= items_amount {
+            self.selected_item = items_amount - 1;
+        }
+
+        let selected_menu_item = self.selected_item.checked_sub(rooms_amount);
+        let selected_room_code = rooms.get_rooms()
+            .get(self.selected_item)
+            .map(|room| room.get_code());
+        drop(rooms);
+
+        match action {
+            Some(Action::Move(Direction::Up)) => {
+                self.selected_item = (self.selected_item + items_amount - 1) % items_amount;
+            },
+            Some(Action::Move(Direction::Down)) => {
+                self.selected_item = (self.selected_item + 1) % items_amount;
+            },
+            Some(Action::Move(direction)) if selected_menu_item == Some(JOIN_BY_CODE_ITEM) => {
+
+                /* controllers select the next digit by cycling through the values */
+                const DIGITS_AMOUNT: u8 = 10;
+                if direction == Direction::Left {
+                    self.code_selected_digit = (self.code_selected_digit + DIGITS_AMOUNT - 1) % DIGITS_AMOUNT;
+                }
+                else if direction == Direction::Right {
+                    self.code_selected_digit = (self.code_selected_digit + 1) % DIGITS_AMOUNT;
+                }
+            },
+            Some(Action::Move(Direction::Left)) | Some(Action::Move(Direction::Right))
+                if selected_menu_item == Some(JOIN_AS_ITEM) => {
+                self.spectating = !self.spectating;
+            },
+            Some(Action::Confirm) => {
+                match selected_menu_item {
+                    None => {
+                        if let Some(code) = selected_room_code {
+                            self.join_room(code, current_screen);
+                        }
+                    },
+                    Some(REFRESH_ITEM) => self.request_rooms(),
+                    Some(CREATE_ITEM) => self.create_room(current_screen),
+                    Some(JOIN_AS_ITEM) => self.spectating = !self.spectating,
+                    Some(_) => {
+                        if self.code.len() < ROOM_CODE_LENGTH {
+                            self.push_code_digit(self.code_selected_digit);
+                            return;
+                        }
+
+                        let mut code: [u8; ROOM_CODE_LENGTH] = [0; ROOM_CODE_LENGTH];
+                        code.copy_from_slice(&self.code);
+                        self.join_room(code, current_screen);
+                    },
+                };
+            },
+            Some(Action::Cancel) => {
+                self.code.pop();
+            },
+            _ => {}
+        };
+    }
+
+    /// Appends a digit to the typed room code, if not already complete.
+    ///
+    /// # Args:
+    ///
+    /// `digit` - the digit value (from 0 to 9)
+    fn push_code_digit(
+        &mut self,
+        digit: u8,
+    ) {
+
+        if self.code.len() == ROOM_CODE_LENGTH {
+            return;
+        }
+
+        const DIGIT_CHARACTER_OFFSET: u8 = b'0';
+        self.code.push(DIGIT_CHARACTER_OFFSET + digit);
+        self.code_selected_digit = 0;
+    }
+
+    /// Requests the rooms list to the server.
+    fn request_rooms(&mut self) {
+
+        self.sender.send(Message::new(MESSAGE_ACTION_REQUEST_ROOMS)).unwrap();
+
+        self.rooms_request_previous_time = Some(Instant::now());
+    }
+
+    /// Indicates if the rooms are joined as a spectator.
+    pub fn is_spectating(&self) -> bool {
+        self.spectating
+    }
+
+    /// Requests the creation of a room and goes to its lobby.
+    ///
+    /// # Args:
+    ///
+    /// `current_screen` - reference to the current screen; expected to be mutable to go to the lobby
+    fn create_room(
+        &mut self,
+        current_screen: &mut Screen,
+    ) {
+
+        let mut message = Message::new(MESSAGE_ACTION_CREATE_ROOM);
+
+        const MESSAGE_DATA_LENGTH: usize = 32;
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        /* the game model only handles a fixed amount of players, the rooms are always created for all of them */
+        bytes[0] = PLAYERS_AMOUNT as u8;
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+
+        self.spectating = false;
+        self.go_to_lobby(current_screen);
+    }
+
+    /// Requests to join the room with the given code, as a player or as a spectator, and goes to its lobby.
+    ///
+    /// # Args:
+    ///
+    /// `code` - the code of the room to join
+    /// `current_screen` - reference to the current screen; expected to be mutable to go to the lobby
+    fn join_room(
+        &mut self,
+        code: [u8; ROOM_CODE_LENGTH],
+        current_screen: &mut Screen,
+    ) {
+
+        let mut message = Message::new(if self.spectating {
+            MESSAGE_ACTION_SPECTATE_ROOM
+        } else {
+            MESSAGE_ACTION_JOIN_ROOM
+        });
+
+        const MESSAGE_DATA_LENGTH: usize = 32;
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[..ROOM_CODE_LENGTH].copy_from_slice(&code);
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+
+        self.go_to_lobby(current_screen);
+    }
+
+    /// Goes to the lobby of the created or joined room; the server sends us back to the room browser
+    /// if the room cannot be joined.
+    ///
+    /// # Args:
+    ///
+    /// `current_screen` - reference to the current screen; expected to be mutable to go to the lobby
+    fn go_to_lobby(
+        &mut self,
+        current_screen: &mut Screen,
+    ) {
+
+        self.rooms.lock().unwrap().set_join_refused(false);
+        self.code.clear();
+        self.rooms_request_previous_time = None;
+
+        *current_screen = Screen::Lobby;
+    }
+}
+
+/// Returns the digit of a pressed digit key (top row or numeric keypad).
+///
+/// # Args:
+///
+/// `key` - the pressed key
+fn get_digit_from_key(key: Key) -> Option<u8> {
+    match key {
+        Key::D0 | Key::NumPad0 => Some(0),
+        Key::D1 | Key::NumPad1 => Some(1),
+        Key::D2 | Key::NumPad2 => Some(2),
+        Key::D3 | Key::NumPad3 => Some(3),
+        Key::D4 | Key::NumPad4 => Some(4),
+        Key::D5 | Key::NumPad5 => Some(5),
+        Key::D6 | Key::NumPad6 => Some(6),
+        Key::D7 | Key::NumPad7 => Some(7),
+        Key::D8 | Key::NumPad8 => Some(8),
+        Key::D9 | Key::NumPad9 => Some(9),
+        _ => None,
+    }
+}
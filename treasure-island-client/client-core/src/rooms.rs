@@ -0,0 +1,121 @@
+//! Structure and implementation of the game rooms hosted by the server.
+
+use protocol::get_string_from_bytes;
+
+pub const ROOM_CODE_LENGTH: usize = 4;
+
+/// One game room of the server.
+pub struct Room {
+    name: String,
+    code: [u8; ROOM_CODE_LENGTH],
+    players: u8,
+    max_players: u8,
+    playing: bool,
+}
+
+impl Room {
+
+    /// Returns the room name.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the room code (digits characters), used to join the room.
+    pub fn get_code(&self) -> [u8; ROOM_CODE_LENGTH] {
+        self.code
+    }
+
+    /// Returns the amount of players into the room.
+    pub fn get_players(&self) -> u8 {
+        self.players
+    }
+
+    /// Returns the maximum amount of players of the room.
+    pub fn get_max_players(&self) -> u8 {
+        self.max_players
+    }
+
+    /// Indicates if a game is currently played into the room.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+}
+
+/// Rooms list sent by the server.
+pub struct RoomList {
+    rooms: Vec<Room>,
+    join_refused: bool,
+}
+
+impl RoomList {
+
+    pub fn new() -> RoomList {
+        RoomList {
+            rooms: Vec::new(),
+            join_refused: false,
+        }
+    }
+
+    /// Replaces the rooms by the ones sent by the server.
+    ///
+    /// The data contains the amount of rooms, then for each room its name on 16 bytes (padded with zeros),
+    /// its code on 4 digits characters, its amount of players, its maximum amount of players
+    /// and its status (0 when waiting for players, 1 when playing).
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the rooms list message
+    pub fn update(
+        &mut self,
+        data: &[u8],
+    ) {
+
+        let rooms_amount = data[0] as usize;
+
+        const ROOMS_OFFSET: usize = 1;
+        const ROOM_NAME_LENGTH: usize = 16;
+        const ROOM_LENGTH: usize = ROOM_NAME_LENGTH + ROOM_CODE_LENGTH + 3;
+        const CODE_OFFSET: usize = ROOM_NAME_LENGTH;
+        const PLAYERS_OFFSET: usize = CODE_OFFSET + ROOM_CODE_LENGTH;
+
+        self.rooms = data[ROOMS_OFFSET..]
+            .chunks_exact(ROOM_LENGTH)
+            .take(rooms_amount)
+            .map(|room| {
+
+                let mut code: [u8; ROOM_CODE_LENGTH] = [0; ROOM_CODE_LENGTH];
+                code.copy_from_slice(&room[CODE_OFFSET..PLAYERS_OFFSET]);
+
+                Room {
+                    name: get_string_from_bytes(&room[..ROOM_NAME_LENGTH]),
+                    code,
+                    players: room[PLAYERS_OFFSET],
+                    max_players: room[PLAYERS_OFFSET + 1],
+                    playing: room[PLAYERS_OFFSET + 2] != 0,
+                }
+            })
+            .collect();
+    }
+
+    /// Returns the rooms of the server.
+    pub fn get_rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+
+    /// Sets if the server refused the last room join (unknown code, full room or started game).
+    ///
+    /// # Args:
+    ///
+    /// `join_refused` - true if the join has been refused
+    pub fn set_join_refused(
+        &mut self,
+        join_refused: bool,
+    ) {
+        self.join_refused = join_refused;
+    }
+
+    /// Indicates if the server refused the last room join.
+    pub fn is_join_refused(&self) -> bool {
+        self.join_refused
+    }
+}
@@ -1,8 +1,12 @@
 //! Enumeration that represents every different screen of the game.
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Copy, Clone, Debug)]
 pub enum Screen {
+    Connect,
     UsernamePrompt,
-    WaitingForPlayers,
+    SkinSelection,
+    RoomBrowser,
+    Lobby,
     Game,
+    GameOver,
 }
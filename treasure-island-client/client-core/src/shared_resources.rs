@@ -0,0 +1,50 @@
+//! Contains the resources shared between the main thread and the thread receiving the server messages.
+
+use crate::screen::Screen;
+use crate::lobby::Lobby;
+use crate::rooms::RoomList;
+use crate::chat::ChatLog;
+use crate::game_state::GameState;
+use crate::skins::Skins;
+use crate::ping::Ping;
+
+use std::sync::{
+    Mutex,
+    Arc,
+};
+
+const TILES_AMOUNT: usize = 400;
+
+/// Thread-safe pointers to every shared resource; cloning it only clones the pointers.
+#[derive(Clone)]
+pub struct SharedResources {
+    pub tiles: Arc<Mutex<[u8; TILES_AMOUNT]>>,
+    pub current_screen: Arc<Mutex<Screen>>,
+    pub lobby: Arc<Mutex<Lobby>>,
+    pub rooms: Arc<Mutex<RoomList>>,
+    pub chat_log: Arc<Mutex<ChatLog>>,
+    pub game_state: Arc<Mutex<GameState>>,
+    pub skins: Arc<Mutex<Skins>>,
+    pub ping: Arc<Mutex<Ping>>,
+}
+
+impl SharedResources {
+
+    /// Constructor. Creates every resource with its default content.
+    ///
+    /// # Args:
+    ///
+    /// `current_screen` - the screen displayed when the client starts
+    pub fn new(current_screen: Screen) -> SharedResources {
+        SharedResources {
+            tiles: Arc::new(Mutex::new([0; TILES_AMOUNT])),
+            current_screen: Arc::new(Mutex::new(current_screen)),
+            lobby: Arc::new(Mutex::new(Lobby::new())),
+            rooms: Arc::new(Mutex::new(RoomList::new())),
+            chat_log: Arc::new(Mutex::new(ChatLog::new())),
+            game_state: Arc::new(Mutex::new(GameState::new())),
+            skins: Arc::new(Mutex::new(Skins::new())),
+            ping: Arc::new(Mutex::new(Ping::new())),
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Handles the shoreline autotiling: the server only sends terrain types (sands, palm trees, chests and water),
+//! the client finds the edges of every water tile touching the land and displays a sand strip along them.
+//! A water tile only touching the land by one of its corners displays a corner piece, so the coasts stay continuous.
+//!
+//! On the isometric projection, the previous line is on the upper left edge of a tile,
+//! the next column on its bottom left edge, the next line on its bottom right edge
+//! and the previous column on its upper right edge. The diagonal neighbours touch the tile corners:
+//! the previous line and column on the top corner, the previous line and next column on the left corner,
+//! the next line and column on the bottom corner, the next line and previous column on the right corner.
+
+const TILES_AMOUNT: usize = 400;
+const TILES_PER_LINE: usize = 20;
+
+pub const SHORE_UPPER_LEFT: u8 = 1;
+pub const SHORE_BOTTOM_LEFT: u8 = 2;
+pub const SHORE_BOTTOM_RIGHT: u8 = 4;
+pub const SHORE_UPPER_RIGHT: u8 = 8;
+
+pub const SHORE_EDGES: [u8; 4] = [
+    SHORE_UPPER_LEFT,
+    SHORE_BOTTOM_LEFT,
+    SHORE_BOTTOM_RIGHT,
+    SHORE_UPPER_RIGHT,
+];
+
+pub const SHORE_TOP_CORNER: u8 = 16;
+pub const SHORE_LEFT_CORNER: u8 = 32;
+pub const SHORE_BOTTOM_CORNER: u8 = 64;
+pub const SHORE_RIGHT_CORNER: u8 = 128;
+
+pub const SHORE_CORNERS: [u8; 4] = [
+    SHORE_TOP_CORNER,
+    SHORE_LEFT_CORNER,
+    SHORE_BOTTOM_CORNER,
+    SHORE_RIGHT_CORNER,
+];
+
+/// Indicates if the given tile type is water; the sand and water links sent by older servers are considered as water
+/// and autotiled again, so the coasts stay continuous whatever the server sends.
+///
+/// # Args:
+///
+/// `tile` - the tile sprite index
+pub fn is_water_tile(tile: u8) -> bool {
+    matches!(tile, 4..=7 | 10)
+}
+
+/// Returns the shore edges of every tile: a set of `SHORE_*` flags for every edge of a water tile
+/// shared with a land tile, and for every corner shared with a land tile when both edges around it are not shores
+/// (the edges sprites already cover the corner otherwise); always empty for land tiles.
+/// The outside of the map is considered as water.
+///
+/// # Args:
+///
+/// `tiles` - the tiles of the map
+pub fn get_shore_edges(tiles: &[u8; TILES_AMOUNT]) -> [u8; TILES_AMOUNT] {
+
+    let mut shore_edges = [0; TILES_AMOUNT];
+
+    const LAST_INDEX: usize = TILES_PER_LINE - 1;
+
+    for (index, tile) in tiles.iter().enumerate() {
+
+        if !is_water_tile(*tile) {
+            continue;
+        }
+
+        let line = index / TILES_PER_LINE;
+        let column = index % TILES_PER_LINE;
+
+        let neighbours = [
+            (SHORE_UPPER_LEFT, line > 0, index.wrapping_sub(TILES_PER_LINE)),
+            (SHORE_BOTTOM_LEFT, column < LAST_INDEX, index + 1),
+            (SHORE_BOTTOM_RIGHT, line < LAST_INDEX, index + TILES_PER_LINE),
+            (SHORE_UPPER_RIGHT, column > 0, index.wrapping_sub(1)),
+        ];
+
+        for (edge, exists, neighbour) in neighbours.iter() {
+            if *exists && !is_water_tile(tiles[*neighbour]) {
+                shore_edges[index] |= edge;
+            }
+        }
+
+        let diagonal_neighbours = [
+            (
+                SHORE_TOP_CORNER,
+                SHORE_UPPER_LEFT | SHORE_UPPER_RIGHT,
+                line > 0 && column > 0,
+                index.wrapping_sub(TILES_PER_LINE + 1),
+            ),
+            (
+                SHORE_LEFT_CORNER,
+                SHORE_UPPER_LEFT | SHORE_BOTTOM_LEFT,
+                line > 0 && column < LAST_INDEX,
+                index.wrapping_sub(TILES_PER_LINE - 1),
+            ),
+            (
+                SHORE_BOTTOM_CORNER,
+                SHORE_BOTTOM_LEFT | SHORE_BOTTOM_RIGHT,
+                line < LAST_INDEX && column < LAST_INDEX,
+                index + TILES_PER_LINE + 1,
+            ),
+            (
+                SHORE_RIGHT_CORNER,
+                SHORE_BOTTOM_RIGHT | SHORE_UPPER_RIGHT,
+                line < LAST_INDEX && column > 0,
+                index + TILES_PER_LINE - 1,
+            ),
+        ];
+
+        for (corner, edges, exists, neighbour) in diagonal_neighbours.iter() {
+            if *exists && shore_edges[index] & edges == 0 && !is_water_tile(tiles[*neighbour]) {
+                shore_edges[index] |= corner;
+            }
+        }
+    }
+
+    shore_edges
+}
+
+/// Returns the index of the sand and water link sprite with a sand strip along the given edge.
+///
+/// # Args:
+///
+/// `edge` - one of the `SHORE_*` flags
+pub fn get_shore_sprite(edge: u8) -> usize {
+
+    const FIRST_SHORE_SPRITE_INDEX: usize = 4;
+    FIRST_SHORE_SPRITE_INDEX + edge.trailing_zeros() as usize
+}
+
+/// Returns the index of the sand and water link sprite used for the given corner piece:
+/// the sprite of one of the edges around the corner, as it is covered with sand at both ends of its edge.
+///
+/// # Args:
+///
+/// `corner` - one of the `SHORE_*_CORNER` flags
+pub fn get_shore_corner_sprite(corner: u8) -> usize {
+
+    let edge = match corner {
+        SHORE_TOP_CORNER | SHORE_LEFT_CORNER => SHORE_UPPER_LEFT,
+        _ => SHORE_BOTTOM_RIGHT,
+    };
+
+    get_shore_sprite(edge)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const SAND: u8 = 0;
+    const WATER: u8 = 10;
+
+    /// Builds a map from lines of characters: '.' for sand and '~' for water; missing tiles are water.
+    fn get_fixture_map(lines: &[&str]) -> [u8; TILES_AMOUNT] {
+
+        let mut tiles = [WATER; TILES_AMOUNT];
+
+        for (line, content) in lines.iter().enumerate() {
+            for (column, character) in content.chars().enumerate() {
+                if character == '.' {
+                    tiles[line * TILES_PER_LINE + column] = SAND;
+                }
+            }
+        }
+
+        tiles
+    }
+
+    fn get_index(
+        line: usize,
+        column: usize,
+    ) -> usize {
+        line * TILES_PER_LINE + column
+    }
+
+    #[test]
+    fn test_open_water_has_no_shore() {
+
+        let shore_edges = get_shore_edges(&[WATER; TILES_AMOUNT]);
+
+        assert!(shore_edges.iter().all(|edges| *edges == 0));
+    }
+
+    #[test]
+    fn test_land_has_no_shore() {
+
+        let tiles = get_fixture_map(&[
+            "...",
+            "...",
+        ]);
+
+        let shore_edges = get_shore_edges(&tiles);
+
+        assert_eq!(shore_edges[get_index(0, 1)], 0);
+        assert_eq!(shore_edges[get_index(1, 1)], 0);
+    }
+
+    #[test]
+    fn test_straight_shores() {
+
+        let tiles = get_fixture_map(&[
+            "~~~",
+            "~.~",
+            "~~~",
+        ]);
+
+        let shore_edges = get_shore_edges(&tiles);
+
+        assert_eq!(shore_edges[get_index(0, 1)], SHORE_BOTTOM_RIGHT);
+        assert_eq!(shore_edges[get_index(1, 0)], SHORE_BOTTOM_LEFT);
+        assert_eq!(shore_edges[get_index(2, 1)], SHORE_UPPER_LEFT);
+        assert_eq!(shore_edges[get_index(1, 2)], SHORE_UPPER_RIGHT);
+
+        /* only touching the land by a corner */
+        assert_eq!(shore_edges[get_index(0, 0)], SHORE_BOTTOM_CORNER);
+        assert_eq!(shore_edges[get_index(0, 2)], SHORE_RIGHT_CORNER);
+        assert_eq!(shore_edges[get_index(2, 2)], SHORE_TOP_CORNER);
+        assert_eq!(shore_edges[get_index(2, 0)], SHORE_LEFT_CORNER);
+    }
+
+    #[test]
+    fn test_corner_shores() {
+
+        let tiles = get_fixture_map(&[
+            "...",
+            ".~~",
+            ".~.",
+        ]);
+
+        let shore_edges = get_shore_edges(&tiles);
+
+        /* the land of the top and left corners is already covered by the edges */
+        assert_eq!(shore_edges[get_index(1, 1)], SHORE_UPPER_LEFT | SHORE_UPPER_RIGHT | SHORE_BOTTOM_CORNER);
+        assert_eq!(shore_edges[get_index(1, 2)], SHORE_UPPER_LEFT | SHORE_BOTTOM_RIGHT);
+        assert_eq!(shore_edges[get_index(2, 1)], SHORE_UPPER_RIGHT | SHORE_BOTTOM_LEFT);
+    }
+
+    #[test]
+    fn test_enclosed_pond() {
+
+        let tiles = get_fixture_map(&[
+            "...",
+            ".~.",
+            "...",
+        ]);
+
+        let shore_edges = get_shore_edges(&tiles);
+
+        assert_eq!(shore_edges[get_index(1, 1)], 15);
+    }
+
+    #[test]
+    fn test_shores_do_not_wrap_lines() {
+
+        let mut tiles = [WATER; TILES_AMOUNT];
+        tiles[get_index(0, 19)] = SAND;
+
+        let shore_edges = get_shore_edges(&tiles);
+
+        assert_eq!(shore_edges[get_index(1, 0)], 0);
+        assert_eq!(shore_edges[get_index(0, 18)], SHORE_BOTTOM_LEFT);
+        assert_eq!(shore_edges[get_index(1, 19)], SHORE_UPPER_LEFT);
+        assert_eq!(shore_edges[get_index(1, 18)], SHORE_LEFT_CORNER);
+    }
+
+    #[test]
+    fn test_server_shores_are_autotiled_again() {
+
+        let mut tiles = get_fixture_map(&[
+            "~.",
+        ]);
+        tiles[get_index(0, 0)] = 6;
+
+        let shore_edges = get_shore_edges(&tiles);
+
+        assert_eq!(shore_edges[get_index(0, 0)], SHORE_BOTTOM_LEFT);
+    }
+
+    #[test]
+    fn test_shore_sprites() {
+        assert_eq!(get_shore_sprite(SHORE_UPPER_LEFT), 4);
+        assert_eq!(get_shore_sprite(SHORE_BOTTOM_LEFT), 5);
+        assert_eq!(get_shore_sprite(SHORE_BOTTOM_RIGHT), 6);
+        assert_eq!(get_shore_sprite(SHORE_UPPER_RIGHT), 7);
+        assert_eq!(get_shore_corner_sprite(SHORE_TOP_CORNER), 4);
+        assert_eq!(get_shore_corner_sprite(SHORE_RIGHT_CORNER), 6);
+    }
+}
@@ -0,0 +1,194 @@
+//! Handles the skin selection screen, where the player chooses the pirate it plays with.
+
+use crate::screen::Screen;
+use crate::renderer::Renderer;
+use protocol::{
+    Message,
+    MESSAGE_ACTION_CHOOSE_SKIN,
+};
+use crate::network::MessageSender;
+use crate::skins::{
+    Skins,
+    SKINS,
+    SKINS_AMOUNT,
+};
+use crate::input::{
+    Action,
+    Direction,
+};
+
+use piston_window::Transformed;
+
+use std::sync::{
+    Mutex,
+    Arc,
+};
+
+pub struct SkinSelectionScreen {
+    sender: MessageSender,
+    skins: Arc<Mutex<Skins>>,
+    selected_skin: usize,
+}
+
+impl SkinSelectionScreen {
+
+    /// Constructor.
+    ///
+    /// # Args:
+    ///
+    /// `sender` - the sender to send messages to the server
+    /// `skins` - thread safe pointer to the skins; shared with the thread that receives the taken skins from the server
+    pub fn new(
+        sender: MessageSender,
+        skins: Arc<Mutex<Skins>>,
+    ) -> SkinSelectionScreen {
+
+        SkinSelectionScreen {
+            sender,
+            skins,
+            selected_skin: 0,
+        }
+    }
+
+    /// Renders the screen.
+    ///
+    /// # Args:
+    ///
+    /// `renderer` - the renderer to draw with
+    pub fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+    ) {
+
+        let transform = renderer.get_transform();
+
+        let skins_mutex_guard = self.skins.lock().unwrap();
+        let skins = &*skins_mutex_guard;
+
+        const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+        const SELECTED_SKIN_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+        const TAKEN_SKIN_COLOR: [f32; 4] = [0.4, 0.4, 0.4, 1.0];
+
+        const TEXT_HORIZONTAL_POSITION: f64 = 635.0;
+
+        const TITLE_FONT_SIZE: u32 = 64;
+        const TITLE_VERTICAL_POSITION: f64 = 200.0;
+        renderer.draw_text(
+            WHITE_COLOR,
+            TITLE_FONT_SIZE,
+            "Choose your pirate",
+            transform.trans(
+                TEXT_HORIZONTAL_POSITION,
+                TITLE_VERTICAL_POSITION,
+            ),
+        );
+
+        const SKIN_SCALE: f64 = 2.5;
+        const SKINS_VERTICAL_POSITION: f64 = 300.0;
+        const SKINS_HORIZONTAL_DISTANCE: f64 = 450.0;
+        const SKIN_NAME_FONT_SIZE: u32 = 48;
+        const SKIN_NAME_VERTICAL_POSITION: f64 = 720.0;
+
+        for (index, (sprite, name)) in SKINS.iter().enumerate() {
+
+            let horizontal_position = TEXT_HORIZONTAL_POSITION + (index as f64) * SKINS_HORIZONTAL_DISTANCE;
+            let taken = skins.is_taken(index);
+
+            let sprite_color = if taken {
+                TAKEN_SKIN_COLOR
+            } else {
+                WHITE_COLOR
+            };
+
+            renderer.draw_image(
+                sprite,
+                sprite_color,
+                None,
+                transform
+                    .trans(
+                        horizontal_position,
+                        SKINS_VERTICAL_POSITION,
+                    )
+                    .scale(
+                        SKIN_SCALE,
+                        SKIN_SCALE,
+                    ),
+            );
+
+            let name_color = if index == self.selected_skin {
+                SELECTED_SKIN_COLOR
+            } else if taken {
+                TAKEN_SKIN_COLOR
+            } else {
+                WHITE_COLOR
+            };
+
+            let label = if taken {
+                format!("{} (taken)", name)
+            } else {
+                name.to_string()
+            };
+
+            renderer.draw_text(
+                name_color,
+                SKIN_NAME_FONT_SIZE,
+                &label,
+                transform.trans(
+                    horizontal_position,
+                    SKIN_NAME_VERTICAL_POSITION,
+                ),
+            );
+        }
+    }
+
+    /// Handle the actions of the screen. Mutable as it modifies the selected skin.
+    ///
+    /// # Args:
+    ///
+    /// `action` - the action triggered by the event, if any
+    /// `current_screen` - reference to the current screen; expected to be mutable to be changed when the skin is chosen
+    pub fn handle_events(
+        &mut self,
+        action: Option<Action>,
+        current_screen: &mut Screen,
+    ) {
+
+        match action {
+            Some(Action::Move(Direction::Left)) => {
+                self.selected_skin = (self.selected_skin + SKINS_AMOUNT - 1) % SKINS_AMOUNT;
+            },
+            Some(Action::Move(Direction::Right)) => {
+                self.selected_skin = (self.selected_skin + 1) % SKINS_AMOUNT;
+            },
+            Some(Action::Confirm) => {
+                self.choose_skin(current_screen);
+            },
+            _ => {}
+        };
+    }
+
+    /// Sends the selected skin to the server and goes to the room browser, unless another player took it.
+    ///
+    /// # Args:
+    ///
+    /// `current_screen` - reference to the current screen; expected to be mutable to be changed when the skin is sent
+    fn choose_skin(
+        &mut self,
+        current_screen: &mut Screen,
+    ) {
+
+        if self.skins.lock().unwrap().is_taken(self.selected_skin) {
+            return;
+        }
+
+        let mut message = Message::new(MESSAGE_ACTION_CHOOSE_SKIN);
+
+        const MESSAGE_DATA_LENGTH: usize = 32;
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[0] = self.selected_skin as u8;
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+
+        *current_screen = Screen::RoomBrowser;
+    }
+}
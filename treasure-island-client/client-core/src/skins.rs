@@ -0,0 +1,72 @@
+//! Structure and implementation of the characters skins players choose from; the server tells which ones are taken.
+
+pub const SKINS_AMOUNT: usize = 2;
+
+/// Sprite file and displayed name of every skin, ordered by skin index.
+pub const SKINS: [(&str, &str); SKINS_AMOUNT] = [
+    ("character_1.png", "Blue pirate"),
+    ("character_2.png", "Pink pirate"),
+];
+
+pub struct Skins {
+    taken: [bool; SKINS_AMOUNT],
+}
+
+impl Skins {
+
+    pub fn new() -> Skins {
+        Skins {
+            taken: [false; SKINS_AMOUNT],
+        }
+    }
+
+    /// Updates the skins taken by the other players.
+    ///
+    /// The data contains one byte per skin (ordered by skin index): 1 if the skin is taken by another player, 0 if not.
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the skins message
+    pub fn update(
+        &mut self,
+        data: &[u8],
+    ) {
+        for (taken, byte) in self.taken.iter_mut().zip(data.iter()) {
+            *taken = *byte != 0;
+        }
+    }
+
+    /// Indicates if the given skin is already taken by another player.
+    ///
+    /// # Args:
+    ///
+    /// `skin` - the index of the skin
+    pub fn is_taken(
+        &self,
+        skin: usize,
+    ) -> bool {
+        self.taken[skin]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_update_marks_the_taken_skins() {
+
+        let mut skins = Skins::new();
+        assert!(!skins.is_taken(0));
+
+        skins.update(&[0, 1]);
+        assert!(!skins.is_taken(0));
+        assert!(skins.is_taken(1));
+
+        /* the skins missing from the message keep their state */
+        skins.update(&[1]);
+        assert!(skins.is_taken(0));
+        assert!(skins.is_taken(1));
+    }
+}
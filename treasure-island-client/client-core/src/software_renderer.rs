@@ -0,0 +1,434 @@
+//! Renderer compositing the frames on the CPU into an image, from the PNG sprites of the game.
+//! Used by the snapshot tests to check the map rendering without a window nor a GPU.
+//!
+//! Every shape is rasterized by testing the center of every pixel of its bounding box against the shape,
+//! through the inverse of the shape transformation; sprites use the nearest texel. Texts are not rasterized.
+
+use crate::renderer::{
+    Renderer,
+    Transform,
+};
+
+use image::{
+    Rgba,
+    RgbaImage,
+};
+
+use std::collections::HashMap;
+
+/* the resources of the game binary, next to the core crate */
+const IMAGES_FOLDER: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../res/images/");
+
+pub struct SoftwareRenderer {
+    image: RgbaImage,
+    sprites: HashMap<String, RgbaImage>,
+}
+
+impl SoftwareRenderer {
+
+    /// Constructor. Starts with a fully transparent image.
+    ///
+    /// # Args:
+    ///
+    /// `width` - the width of the image
+    /// `height` - the height of the image
+    pub fn new(
+        width: u32,
+        height: u32,
+    ) -> SoftwareRenderer {
+        SoftwareRenderer {
+            image: RgbaImage::new(width, height),
+            sprites: HashMap::new(),
+        }
+    }
+
+    /// Returns the composited image.
+    pub fn get_image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    /// Returns the given sprite, loaded from the images resources folder the first time it is drawn.
+    ///
+    /// # Args:
+    ///
+    /// `file_name` - the image file name of the sprite
+    fn load_sprite(
+        &mut self,
+        file_name: &str,
+    ) {
+
+        if self.sprites.contains_key(file_name) {
+            return;
+        }
+
+        let sprite = image::open(format!("{}{}", IMAGES_FOLDER, file_name))
+            .unwrap_or_else(|_| panic!("Unknown sprite {}", file_name))
+            .to_rgba8();
+
+        self.sprites.insert(file_name.to_string(), sprite);
+    }
+}
+
+impl Renderer for SoftwareRenderer {
+
+    fn get_view_size(&self) -> [f64; 2] {
+        [
+            self.image.width() as f64,
+            self.image.height() as f64,
+        ]
+    }
+
+    fn get_transform(&self) -> Transform {
+
+        /* same transformation as Piston: from window coordinates to the [-1, 1] normalized ones */
+        let [width, height] = self.get_view_size();
+        [
+            [2.0 / width, 0.0, -1.0],
+            [0.0, -2.0 / height, 1.0],
+        ]
+    }
+
+    fn clear(
+        &mut self,
+        color: [f32; 4],
+    ) {
+        let pixel = get_pixel(color);
+        for destination in self.image.pixels_mut() {
+            *destination = pixel;
+        }
+    }
+
+    fn draw_image(
+        &mut self,
+        sprite: &str,
+        color: [f32; 4],
+        source: Option<[f64; 4]>,
+        transform: Transform,
+    ) {
+
+        self.load_sprite(sprite);
+        let sprite = &self.sprites[sprite];
+
+        /* the drawn part of the sprite is displayed at the same position as into the sprite */
+        let rectangle = source.unwrap_or([
+            0.0,
+            0.0,
+            sprite.width() as f64,
+            sprite.height() as f64,
+        ]);
+
+        fill(
+            &mut self.image,
+            transform,
+            rectangle,
+            |x, y| {
+
+                if !is_into_rectangle(x, y, rectangle) {
+                    return None;
+                }
+
+                let texel = sprite.get_pixel(
+                    (x as u32).min(sprite.width() - 1),
+                    (y as u32).min(sprite.height() - 1),
+                );
+
+                let mut texel_color = [0.0; 4];
+                for (channel, value) in texel_color.iter_mut().enumerate() {
+                    *value = (texel[channel] as f32) / 255.0 * color[channel];
+                }
+
+                Some(texel_color)
+            },
+        );
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        fill(
+            &mut self.image,
+            transform,
+            rectangle,
+            |x, y| if is_into_rectangle(x, y, rectangle) { Some(color) } else { None },
+        );
+    }
+
+    fn draw_rectangle_border(
+        &mut self,
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+
+        /* the border is centered on the rectangle edges */
+        let [x, y, width, height] = rectangle;
+        let outer = [x - radius, y - radius, width + 2.0 * radius, height + 2.0 * radius];
+        let inner = [x + radius, y + radius, width - 2.0 * radius, height - 2.0 * radius];
+
+        fill(
+            &mut self.image,
+            transform,
+            outer,
+            |x, y| {
+                if is_into_rectangle(x, y, outer) && !is_into_rectangle(x, y, inner) {
+                    Some(color)
+                } else {
+                    None
+                }
+            },
+        );
+    }
+
+    fn draw_ellipse(
+        &mut self,
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        fill(
+            &mut self.image,
+            transform,
+            rectangle,
+            |x, y| if is_into_ellipse(x, y, rectangle) { Some(color) } else { None },
+        );
+    }
+
+    fn draw_ellipse_border(
+        &mut self,
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+
+        /* the border is centered on the ellipse */
+        let [x, y, width, height] = rectangle;
+        let outer = [x - radius, y - radius, width + 2.0 * radius, height + 2.0 * radius];
+        let inner = [x + radius, y + radius, width - 2.0 * radius, height - 2.0 * radius];
+
+        fill(
+            &mut self.image,
+            transform,
+            outer,
+            |x, y| {
+                if is_into_ellipse(x, y, outer) && !is_into_ellipse(x, y, inner) {
+                    Some(color)
+                } else {
+                    None
+                }
+            },
+        );
+    }
+
+    fn draw_polygon(
+        &mut self,
+        color: [f32; 4],
+        points: &[[f64; 2]],
+        transform: Transform,
+    ) {
+
+        let left = points.iter().map(|point| point[0]).fold(f64::INFINITY, f64::min);
+        let right = points.iter().map(|point| point[0]).fold(f64::NEG_INFINITY, f64::max);
+        let top = points.iter().map(|point| point[1]).fold(f64::INFINITY, f64::min);
+        let bottom = points.iter().map(|point| point[1]).fold(f64::NEG_INFINITY, f64::max);
+
+        fill(
+            &mut self.image,
+            transform,
+            [left, top, right - left, bottom - top],
+            |x, y| if is_into_polygon(x, y, points) { Some(color) } else { None },
+        );
+    }
+
+    fn draw_text(
+        &mut self,
+        _color: [f32; 4],
+        _font_size: u32,
+        _text: &str,
+        _transform: Transform,
+    ) {
+        /* texts depend on the font rasterizer, they are not part of the snapshots */
+    }
+
+    fn get_text_width(
+        &mut self,
+        font_size: u32,
+        text: &str,
+    ) -> f64 {
+
+        /* rough estimation, texts are not rasterized */
+        (text.chars().count() as f64) * (font_size as f64) * 0.5
+    }
+}
+
+/// Blends a shape into the image: every pixel whose center is into the shape is blended with the shape color there.
+///
+/// # Args:
+///
+/// `image` - the image to draw into
+/// `transform` - the transformation of the shape, from its coordinates to the normalized ones
+/// `bounds` - the bounding rectangle of the shape as [x, y, width, height], into the shape coordinates
+/// `get_color` - returns the color of the shape at the given shape coordinates, none if outside of the shape
+fn fill(
+    image: &mut RgbaImage,
+    transform: Transform,
+    bounds: [f64; 4],
+    get_color: impl Fn(f64, f64) -> Option<[f32; 4]>,
+) {
+
+    /* from the shape coordinates to the image pixels */
+    let half_width = (image.width() as f64) / 2.0;
+    let half_height = (image.height() as f64) / 2.0;
+    let [[a, b, c], [d, e, f]] = transform;
+    let matrix = [
+        [a * half_width, b * half_width, (c + 1.0) * half_width],
+        [-d * half_height, -e * half_height, (1.0 - f) * half_height],
+    ];
+
+    let determinant = matrix[0][0] * matrix[1][1] - matrix[0][1] * matrix[1][0];
+    if determinant == 0.0 {
+        return;
+    }
+
+    let [x, y, width, height] = bounds;
+    let corners = [
+        [x, y],
+        [x + width, y],
+        [x, y + height],
+        [x + width, y + height],
+    ].map(|[x, y]| [
+        matrix[0][0] * x + matrix[0][1] * y + matrix[0][2],
+        matrix[1][0] * x + matrix[1][1] * y + matrix[1][2],
+    ]);
+
+    let left = corners.iter().map(|corner| corner[0]).fold(f64::INFINITY, f64::min).floor().max(0.0);
+    let right = corners.iter().map(|corner| corner[0]).fold(f64::NEG_INFINITY, f64::max).ceil()
+        .min(image.width() as f64);
+    let top = corners.iter().map(|corner| corner[1]).fold(f64::INFINITY, f64::min).floor().max(0.0);
+    let bottom = corners.iter().map(|corner| corner[1]).fold(f64::NEG_INFINITY, f64::max).ceil()
+        .min(image.height() as f64);
+
+    for pixel_y in (top as u32)..(bottom.max(top) as u32) {
+        for pixel_x in (left as u32)..(right.max(left) as u32) {
+
+            /* back to the shape coordinates, from the pixel center */
+            let horizontal = pixel_x as f64 + 0.5 - matrix[0][2];
+            let vertical = pixel_y as f64 + 0.5 - matrix[1][2];
+            let shape_x = (matrix[1][1] * horizontal - matrix[0][1] * vertical) / determinant;
+            let shape_y = (matrix[0][0] * vertical - matrix[1][0] * horizontal) / determinant;
+
+            if let Some(color) = get_color(shape_x, shape_y) {
+                blend(image.get_pixel_mut(pixel_x, pixel_y), color);
+            }
+        }
+    }
+}
+
+/// Blends the given color over the given pixel.
+///
+/// # Args:
+///
+/// `pixel` - the pixel of the image
+/// `color` - the drawn color
+fn blend(
+    pixel: &mut Rgba<u8>,
+    color: [f32; 4],
+) {
+
+    let alpha = color[3];
+    let destination_alpha = (pixel[3] as f32) / 255.0;
+    let result_alpha = alpha + destination_alpha * (1.0 - alpha);
+
+    if result_alpha == 0.0 {
+        return;
+    }
+
+    let mut result = [0.0; 4];
+    for channel in 0..3 {
+        let destination = (pixel[channel] as f32) / 255.0;
+        result[channel] = (color[channel] * alpha + destination * destination_alpha * (1.0 - alpha)) / result_alpha;
+    }
+    result[3] = result_alpha;
+
+    *pixel = get_pixel(result);
+}
+
+/// Converts a color into a pixel.
+///
+/// # Args:
+///
+/// `color` - the color to convert
+fn get_pixel(color: [f32; 4]) -> Rgba<u8> {
+    Rgba(color.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8))
+}
+
+/// Indicates if the given point is into the given rectangle.
+///
+/// # Args:
+///
+/// `x` - the horizontal position of the point
+/// `y` - the vertical position of the point
+/// `rectangle` - the rectangle as [x, y, width, height]
+fn is_into_rectangle(
+    x: f64,
+    y: f64,
+    rectangle: [f64; 4],
+) -> bool {
+    let [left, top, width, height] = rectangle;
+    x >= left && x < left + width && y >= top && y < top + height
+}
+
+/// Indicates if the given point is into the ellipse fitting into the given rectangle.
+///
+/// # Args:
+///
+/// `x` - the horizontal position of the point
+/// `y` - the vertical position of the point
+/// `rectangle` - the rectangle the ellipse fits into as [x, y, width, height]
+fn is_into_ellipse(
+    x: f64,
+    y: f64,
+    rectangle: [f64; 4],
+) -> bool {
+
+    let [left, top, width, height] = rectangle;
+    if width <= 0.0 || height <= 0.0 {
+        return false;
+    }
+
+    let horizontal = (x - left - width / 2.0) / (width / 2.0);
+    let vertical = (y - top - height / 2.0) / (height / 2.0);
+    horizontal * horizontal + vertical * vertical <= 1.0
+}
+
+/// Indicates if the given point is into the given polygon (even-odd rule).
+///
+/// # Args:
+///
+/// `x` - the horizontal position of the point
+/// `y` - the vertical position of the point
+/// `points` - the points of the polygon
+fn is_into_polygon(
+    x: f64,
+    y: f64,
+    points: &[[f64; 2]],
+) -> bool {
+
+    let mut inside = false;
+
+    for (index, [first_x, first_y]) in points.iter().enumerate() {
+
+        let [second_x, second_y] = points[(index + 1) % points.len()];
+
+        if (*first_y > y) != (second_y > y) &&
+            x < first_x + (y - first_y) * (second_x - first_x) / (second_y - first_y) {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
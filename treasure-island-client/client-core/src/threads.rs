@@ -0,0 +1,291 @@
+//! Contains individual threads dedicated codes.
+
+use crate::screen::Screen;
+use protocol::{
+    encode_message,
+    SERVER_MESSAGE_LENGTH,
+    MESSAGE_ACTION_IGNORED,
+    MESSAGE_ACTION_PUSH_MAP,
+    MESSAGE_ACTION_START_GAME,
+    MESSAGE_ACTION_UPDATE_LOBBY,
+    MESSAGE_ACTION_LOBBY_COUNTDOWN,
+    MESSAGE_ACTION_ROOMS_LIST,
+    MESSAGE_ACTION_JOIN_ROOM_REFUSED,
+    MESSAGE_ACTION_CHAT_MESSAGE,
+    MESSAGE_ACTION_DIG_RESULT,
+    MESSAGE_ACTION_TURN,
+    MESSAGE_ACTION_CHARACTER_MOVE,
+    MESSAGE_ACTION_GAME_OVER,
+    MESSAGE_ACTION_SKINS,
+};
+use crate::shared_resources::SharedResources;
+use crate::network::MessageReceiver;
+use crate::recording::{
+    Recorder,
+    Direction,
+};
+
+use std::sync::{
+    Mutex,
+    Arc,
+};
+use std::thread::spawn;
+use std::io::{
+    BufReader,
+    Read,
+    Write,
+};
+
+/// Starts the threads that receive and send the messages through the given streams.
+///
+/// Args:
+///
+/// `read_stream` - the stream the server messages are read from
+/// `write_stream` - the stream the messages are sent to
+/// `receiver` - receives messages sent from sender from the different screens
+/// `shared_resources` - thread-safe pointers to the resources updated by the server messages
+/// `recorder` - records every received and sent message, if any
+pub fn start_network_threads(
+    read_stream: impl Read + Send + 'static,
+    write_stream: impl Write + Send + 'static,
+    receiver: MessageReceiver,
+    shared_resources: SharedResources,
+    recorder: Option<Arc<Mutex<Recorder>>>,
+) {
+
+    let read_buffer = BufReader::new(read_stream);
+    let write_recorder = recorder.clone();
+
+    spawn(|| {
+        receive_message_from_stream(
+            read_buffer,
+            shared_resources,
+            recorder,
+        );
+    });
+
+    spawn(|| {
+        send_message_to_stream(
+            write_stream,
+            receiver,
+            write_recorder,
+        )
+    });
+}
+
+/// Contains the whole code of a dedicated thread.
+/// Continuously checks for messages coming from the server, until the server is disconnected.
+///
+/// Args:
+///
+/// `buffer` - buffer linked to a stream to read the received data
+/// `shared_resources` - thread-safe pointers to the resources updated by the server messages
+/// `recorder` - records every received message, if any
+fn receive_message_from_stream(
+    mut buffer: BufReader<impl Read>,
+    shared_resources: SharedResources,
+    recorder: Option<Arc<Mutex<Recorder>>>,
+) {
+
+    /* maximum data received length is 400 bytes long (for the map);
+       + 1 byte for the action */
+    const BUFFER_LENGTH: usize = SERVER_MESSAGE_LENGTH;
+    let mut message: [u8; BUFFER_LENGTH] = [0; BUFFER_LENGTH];
+
+    println!("Listening for messages from the server...");
+
+    loop {
+
+        /* blocking; every message sent by the server has the same length,
+           so a message is only handled once completely received */
+        if buffer.read_exact(&mut message).is_err() {
+
+            println!("Disconnected from the server.");
+
+            /* the main thread resets the client when it goes back to the connect screen */
+            let mut current_screen_mutex_guard = shared_resources.current_screen.lock().unwrap();
+            let current_screen = &mut *current_screen_mutex_guard;
+            *current_screen = Screen::Connect;
+
+            return;
+        }
+
+        if let Some(recorder) = &recorder {
+            recorder.lock().unwrap().record_or_stop(Direction::Received, &message);
+        }
+
+        apply_server_message(
+            &message,
+            &shared_resources,
+        );
+    }
+}
+
+/// Applies one message received from the server to the shared resources.
+///
+/// Args:
+///
+/// `message` - the complete message, action included
+/// `shared_resources` - thread-safe pointers to the resources updated by the server messages
+pub fn apply_server_message(
+    message: &[u8; SERVER_MESSAGE_LENGTH],
+    shared_resources: &SharedResources,
+) {
+
+    let message_action = message[0];
+
+    if message_action == MESSAGE_ACTION_IGNORED {
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_PUSH_MAP {
+
+        let mut tiles_mutex_guard = shared_resources.tiles.lock().unwrap();
+        let tiles = &mut *tiles_mutex_guard;
+        tiles.copy_from_slice(&message[1..SERVER_MESSAGE_LENGTH]);
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_START_GAME {
+
+        /* the game state is unlocked before locking the current screen
+           as the main thread locks them in the opposite order */
+        if !shared_resources.game_state.lock().unwrap().start(&message[1..SERVER_MESSAGE_LENGTH]) {
+            return;
+        }
+
+        /* the lobby of the started game is not displayed anymore */
+        shared_resources.lobby.lock().unwrap().clear();
+
+        let mut current_screen_mutex_guard = shared_resources.current_screen.lock().unwrap();
+        let current_screen = &mut *current_screen_mutex_guard;
+        *current_screen = Screen::Game;
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_UPDATE_LOBBY {
+
+        let mut lobby_mutex_guard = shared_resources.lobby.lock().unwrap();
+        let lobby = &mut *lobby_mutex_guard;
+        lobby.update(&message[1..SERVER_MESSAGE_LENGTH]);
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_LOBBY_COUNTDOWN {
+
+        let mut lobby_mutex_guard = shared_resources.lobby.lock().unwrap();
+        let lobby = &mut *lobby_mutex_guard;
+        lobby.set_countdown(message[1]);
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_ROOMS_LIST {
+
+        let mut rooms_mutex_guard = shared_resources.rooms.lock().unwrap();
+        let rooms = &mut *rooms_mutex_guard;
+        rooms.update(&message[1..SERVER_MESSAGE_LENGTH]);
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_JOIN_ROOM_REFUSED {
+
+        /* the rooms are unlocked before locking the current screen
+           as the main thread locks them in the opposite order */
+        shared_resources.rooms.lock().unwrap().set_join_refused(true);
+
+        let mut current_screen_mutex_guard = shared_resources.current_screen.lock().unwrap();
+        let current_screen = &mut *current_screen_mutex_guard;
+        *current_screen = Screen::RoomBrowser;
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_CHAT_MESSAGE {
+
+        let mut chat_log_mutex_guard = shared_resources.chat_log.lock().unwrap();
+        let chat_log = &mut *chat_log_mutex_guard;
+        chat_log.add(&message[1..SERVER_MESSAGE_LENGTH]);
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_DIG_RESULT {
+
+        let mut game_state_mutex_guard = shared_resources.game_state.lock().unwrap();
+        let game_state = &mut *game_state_mutex_guard;
+        game_state.apply_dig_result(&message[1..SERVER_MESSAGE_LENGTH]);
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_TURN {
+
+        let mut game_state_mutex_guard = shared_resources.game_state.lock().unwrap();
+        let game_state = &mut *game_state_mutex_guard;
+        game_state.set_turn(&message[1..SERVER_MESSAGE_LENGTH]);
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_CHARACTER_MOVE {
+
+        let mut game_state_mutex_guard = shared_resources.game_state.lock().unwrap();
+        let game_state = &mut *game_state_mutex_guard;
+        game_state.apply_character_move(&message[1..SERVER_MESSAGE_LENGTH]);
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_GAME_OVER {
+
+        /* the game state is unlocked before locking the current screen
+           as the main thread locks them in the opposite order */
+        shared_resources.game_state.lock().unwrap().finish(&message[1..SERVER_MESSAGE_LENGTH]);
+
+        let mut current_screen_mutex_guard = shared_resources.current_screen.lock().unwrap();
+        let current_screen = &mut *current_screen_mutex_guard;
+        *current_screen = Screen::GameOver;
+
+        return;
+    }
+
+    if message_action == MESSAGE_ACTION_SKINS {
+
+        let mut skins_mutex_guard = shared_resources.skins.lock().unwrap();
+        let skins = &mut *skins_mutex_guard;
+        skins.update(&message[1..SERVER_MESSAGE_LENGTH]);
+    }
+}
+
+/// Contains the whole code of a dedicated thread.
+/// Continuously checks for messages to be sent to the server and sends them.
+///
+/// Args:
+///
+/// `stream` - the stream connected to the server, used to send messages
+/// `receiver` - receives messages sent from sender from the different screens
+/// `recorder` - records every sent message, if any
+fn send_message_to_stream(
+    mut stream: impl Write,
+    receiver: MessageReceiver,
+    recorder: Option<Arc<Mutex<Recorder>>>,
+) {
+
+    /* stops once every sender is dropped (the client has been reset) or the server is disconnected */
+    while let Ok(message) = receiver.recv() {
+
+        let data: Vec<u8> = encode_message(&message);
+        if stream.write_all(&data).is_err() {
+            return;
+        }
+
+        if let Some(recorder) = &recorder {
+            recorder.lock().unwrap().record_or_stop(Direction::Sent, &data);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Structure and implementation of the current turn, sent by the server at the beginning of every turn
+//! and every time the active player spends an action.
+
+use crate::game_state::PLAYERS_AMOUNT;
+
+use std::time::Instant;
+
+pub struct Turn {
+    player: usize,
+    number: u16,
+    remaining_actions: u8,
+    remaining_seconds: u16,
+    received: Instant,
+}
+
+impl Turn {
+
+    /// Creates the turn from the server message, none if the active player is unknown.
+    ///
+    /// The data contains the index of the active player, the turn number on two bytes (big endian),
+    /// the remaining actions of the active player and the remaining seconds of the turn on two bytes (big endian).
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data of the turn message
+    pub fn from_bytes(data: &[u8]) -> Option<Turn> {
+
+        let player = data[0] as usize;
+        if player >= PLAYERS_AMOUNT {
+            return None;
+        }
+
+        Some(Turn {
+            player,
+            number: u16::from_be_bytes([data[1], data[2]]),
+            remaining_actions: data[3],
+            remaining_seconds: u16::from_be_bytes([data[4], data[5]]),
+            received: Instant::now(),
+        })
+    }
+
+    /// Returns the index of the player who plays the turn.
+    pub fn get_player(&self) -> usize {
+        self.player
+    }
+
+    /// Returns the turn number, starting at 1.
+    pub fn get_number(&self) -> u16 {
+        self.number
+    }
+
+    /// Returns the amount of actions the active player can still do during the turn.
+    pub fn get_remaining_actions(&self) -> u8 {
+        self.remaining_actions
+    }
+
+    /// Returns the seconds left before the end of the turn, counted down since the message reception.
+    pub fn get_remaining_seconds(&self) -> u64 {
+        (self.remaining_seconds as u64).saturating_sub(self.received.elapsed().as_secs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_from_bytes() {
+
+        let turn = Turn::from_bytes(&[1, 0, 12, 2, 0, 30]).unwrap();
+
+        assert_eq!(turn.get_player(), 1);
+        assert_eq!(turn.get_number(), 12);
+        assert_eq!(turn.get_remaining_actions(), 2);
+        assert_eq!(turn.get_remaining_seconds(), 30);
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_unknown_player() {
+        assert!(Turn::from_bytes(&[PLAYERS_AMOUNT as u8, 0, 12, 2, 0, 30]).is_none());
+        assert!(Turn::from_bytes(&[255, 0, 12, 2, 0, 30]).is_none());
+    }
+
+    #[test]
+    fn test_remaining_seconds_are_counted_down() {
+
+        let mut turn = Turn::from_bytes(&[0, 0, 1, 2, 0, 30]).unwrap();
+
+        /* as if the message had been received ten seconds ago */
+        turn.received = Instant::now() - Duration::from_secs(10);
+        assert_eq!(turn.get_remaining_seconds(), 20);
+
+        turn.received = Instant::now() - Duration::from_secs(40);
+        assert_eq!(turn.get_remaining_seconds(), 0);
+    }
+}
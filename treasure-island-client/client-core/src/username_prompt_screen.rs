@@ -1,37 +1,53 @@
 //! Handles the username prompt screen.
 
 use crate::screen::Screen;
-use crate::message::Message;
+use crate::renderer::Renderer;
+use protocol::{
+    Message,
+    MESSAGE_ACTION_SEND_USERNAME,
+};
+use crate::network::MessageSender;
+use crate::input::{
+    Action,
+    Direction,
+};
 
-use piston_window::text::Text;
 use piston_window::{
-    Glyphs,
-    Context,
     Transformed,
-    G2d,
     Event,
     Key,
     Button,
     PressEvent,
 };
 
-use gfx_device_gl::Device;
+const USERNAME_MAX_LENGTH: usize = 10;
 
-use std::sync::mpsc::Sender;
+/* keys of the on-screen keyboard, used to type the username with a controller */
+const ON_SCREEN_KEYS: [&str; 28] = [
+    "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N",
+    "O", "P", "Q", "R", "S", "T", "U",
+    "V", "W", "X", "Y", "Z", "DEL", "OK",
+];
+const ON_SCREEN_KEYS_PER_LINE: usize = 7;
+const ON_SCREEN_DELETE_KEY: &str = "DEL";
+const ON_SCREEN_VALIDATE_KEY: &str = "OK";
 
 pub struct UsernamePromptScreen {
-    sender: Sender<Message>,
+    sender: MessageSender,
     username: String,
+    selected_key: usize,
 }
 
 impl UsernamePromptScreen {
 
-    pub fn new(sender: Sender<Message>) -> UsernamePromptScreen {
+    pub fn new(sender: MessageSender) -> UsernamePromptScreen {
 
         const DEFAULT_USERNAME: &str = "";
         UsernamePromptScreen {
-            sender: sender,
+            sender,
             username: DEFAULT_USERNAME.to_string(),
+            selected_key: 0,
         }
     }
 
@@ -39,104 +55,114 @@ impl UsernamePromptScreen {
     ///
     /// # Args:
     ///
-    /// `context` - the Piston context to use
-    /// `window` - the Piston window to use expected to be mutable to display stuffs on screen
-    /// `device` - the Piston device to use; expected to be mutable to display stuffs on screen
-    /// `font` - the font to use to render characters; expected to be mutable to render text
+    /// `renderer` - the renderer to draw with
     pub fn render(
         &self,
-        context: Context,
-        window: &mut G2d,
-        device: &mut Device,
-        font: &mut Glyphs,
+        renderer: &mut dyn Renderer,
     ) {
 
+        let transform = renderer.get_transform();
+
         const WHITE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
 
         const CHOOSE_YOUR_USERNAME_MESSAGE_FONT_SIZE: u32 = 64;
         const CHOOSE_YOUR_USERNAME_MESSAGE_HORIZONTAL_POSITION: f64 = 635.0;
         const CHOOSE_YOUR_USERNAME_MESSAGE_VERTICAL_POSITION: f64 = 500.0;
         const CHOOSE_YOUR_USERNAME_MESSAGE: &str = "Choose your username:";
-        Text::new_color(
+        renderer.draw_text(
             WHITE_COLOR,
             CHOOSE_YOUR_USERNAME_MESSAGE_FONT_SIZE,
-        ).draw(
             CHOOSE_YOUR_USERNAME_MESSAGE,
-            font,
-            &context.draw_state,
-            context.transform.trans(
+            transform.trans(
                 CHOOSE_YOUR_USERNAME_MESSAGE_HORIZONTAL_POSITION,
                 CHOOSE_YOUR_USERNAME_MESSAGE_VERTICAL_POSITION,
             ),
-            window
-        ).unwrap();
+        );
 
         const USERNAME_MESSAGE_FONT_SIZE: u32 = 64;
         const USERNAME_MESSAGE_HORIZONTAL_POSITION: f64 = 635.0;
         const USERNAME_MESSAGE_VERTICAL_POSITION: f64 = 600.0;
-        Text::new_color(
+        renderer.draw_text(
             WHITE_COLOR,
             USERNAME_MESSAGE_FONT_SIZE,
-        ).draw(
             &self.username,
-            font,
-            &context.draw_state,
-            context.transform.trans(
+            transform.trans(
                 USERNAME_MESSAGE_HORIZONTAL_POSITION,
                 USERNAME_MESSAGE_VERTICAL_POSITION,
             ),
-            window
-        ).unwrap();
+        );
+
+        const SELECTED_KEY_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+        const KEY_FONT_SIZE: u32 = 48;
+        const KEYS_HORIZONTAL_POSITION: f64 = 635.0;
+        const KEYS_VERTICAL_POSITION: f64 = 720.0;
+        const KEYS_HORIZONTAL_DISTANCE: f64 = 90.0;
+        const KEYS_VERTICAL_DISTANCE: f64 = 70.0;
+
+        for (index, key) in ON_SCREEN_KEYS.iter().enumerate() {
+
+            let color = if index == self.selected_key {
+                SELECTED_KEY_COLOR
+            } else {
+                WHITE_COLOR
+            };
 
-        font.factory
-            .encoder
-            .flush(device);
+            let line = index / ON_SCREEN_KEYS_PER_LINE;
+            let column = index % ON_SCREEN_KEYS_PER_LINE;
+
+            renderer.draw_text(
+                color,
+                KEY_FONT_SIZE,
+                key,
+                transform.trans(
+                    KEYS_HORIZONTAL_POSITION + (column as f64) * KEYS_HORIZONTAL_DISTANCE,
+                    KEYS_VERTICAL_POSITION + (line as f64) * KEYS_VERTICAL_DISTANCE,
+                ),
+            );
+        }
     }
 
     /// Handle the events of the screen. Mutable as it modifies the player username.
+    /// Keyboard keys are directly typed into the username,
+    /// other input devices use the on-screen keyboard through actions.
     ///
     /// # Args:
     ///
     /// `event` - the event to handle
+    /// `action` - the action triggered by the event, if any
     /// `current_screen` - reference to the current screen; expected to be mutable to be changed when the user validates his username
     pub fn handle_events(
         &mut self,
         event: &Event,
+        action: Option<Action>,
         current_screen: &mut Screen,
     ) {
 
         let pressed_key = event.press_args();
 
-        if let Some(Button::Keyboard(Key::Return)) = pressed_key {
-
-            /* forbid empty name validation
-               NOTE: after pressing the Enter key to start the program,
-               it seems the Enter key is still considered as pressed
-               when rendering the first screen, so this condition
-               prevent switching directly to the next screen */
-            if self.username.len() == 0 {
+        match pressed_key {
+            Some(Button::Keyboard(_)) => {},
+            _ => {
+                self.handle_on_screen_keyboard(
+                    action,
+                    current_screen,
+                );
                 return;
             }
+        };
 
-            const MESSAGE_ACTION_SEND_USERNAME: u8 = 1;
-            let mut message = Message::new(MESSAGE_ACTION_SEND_USERNAME);
-
-            let username_bytes: &[u8] = self.username.as_bytes();
-
-            const MESSAGE_DATA_LENGTH: usize = 32;
-            let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
-            bytes[..self.username.len()].copy_from_slice(username_bytes);
-            message.set_data(bytes);
-            self.sender.send(message).unwrap();
-
-            *current_screen = Screen::WaitingForPlayers;
+        if let Some(Button::Keyboard(Key::Return)) = pressed_key {
+            self.validate_username(current_screen);
+            return;
+        }
 
+        if let Some(Button::Keyboard(Key::Backspace)) = pressed_key {
+            self.username.pop();
             return;
         }
 
         /* check if username is not too long before handling letters inputs */
 
-        const USERNAME_MAX_LENGTH: usize = 10;
         if self.username.len() == USERNAME_MAX_LENGTH {
             return;
         }
@@ -248,4 +274,80 @@ impl UsernamePromptScreen {
             self.username.push_str(CHARACTER);
         }
     }
+
+    /// Handle the actions on the on-screen keyboard: moves the selected key or presses it.
+    ///
+    /// # Args:
+    ///
+    /// `action` - the action to handle, if any
+    /// `current_screen` - reference to the current screen; expected to be mutable to be changed when the user validates his username
+    fn handle_on_screen_keyboard(
+        &mut self,
+        action: Option<Action>,
+        current_screen: &mut Screen,
+    ) {
+
+        const KEYS_AMOUNT: usize = ON_SCREEN_KEYS.len();
+
+        match action {
+            Some(Action::Move(direction)) => {
+                self.selected_key = match direction {
+                    Direction::Up => (self.selected_key + KEYS_AMOUNT - ON_SCREEN_KEYS_PER_LINE) % KEYS_AMOUNT,
+                    Direction::Down => (self.selected_key + ON_SCREEN_KEYS_PER_LINE) % KEYS_AMOUNT,
+                    Direction::Left => (self.selected_key + KEYS_AMOUNT - 1) % KEYS_AMOUNT,
+                    Direction::Right => (self.selected_key + 1) % KEYS_AMOUNT,
+                };
+            },
+            Some(Action::Cancel) => {
+                self.username.pop();
+            },
+            Some(Action::Confirm) => {
+
+                let key = ON_SCREEN_KEYS[self.selected_key];
+
+                if key == ON_SCREEN_DELETE_KEY {
+                    self.username.pop();
+                }
+                else if key == ON_SCREEN_VALIDATE_KEY {
+                    self.validate_username(current_screen);
+                }
+                else if self.username.len() < USERNAME_MAX_LENGTH {
+                    self.username.push_str(key);
+                }
+            },
+            _ => {}
+        };
+    }
+
+    /// Sends the username to the server and goes to the next screen.
+    ///
+    /// # Args:
+    ///
+    /// `current_screen` - reference to the current screen; expected to be mutable to be changed when the username is sent
+    fn validate_username(
+        &mut self,
+        current_screen: &mut Screen,
+    ) {
+
+        /* forbid empty name validation
+           NOTE: after pressing the Enter key to start the program,
+           it seems the Enter key is still considered as pressed
+           when rendering the first screen, so this condition
+           prevent switching directly to the next screen */
+        if self.username.is_empty() {
+            return;
+        }
+
+        let mut message = Message::new(MESSAGE_ACTION_SEND_USERNAME);
+
+        let username_bytes: &[u8] = self.username.as_bytes();
+
+        const MESSAGE_DATA_LENGTH: usize = 32;
+        let mut bytes: [u8; MESSAGE_DATA_LENGTH] = [0; MESSAGE_DATA_LENGTH];
+        bytes[..self.username.len()].copy_from_slice(username_bytes);
+        message.set_data(bytes);
+        self.sender.send(message).unwrap();
+
+        *current_screen = Screen::SkinSelection;
+    }
 }
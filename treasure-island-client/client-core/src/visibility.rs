@@ -0,0 +1,138 @@
+//! Structure and implementation of the fog of war: which tiles are currently seen
+//! by the local character and which ones have been seen at least once.
+
+const TILES_AMOUNT: usize = 400;
+const TILES_PER_LINE: usize = 20;
+
+/// Visibility of one tile.
+#[derive(PartialEq, Copy, Clone)]
+pub enum TileVisibility {
+
+    /* never seen: hidden */
+    Unexplored,
+
+    /* seen before but out of sight now: dimmed */
+    Explored,
+
+    /* in sight: displayed normally */
+    Visible,
+}
+
+pub struct Visibility {
+    explored: [bool; TILES_AMOUNT],
+    visible: [bool; TILES_AMOUNT],
+}
+
+impl Default for Visibility {
+
+    fn default() -> Visibility {
+        Visibility::new()
+    }
+}
+
+impl Visibility {
+
+    pub fn new() -> Visibility {
+        Visibility {
+            explored: [false; TILES_AMOUNT],
+            visible: [false; TILES_AMOUNT],
+        }
+    }
+
+    /// Recomputes the visible tiles from the viewer position; all of them become explored.
+    ///
+    /// # Args:
+    ///
+    /// `viewer_position` - the index of the tile of the viewing character
+    /// `sight_radius` - the amount of tiles the character sees around it
+    pub fn update(
+        &mut self,
+        viewer_position: usize,
+        sight_radius: usize,
+    ) {
+
+        let viewer_line = (viewer_position / TILES_PER_LINE) as isize;
+        let viewer_column = (viewer_position % TILES_PER_LINE) as isize;
+        let sight_radius = sight_radius as isize;
+
+        for index in 0..TILES_AMOUNT {
+
+            let line_distance = (index / TILES_PER_LINE) as isize - viewer_line;
+            let column_distance = (index % TILES_PER_LINE) as isize - viewer_column;
+
+            let visible = line_distance * line_distance + column_distance * column_distance <=
+                sight_radius * sight_radius;
+
+            self.visible[index] = visible;
+            self.explored[index] |= visible;
+        }
+    }
+
+    /// Makes every tile visible, for the viewers who are not restricted to the sight of a character.
+    pub fn reveal_all(&mut self) {
+        self.visible = [true; TILES_AMOUNT];
+        self.explored = [true; TILES_AMOUNT];
+    }
+
+    /// Returns the visibility of the given tile.
+    ///
+    /// # Args:
+    ///
+    /// `position` - the index of the tile
+    pub fn get_tile_visibility(
+        &self,
+        position: usize,
+    ) -> TileVisibility {
+
+        if self.visible[position] {
+            return TileVisibility::Visible;
+        }
+
+        if self.explored[position] {
+            return TileVisibility::Explored;
+        }
+
+        TileVisibility::Unexplored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_tiles_within_the_sight_radius_are_visible() {
+
+        let mut visibility = Visibility::new();
+
+        /* the line 10, column 10 */
+        visibility.update(210, 4);
+
+        assert!(visibility.get_tile_visibility(210) == TileVisibility::Visible);
+        assert!(visibility.get_tile_visibility(214) == TileVisibility::Visible);
+        assert!(visibility.get_tile_visibility(215) == TileVisibility::Unexplored);
+        assert!(visibility.get_tile_visibility(130) == TileVisibility::Visible);
+        assert!(visibility.get_tile_visibility(110) == TileVisibility::Unexplored);
+
+        /* 3 lines and 3 columns away: out of the circle of radius 4 */
+        assert!(visibility.get_tile_visibility(273) == TileVisibility::Unexplored);
+        assert!(visibility.get_tile_visibility(252) == TileVisibility::Visible);
+    }
+
+    #[test]
+    fn test_tiles_out_of_sight_stay_explored() {
+
+        let mut visibility = Visibility::new();
+
+        visibility.update(0, 4);
+        visibility.update(399, 4);
+
+        assert!(visibility.get_tile_visibility(0) == TileVisibility::Explored);
+        assert!(visibility.get_tile_visibility(399) == TileVisibility::Visible);
+        assert!(visibility.get_tile_visibility(210) == TileVisibility::Unexplored);
+
+        visibility.reveal_all();
+        assert!(visibility.get_tile_visibility(210) == TileVisibility::Visible);
+    }
+}
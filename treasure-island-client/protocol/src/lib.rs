@@ -0,0 +1,220 @@
+//! Network protocol of Treasure Island, shared by the client, the server and the tools.
+//!
+//! The client sends messages of 33 bytes: 1 byte for the action, 32 bytes of data.
+//! The server sends messages of 401 bytes: 1 byte for the action, 400 bytes of data.
+//! Strings (usernames, room names...) are sent padded with zeros, numbers are big endian.
+//!
+//! Every message is a complete frame: the receiver reads exactly one frame before handling it,
+//! so a server message shorter than 401 bytes must be padded with zeros. The first servers
+//! only sent the map (action 1) and considered any other action as the game start;
+//! the game start now has its own action (2) and the unknown actions are ignored,
+//! so a server has to implement the actions below to be compatible with this client.
+//!
+//! Actions sent by the client:
+//!
+//! | Action | Message        | Data                                              |
+//! |--------|----------------|---------------------------------------------------|
+//! | 1      | username       | the username                                      |
+//! | 2      | set ready      | 1 if ready, 0 otherwise                           |
+//! | 3      | leave lobby    |                                                   |
+//! | 4      | request rooms  |                                                   |
+//! | 5      | create room    | the maximum amount of players                     |
+//! | 6      | join room      | the room code (4 characters)                      |
+//! | 7      | chat message   | the message                                       |
+//! | 8      | dig            | the tile index (2 bytes)                          |
+//! | 9      | move           | the destination tile index (2 bytes)              |
+//! | 10     | return to lobby|                                                   |
+//! | 11     | choose skin    | the skin index                                    |
+//! | 12     | spectate room  | the room code (4 characters)                      |
+//! | 13     | ping           | the ping sequence number (2 bytes)                |
+//!
+//! Actions sent by the server:
+//!
+//! | Action | Message        | Data                                                                          |
+//! |--------|----------------|-------------------------------------------------------------------------------|
+//! | 0      | ignored        |                                                                               |
+//! | 1      | push map       | the 400 tiles                                                                 |
+//! | 2      | start game     | the local player index (255 for a spectator), the usernames, the skins        |
+//! | 3      | update lobby   | the required players, the amount of players, every player and its ready state |
+//! | 4      | countdown      | the seconds before the game starts                                            |
+//! | 5      | rooms list     | the amount of rooms, then every room (name, code, players, maximum, playing)  |
+//! | 6      | join refused   |                                                                               |
+//! | 7      | chat message   | the author and the message                                                    |
+//! | 8      | dig result     | the player, the tile (2 bytes), 1 for a treasure, the player treasures        |
+//! | 9      | turn           | the player, the number (2 bytes), the actions left, seconds left (2 bytes)    |
+//! | 10     | character move | the player and the destination tile index (2 bytes)                           |
+//! | 11     | game over      | the winner (255 for a draw), the treasures, the duration in seconds (2 bytes) |
+//! | 12     | skins          | 1 for every skin taken by another player, 0 otherwise                         |
+//! | 13     | pong           | the data of the answered ping                                                 |
+
+use serde_derive::{
+    Serialize,
+    Deserialize,
+};
+
+/// Length of the data of a message sent by the client.
+pub const MESSAGE_DATA_LENGTH: usize = 32;
+
+/// Length of a message sent by the client, action included.
+pub const CLIENT_MESSAGE_LENGTH: usize = MESSAGE_DATA_LENGTH + 1;
+
+/// Length of the data of a message sent by the server.
+pub const SERVER_MESSAGE_DATA_LENGTH: usize = 400;
+
+/// Length of a message sent by the server, action included.
+pub const SERVER_MESSAGE_LENGTH: usize = SERVER_MESSAGE_DATA_LENGTH + 1;
+
+/* actions of the messages sent by the client (see the table above) */
+
+pub const MESSAGE_ACTION_SEND_USERNAME: u8 = 1;
+pub const MESSAGE_ACTION_SET_READY: u8 = 2;
+pub const MESSAGE_ACTION_LEAVE_LOBBY: u8 = 3;
+pub const MESSAGE_ACTION_REQUEST_ROOMS: u8 = 4;
+pub const MESSAGE_ACTION_CREATE_ROOM: u8 = 5;
+pub const MESSAGE_ACTION_JOIN_ROOM: u8 = 6;
+pub const MESSAGE_ACTION_SEND_CHAT_MESSAGE: u8 = 7;
+pub const MESSAGE_ACTION_DIG: u8 = 8;
+pub const MESSAGE_ACTION_MOVE: u8 = 9;
+pub const MESSAGE_ACTION_RETURN_TO_LOBBY: u8 = 10;
+pub const MESSAGE_ACTION_CHOOSE_SKIN: u8 = 11;
+pub const MESSAGE_ACTION_SPECTATE_ROOM: u8 = 12;
+pub const MESSAGE_ACTION_PING: u8 = 13;
+
+/* actions of the messages sent by the server (see the table above) */
+
+pub const MESSAGE_ACTION_IGNORED: u8 = 0;
+pub const MESSAGE_ACTION_PUSH_MAP: u8 = 1;
+pub const MESSAGE_ACTION_START_GAME: u8 = 2;
+pub const MESSAGE_ACTION_UPDATE_LOBBY: u8 = 3;
+pub const MESSAGE_ACTION_LOBBY_COUNTDOWN: u8 = 4;
+pub const MESSAGE_ACTION_ROOMS_LIST: u8 = 5;
+pub const MESSAGE_ACTION_JOIN_ROOM_REFUSED: u8 = 6;
+pub const MESSAGE_ACTION_CHAT_MESSAGE: u8 = 7;
+pub const MESSAGE_ACTION_DIG_RESULT: u8 = 8;
+pub const MESSAGE_ACTION_TURN: u8 = 9;
+pub const MESSAGE_ACTION_CHARACTER_MOVE: u8 = 10;
+pub const MESSAGE_ACTION_GAME_OVER: u8 = 11;
+pub const MESSAGE_ACTION_SKINS: u8 = 12;
+pub const MESSAGE_ACTION_PONG: u8 = 13;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Message {
+
+    /* we do not use enums to send actions because:
+       - this is a "raw network" information to be handled both on client and server,
+         having an enumeration may require to maintain same version of the structure
+         from both side,
+       - enumerations might take a padded space in memory according to the architecture,
+         but we have to be sure we only uses one bit,
+       - enumerations are tagged unions, so they may take a few bytes for the type too */
+    action: u8,
+    data: [u8; MESSAGE_DATA_LENGTH]
+}
+
+impl Message {
+
+    /// Constructor
+    ///
+    /// # Args:
+    ///
+    /// `action` - the action of the message
+    pub fn new(action: u8) -> Self {
+        Message {
+            action,
+            data: [0; MESSAGE_DATA_LENGTH]
+        }
+    }
+
+    /// Sets the data to send (32 bytes).
+    ///
+    /// # Args:
+    ///
+    /// `data` - the data to send
+    pub fn set_data(
+        &mut self,
+        data: [u8; MESSAGE_DATA_LENGTH],
+    ) {
+        self.data = data;
+    }
+
+    /// Returns the action of the message.
+    pub fn get_action(&self) -> u8 {
+        self.action
+    }
+
+    /// Returns the data of the message.
+    pub fn get_data(&self) -> &[u8; MESSAGE_DATA_LENGTH] {
+        &self.data
+    }
+}
+
+/// Encodes a message into the bytes sent through the stream.
+///
+/// # Args:
+///
+/// `message` - the message to encode
+pub fn encode_message(message: &Message) -> Vec<u8> {
+    bincode::serialize(message).unwrap()
+}
+
+/// Decodes a message from the bytes received through the stream, none if they are not a message.
+///
+/// # Args:
+///
+/// `bytes` - the received bytes, exactly one message long
+pub fn decode_message(bytes: &[u8]) -> Option<Message> {
+
+    if bytes.len() != CLIENT_MESSAGE_LENGTH {
+        return None;
+    }
+
+    bincode::deserialize(bytes).ok()
+}
+
+/// Converts a zero-padded string sent by the server (username, room name...) into a string.
+///
+/// # Args:
+///
+/// `bytes` - the string bytes
+pub fn get_string_from_bytes(bytes: &[u8]) -> String {
+
+    let length = bytes.iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+
+    String::from_utf8_lossy(&bytes[..length]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_message_is_encoded_as_action_then_data() {
+
+        let mut message = Message::new(7);
+        let mut data = [0; MESSAGE_DATA_LENGTH];
+        data[0] = 42;
+        data[MESSAGE_DATA_LENGTH - 1] = 24;
+        message.set_data(data);
+
+        let bytes = encode_message(&message);
+
+        assert_eq!(bytes.len(), CLIENT_MESSAGE_LENGTH);
+        assert_eq!(bytes[0], 7);
+        assert_eq!(&bytes[1..], &data[..]);
+        assert_eq!(decode_message(&bytes), Some(message));
+    }
+
+    #[test]
+    fn test_truncated_message_is_not_decoded() {
+        assert_eq!(decode_message(&[7; CLIENT_MESSAGE_LENGTH - 1]), None);
+    }
+
+    #[test]
+    fn test_padded_string_is_trimmed() {
+        assert_eq!(get_string_from_bytes(b"JACK\0\0\0"), "JACK");
+        assert_eq!(get_string_from_bytes(b"ANNE"), "ANNE");
+    }
+}
@@ -0,0 +1,106 @@
+//! Load testing tool: connects many headless clients to a server and reports how the server holds on.
+//!
+//! Usage: `treasure-island-load-test <address> [--clients <count>] [--requests <count>] [--interval <milliseconds>]
+//! [--network <threads|event-loop>] [--compare]`
+//!
+//! The clients run on the network event loop by default; `--compare` runs the test on both network cores
+//! one after the other, to compare their latencies.
+
+use client_core::load_test::{
+    LoadTestOptions,
+    NetworkCore,
+    run_load_test,
+};
+
+use std::env;
+use std::time::Duration;
+
+fn main() {
+
+    let arguments: Vec<String> = env::args().collect();
+
+    let address = arguments
+        .get(1)
+        .and_then(|address| address.parse().ok())
+        .expect("The first argument must be the server address (IP address and port)");
+
+    const CLIENTS_OPTION: &str = "--clients";
+    const REQUESTS_OPTION: &str = "--requests";
+    const INTERVAL_OPTION: &str = "--interval";
+    const NETWORK_OPTION: &str = "--network";
+    const COMPARE_OPTION: &str = "--compare";
+
+    let network = match get_option_value(&arguments, NETWORK_OPTION) {
+        Some(name) => NetworkCore::from_name(name).expect("The network must be 'threads' or 'event-loop'"),
+        None => NetworkCore::EventLoop,
+    };
+
+    let networks = if arguments.iter().any(|argument| argument == COMPARE_OPTION) {
+        vec![NetworkCore::Threads, NetworkCore::EventLoop]
+    } else {
+        vec![network]
+    };
+
+    let options = LoadTestOptions {
+        network,
+        clients: get_option_number(&arguments, CLIENTS_OPTION, 50) as usize,
+        requests: get_option_number(&arguments, REQUESTS_OPTION, 20) as usize,
+        interval: Duration::from_millis(get_option_number(&arguments, INTERVAL_OPTION, 100)),
+        timeout: Duration::from_secs(5),
+    };
+
+    for network in networks {
+
+        println!(
+            "Connecting {} clients to {} through the {} network, {} requests each...",
+            options.clients,
+            address,
+            network,
+            options.requests,
+        );
+
+        let report = run_load_test(
+            address,
+            LoadTestOptions {
+                network,
+                ..options
+            },
+        );
+
+        print!("{}", report);
+    }
+}
+
+/// Returns the value following the given option into the command line arguments, if any.
+///
+/// # Args:
+///
+/// `arguments` - the command line arguments
+/// `option` - the option name
+fn get_option_value<'a>(
+    arguments: &'a [String],
+    option: &str,
+) -> Option<&'a str> {
+    arguments
+        .iter()
+        .position(|argument| argument == option)
+        .and_then(|index| arguments.get(index + 1))
+        .map(|value| value.as_str())
+}
+
+/// Returns the number following the given option into the command line arguments, or the default value.
+///
+/// # Args:
+///
+/// `arguments` - the command line arguments
+/// `option` - the option name
+/// `default` - the value when the option is not given
+fn get_option_number(
+    arguments: &[String],
+    option: &str,
+    default: u64,
+) -> u64 {
+    get_option_value(arguments, option)
+        .map(|value| value.parse().unwrap_or_else(|_| panic!("The {} option must be a number", option)))
+        .unwrap_or(default)
+}
@@ -1,59 +1,222 @@
 extern crate piston_window;
 extern crate gfx_device_gl;
-extern crate serde_derive;
 
-mod gui;
 mod sprite;
-mod character;
-mod threads;
-mod screen;
-mod message;
-
-mod username_prompt_screen;
-mod waiting_for_players_screen;
-mod game_screen;
-
-use threads::{
-    receive_message_from_stream,
-    send_message_to_stream,
+mod piston_renderer;
+
+use client_core::client::Client;
+use client_core::renderer::RecordingRenderer;
+use client_core::recording::{
+    Recorder,
+    Recording,
+};
+
+use client_core::bot::{
+    BotDifficulty,
+    start_bots,
 };
-use screen::Screen;
-use message::Message;
 
-use username_prompt_screen::UsernamePromptScreen;
-use waiting_for_players_screen::WaitingForPlayersScreen;
-use game_screen::GameScreen;
+use client_core::discovery::DISCOVERY_PORT;
+
+use piston_renderer::PistonRenderer;
+use sprite::Sprites;
 
 use piston_window::{
     PistonWindow,
     WindowSettings,
-    clear,
+    Window,
     TextureSettings,
     Glyphs,
+    Event,
+    Loop,
+    RenderArgs,
+    Input,
+    Button,
+    ButtonArgs,
+    ButtonState,
+    Key,
 };
 
-use piston_window::color::hex;
-
-use std::net::TcpStream;
-use std::thread::spawn;
+use std::env;
+use std::thread;
+use std::time::Duration;
+use std::path::Path;
 use std::sync::{
     Mutex,
     Arc,
 };
-use std::sync::mpsc::{
-    Sender,
-    Receiver,
-    channel,
+use std::net::{
+    SocketAddr,
+    IpAddr,
+    Ipv4Addr,
 };
-use std::io::BufReader;
+
+const WINDOW_WIDTH: f64 = 1920.0;
+const WINDOW_HEIGHT: f64 = 1080.0;
 
 fn main() {
 
+    let arguments: Vec<String> = env::args().collect();
+
+    /* with a server address, only the bots are run, without any client */
+    const SERVER_OPTION: &str = "--server";
+    if let Some(address) = get_option_value(&arguments, SERVER_OPTION) {
+        run_bots(&arguments, address.parse().expect("The server address must be an IP address and a port"));
+        return;
+    }
+
+    let client = create_client(&arguments);
+
+    /* the headless mode runs the whole client without any window, all the frames are recorded */
+    const HEADLESS_OPTION: &str = "--headless";
+    if arguments.iter().any(|argument| argument == HEADLESS_OPTION) {
+        run_headless(client, &arguments);
+        return;
+    }
+
+    run_windowed(client);
+}
+
+/// Creates the client, recording or replaying the session according to the command line options:
+/// `--record <file>` records the whole session into the file,
+/// `--replay <file>` replays the recorded session instead of connecting to a server,
+/// at the speed factor given by `--replay-speed <factor>` (real speed by default)
+/// or message by message with `--replay-speed step`.
+/// `--bots <count>` connects bots to the chosen server alongside the client (see `get_bots_options`).
+/// `--connect <address>` connects to the given server at once, skipping the connect screen
+/// (it cannot be combined with `--replay`).
+///
+/// # Args:
+///
+/// `arguments` - the command line arguments
+fn create_client(arguments: &[String]) -> Client {
+
+    let mut client = Client::new(get_discovery_broadcast_address());
+
+    if let Some((count, difficulty)) = get_bots_options(arguments) {
+        client.set_bots(
+            count,
+            difficulty,
+        );
+    }
+
+    const RECORD_OPTION: &str = "--record";
+    if let Some(path) = get_option_value(arguments, RECORD_OPTION) {
+        let recorder = Recorder::create(Path::new(path)).expect("Cannot create the recording file");
+        client.set_recorder(Arc::new(Mutex::new(recorder)));
+    }
+
+    /* a replay feeds the recorded messages instead of a server, both cannot be used at once */
+    const CONNECT_OPTION: &str = "--connect";
+    const REPLAY_OPTION: &str = "--replay";
+    let connect_address = get_option_value(arguments, CONNECT_OPTION);
+    let replay_path = get_option_value(arguments, REPLAY_OPTION);
+    assert!(
+        connect_address.is_none() || replay_path.is_none(),
+        "The --connect and --replay options cannot be used together",
+    );
+
+    /* connects after setting the recorder, so that the whole session is recorded */
+    if let Some(address) = connect_address {
+        client.connect(address.parse().expect("The server address must be an IP address and a port"));
+    }
+
+    if let Some(path) = replay_path {
+
+        let recording = Recording::load(Path::new(path)).expect("Cannot load the recording file");
+
+        const REPLAY_SPEED_OPTION: &str = "--replay-speed";
+        const STEP_REPLAY_SPEED: &str = "step";
+        let speed = match get_option_value(arguments, REPLAY_SPEED_OPTION) {
+            Some(STEP_REPLAY_SPEED) => None,
+            Some(factor) => Some(factor.parse().expect("The replay speed must be a number or 'step'")),
+            None => Some(1.0),
+        };
+
+        client.replay(
+            &recording,
+            speed,
+        ).expect("Cannot start the replay");
+    }
+
+    client
+}
+
+/// Returns the bots options: the amount of bots given by `--bots <count>`, if any,
+/// and their difficulty given by `--bot-difficulty <easy|normal|hard>` (normal by default).
+///
+/// # Args:
+///
+/// `arguments` - the command line arguments
+fn get_bots_options(arguments: &[String]) -> Option<(usize, BotDifficulty)> {
+
+    const BOTS_OPTION: &str = "--bots";
+    let count = get_option_value(arguments, BOTS_OPTION)?
+        .parse()
+        .expect("The amount of bots must be a number");
+
+    const BOT_DIFFICULTY_OPTION: &str = "--bot-difficulty";
+    let difficulty = match get_option_value(arguments, BOT_DIFFICULTY_OPTION) {
+        Some(name) => BotDifficulty::from_name(name).expect("The bot difficulty must be 'easy', 'normal' or 'hard'"),
+        None => BotDifficulty::Normal,
+    };
+
+    Some((count, difficulty))
+}
+
+/// Runs the bots only, connected to the given server, until they are all disconnected;
+/// the first bot creates a room for all of them when there is none to join.
+/// One bot is run if `--bots` is not given.
+///
+/// # Args:
+///
+/// `arguments` - the command line arguments
+/// `address` - the address of the server
+fn run_bots(
+    arguments: &[String],
+    address: SocketAddr,
+) {
+
+    let (count, difficulty) = get_bots_options(arguments).unwrap_or((1, BotDifficulty::Normal));
+
+    let bots = start_bots(
+        address,
+        count,
+        difficulty,
+        true,
+    );
+
+    for bot in bots {
+        let _ = bot.join();
+    }
+}
+
+/// Returns the value following the given option into the command line arguments, if any.
+///
+/// # Args:
+///
+/// `arguments` - the command line arguments
+/// `option` - the option name
+fn get_option_value<'a>(
+    arguments: &'a [String],
+    option: &str,
+) -> Option<&'a str> {
+    arguments
+        .iter()
+        .position(|argument| argument == option)
+        .and_then(|index| arguments.get(index + 1))
+        .map(|value| value.as_str())
+}
 
-    /* load global resources used everywhere (window, fonts) */
+/// Runs the client into a fullscreen Piston window.
+///
+/// # Args:
+///
+/// `client` - the client to run
+fn run_windowed(mut client: Client) {
 
-    const WINDOW_WIDTH: f64 = 1920.0;
-    const WINDOW_HEIGHT: f64 = 1080.0;
+
+    /* load global resources used everywhere (window, fonts, sprites) */
 
     let mut window: PistonWindow = WindowSettings::new(
         "Treasure Island",
@@ -67,122 +230,176 @@ fn main() {
         .build()
         .unwrap();
 
-    const WAITING_FOR_PLAYERS_MESSAGE_FONT_FILE_PATH: &str = "res/fonts/pirates-writers.ttf";
+    const FONT_FILE_PATH: &str = "res/fonts/pirates-writers.ttf";
     let mut font = Glyphs::new(
-        WAITING_FOR_PLAYERS_MESSAGE_FONT_FILE_PATH,
+        FONT_FILE_PATH,
         window.create_texture_context(),
         TextureSettings::new(),
     ).unwrap();
 
-
-    /* load cross-threads resources with their pointers and mutexes
-       (current screen, map) */
-
-    const TILES_AMOUNT: usize = 400;
-    let tiles: [u8; TILES_AMOUNT] = [0; TILES_AMOUNT];
-    let tiles_mutex: Mutex<[u8; TILES_AMOUNT]> = Mutex::new(tiles);
-    let tiles_mutex_arc: Arc<Mutex<[u8; TILES_AMOUNT]>> = Arc::new(tiles_mutex);
-
-    let current_screen: Screen = Screen::UsernamePrompt;
-    let current_screen_mutex: Mutex<Screen> = Mutex::new(current_screen);
-    let current_screen_mutex_arc: Arc<Mutex<Screen>> = Arc::new(current_screen_mutex);
-
-    /* FIXME: #13 the address should be the domain name of the real server,
-       only work with a local server for now;
-       this part should be improved as the server has to be up
-       for the client to start which is only a temporary solution */
-    let read_stream = TcpStream::connect("127.0.0.1:9500").unwrap();
-    let write_stream = read_stream.try_clone().unwrap();
-
-    let read_buffer = BufReader::new(read_stream);
-
-    let tiles_mutex_arc_receive_message_thread = tiles_mutex_arc.clone();
-    let current_screen_mutex_arc_receive_message_thread = current_screen_mutex_arc.clone();
-    spawn(|| {
-        receive_message_from_stream(
-            read_buffer,
-            tiles_mutex_arc_receive_message_thread,
-            current_screen_mutex_arc_receive_message_thread,
-        );
-    });
-
-    let (
-        sender,
-        receiver,
-    ): (
-        Sender<Message>,
-        Receiver<Message>
-    ) = channel();
-
-    spawn(|| {
-        send_message_to_stream(
-            write_stream,
-            receiver,
-        )
-    });
-
-    /* load all screens and clone their thread-safe shared resources (if any) */
-
-    let mut username_prompt_screen = UsernamePromptScreen::new(sender);
-
-    let waiting_for_players_screen = WaitingForPlayersScreen::new();
-
-    let mut game_screen = GameScreen::new(
-        &mut window,
-        tiles_mutex_arc,
-    );
+    let sprites = Sprites::load(&mut window);
 
     while let Some(event) = window.next() {
 
-        let mut current_screen_mutex_guard = current_screen_mutex_arc.lock().unwrap();
-        let current_screen_guard = &mut *current_screen_mutex_guard;
-        let current_screen = *current_screen_guard;
+        client.handle_event(&event);
 
-        match current_screen {
-            Screen::UsernamePrompt => {
-                username_prompt_screen.handle_events(
-                    &event,
-                    current_screen_guard,
-                );
-            },
-            Screen::Game => {
-                game_screen.handle_events(&event);
-            },
-            _ => {}
-        };
+        if client.is_quit_requested() {
+            window.set_should_close(true);
+        }
 
         window.draw_2d(
             &event,
             |context, window, device| {
 
-                const BACKGROUND_COLOR: &str = "88FFFF"; /* light blue */
-                clear(hex(BACKGROUND_COLOR), window);
-
-                match current_screen {
-                    Screen::UsernamePrompt => {
-                        username_prompt_screen.render(
-                            context,
-                            window,
-                            device,
-                            &mut font,
-                        );
-                    },
-                    Screen::WaitingForPlayers => {
-                        waiting_for_players_screen.render(
-                            context,
-                            window,
-                            device,
-                            &mut font,
-                        );
-                    },
-                    Screen::Game => {
-                        game_screen.render(
-                            context,
-                            window,
-                        );
-                    }
-                };
+                let mut renderer = PistonRenderer::new(
+                    context,
+                    window,
+                    &mut font,
+                    &sprites,
+                );
+                client.render(&mut renderer);
+
+                /* required to display the characters */
+                font.factory.encoder.flush(device);
             }
         );
     }
 }
+
+/// Runs the client without any window: simulates the render events of a window at about 60 frames per second
+/// and draws every frame through a recording renderer. The texts of the frame are printed every time they change.
+/// The keys given by `--keys <key,key...>` are pressed one after the other (see `get_key_from_name`),
+/// so a whole session can be scripted along with `--connect`.
+/// Runs until the player asks to quit, until the game is over,
+/// or until the amount of frames given by `--frames <count>` is reached, if any.
+///
+/// # Args:
+///
+/// `client` - the client to run
+/// `arguments` - the command line arguments
+fn run_headless(
+    mut client: Client,
+    arguments: &[String],
+) {
+
+    let mut renderer = RecordingRenderer::new([
+        WINDOW_WIDTH,
+        WINDOW_HEIGHT,
+    ]);
+
+    const KEYS_OPTION: &str = "--keys";
+    let mut keys: Vec<Key> = match get_option_value(arguments, KEYS_OPTION) {
+        Some(names) => names
+            .split(',')
+            .map(|name| get_key_from_name(name).expect("Unknown key name"))
+            .collect(),
+        None => Vec::new(),
+    };
+    keys.reverse();
+
+    const FRAMES_OPTION: &str = "--frames";
+    let max_frames: Option<u64> = get_option_value(arguments, FRAMES_OPTION)
+        .map(|count| count.parse().expect("The amount of frames must be a number"));
+
+    const FRAME_DURATION: Duration = Duration::from_millis(16);
+
+    /* leaves the time to the server to answer between the scripted keys */
+    const KEY_PRESS_INTERVAL: u64 = 30;
+
+    let mut previous_texts: Vec<String> = Vec::new();
+    let mut frame: u64 = 0;
+
+    while !client.is_quit_requested() && !client.is_game_over() {
+
+        if max_frames.is_some_and(|max_frames| frame >= max_frames) {
+            break;
+        }
+
+        if frame % KEY_PRESS_INTERVAL == KEY_PRESS_INTERVAL - 1 {
+            if let Some(key) = keys.pop() {
+                client.handle_event(&Event::Input(
+                    Input::Button(ButtonArgs {
+                        state: ButtonState::Press,
+                        button: Button::Keyboard(key),
+                        scancode: None,
+                    }),
+                    None,
+                ));
+            }
+        }
+
+        let event = Event::Loop(Loop::Render(RenderArgs {
+            ext_dt: FRAME_DURATION.as_secs_f64(),
+            window_size: [
+                WINDOW_WIDTH,
+                WINDOW_HEIGHT,
+            ],
+            draw_size: [
+                WINDOW_WIDTH as u32,
+                WINDOW_HEIGHT as u32,
+            ],
+        }));
+
+        client.handle_event(&event);
+        client.render(&mut renderer);
+
+        let texts: Vec<String> = renderer
+            .get_texts()
+            .iter()
+            .map(|text| text.to_string())
+            .collect();
+
+        if texts != previous_texts {
+            println!(
+                "{} draw commands: {}",
+                renderer.get_commands().len(),
+                texts.join(" | "),
+            );
+            previous_texts = texts;
+        }
+
+        frame += 1;
+        thread::sleep(FRAME_DURATION);
+    }
+}
+
+/// Returns the key matching the given name: a letter or a digit,
+/// or one of `Return`, `Space`, `Tab`, `Backspace`, `Escape`, `Up`, `Down`, `Left` and `Right`.
+///
+/// # Args:
+///
+/// `name` - the key name
+fn get_key_from_name(name: &str) -> Option<Key> {
+
+    match name {
+        "Return" => return Some(Key::Return),
+        "Space" => return Some(Key::Space),
+        "Tab" => return Some(Key::Tab),
+        "Backspace" => return Some(Key::Backspace),
+        "Escape" => return Some(Key::Escape),
+        "Up" => return Some(Key::Up),
+        "Down" => return Some(Key::Down),
+        "Left" => return Some(Key::Left),
+        "Right" => return Some(Key::Right),
+        _ => {},
+    };
+
+    let mut characters = name.chars();
+
+    match (characters.next(), characters.next()) {
+
+        /* the keys codes of the letters and digits are their lowercase ASCII codes */
+        (Some(character), None) if character.is_ascii_alphanumeric() => {
+            Some(Key::from(character.to_ascii_lowercase() as u32))
+        },
+        _ => None,
+    }
+}
+
+/// Returns the broadcast address of the discovery port, the servers of the local network answer the probes sent to it.
+fn get_discovery_broadcast_address() -> SocketAddr {
+    SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::BROADCAST),
+        DISCOVERY_PORT,
+    )
+}
@@ -0,0 +1,197 @@
+//! Renderer drawing into the Piston window, used by the game.
+
+use client_core::renderer::{
+    Renderer,
+    Transform,
+};
+use crate::sprite::Sprites;
+
+use piston_window::text::Text;
+use piston_window::{
+    Context,
+    G2d,
+    Glyphs,
+    CharacterCache,
+    Image,
+    Rectangle,
+    Ellipse,
+    Polygon,
+};
+
+pub struct PistonRenderer<'a, 'b> {
+    context: Context,
+    graphics: &'a mut G2d<'b>,
+    font: &'a mut Glyphs,
+    sprites: &'a Sprites,
+}
+
+impl<'a, 'b> PistonRenderer<'a, 'b> {
+
+    /// Constructor. Only lives during the drawing of one frame.
+    ///
+    /// # Args:
+    ///
+    /// `context` - the Piston context of the frame
+    /// `graphics` - the Piston graphics backend of the frame
+    /// `font` - the font to use to render characters; its encoder is flushed by the caller once the frame is drawn
+    /// `sprites` - the loaded sprites
+    pub fn new(
+        context: Context,
+        graphics: &'a mut G2d<'b>,
+        font: &'a mut Glyphs,
+        sprites: &'a Sprites,
+    ) -> PistonRenderer<'a, 'b> {
+        PistonRenderer {
+            context,
+            graphics,
+            font,
+            sprites,
+        }
+    }
+}
+
+impl<'a, 'b> Renderer for PistonRenderer<'a, 'b> {
+
+    fn get_view_size(&self) -> [f64; 2] {
+        self.context.get_view_size()
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.context.transform
+    }
+
+    fn clear(
+        &mut self,
+        color: [f32; 4],
+    ) {
+        piston_window::clear(color, self.graphics);
+    }
+
+    fn draw_image(
+        &mut self,
+        sprite: &str,
+        color: [f32; 4],
+        source: Option<[f64; 4]>,
+        transform: Transform,
+    ) {
+
+        let mut image = Image::new_color(color);
+        if let Some(source) = source {
+            image = image
+                .src_rect(source)
+                .rect(source);
+        }
+
+        image.draw(
+            self.sprites.get(sprite),
+            &self.context.draw_state,
+            transform,
+            self.graphics,
+        );
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        Rectangle::new(color).draw(
+            rectangle,
+            &self.context.draw_state,
+            transform,
+            self.graphics,
+        );
+    }
+
+    fn draw_rectangle_border(
+        &mut self,
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        Rectangle::new_border(
+            color,
+            radius,
+        ).draw(
+            rectangle,
+            &self.context.draw_state,
+            transform,
+            self.graphics,
+        );
+    }
+
+    fn draw_ellipse(
+        &mut self,
+        color: [f32; 4],
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        Ellipse::new(color).draw(
+            rectangle,
+            &self.context.draw_state,
+            transform,
+            self.graphics,
+        );
+    }
+
+    fn draw_ellipse_border(
+        &mut self,
+        color: [f32; 4],
+        radius: f64,
+        rectangle: [f64; 4],
+        transform: Transform,
+    ) {
+        Ellipse::new_border(
+            color,
+            radius,
+        ).draw(
+            rectangle,
+            &self.context.draw_state,
+            transform,
+            self.graphics,
+        );
+    }
+
+    fn draw_polygon(
+        &mut self,
+        color: [f32; 4],
+        points: &[[f64; 2]],
+        transform: Transform,
+    ) {
+        Polygon::new(color).draw(
+            points,
+            &self.context.draw_state,
+            transform,
+            self.graphics,
+        );
+    }
+
+    fn draw_text(
+        &mut self,
+        color: [f32; 4],
+        font_size: u32,
+        text: &str,
+        transform: Transform,
+    ) {
+        Text::new_color(
+            color,
+            font_size,
+        ).draw(
+            text,
+            self.font,
+            &self.context.draw_state,
+            transform,
+            self.graphics,
+        ).unwrap();
+    }
+
+    fn get_text_width(
+        &mut self,
+        font_size: u32,
+        text: &str,
+    ) -> f64 {
+        self.font.width(font_size, text).unwrap()
+    }
+}
@@ -9,18 +9,76 @@ use piston_window::{
     TextureSettings,
 };
 
+use std::collections::HashMap;
+use std::fs::read_dir;
+
+pub const IMAGES_FOLDER: &str = "res/images/";
+
+/// Every sprite of the game, identified by its image file name.
+pub struct Sprites {
+    textures: HashMap<String, G2dTexture>,
+}
+
+impl Sprites {
+
+    /// Loads every image of the images resources folder.
+    ///
+    /// # Args:
+    ///
+    /// `window` - the window where the textures will be displayed
+    pub fn load(window: &mut PistonWindow) -> Sprites {
+
+        let mut textures = HashMap::new();
+
+        for entry in read_dir(IMAGES_FOLDER).unwrap() {
+
+            let file_name = entry.unwrap()
+                .file_name()
+                .into_string()
+                .unwrap();
+
+            if !file_name.ends_with(".png") {
+                continue;
+            }
+
+            let texture = load_sprite_from_file(
+                window,
+                &file_name,
+            );
+            textures.insert(file_name, texture);
+        }
+
+        Sprites {
+            textures,
+        }
+    }
+
+    /// Returns the texture of the given sprite.
+    ///
+    /// # Args:
+    ///
+    /// `file_name` - the image file name of the sprite
+    pub fn get(
+        &self,
+        file_name: &str,
+    ) -> &G2dTexture {
+        self.textures
+            .get(file_name)
+            .unwrap_or_else(|| panic!("Unknown sprite {}", file_name))
+    }
+}
+
 /// Refactored code to load a texture from a given image file name. Looks for files into the images resources folder.
 ///
 /// # Args:
 ///
 /// `window` - the window where the textures will be displayed
 /// `image` - the file of the image to load
-pub fn load_sprite_from_file(
+fn load_sprite_from_file(
     window: &mut PistonWindow,
     file_name: &str,
 ) -> G2dTexture {
 
-    const IMAGES_FOLDER: &str = "res/images/";
     let file_path = format!(
         "{}/{}",
         IMAGES_FOLDER,